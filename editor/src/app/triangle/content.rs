@@ -206,7 +206,7 @@ impl TriangleState {
                 self.workspace.polygons = shapes.triangulate().into_delaunay().to_convex_polygons();
             }
             ModeOption::Tessellation => {
-                // let max_edge = self.radius as u32;
+                let max_edge = self.radius as u64;
                 let max_area = self.max_area as u64;
 
                 self.workspace.triangulations = shapes
@@ -214,14 +214,16 @@ impl TriangleState {
                     .map(|s| shapes.triangulate()
                         .into_delaunay()
                         .refine_with_circumcenters(max_area)
+                        .refine_by_max_edge_length(max_edge)
                         .into_triangulation())
                     .collect();
             }
             ModeOption::CentroidNet => {
-                // let max_edge = self.radius as u32;
+                let max_edge = self.radius as u64;
                 let max_area = self.max_area as u64;
                 self.workspace.polygons = shapes.triangulate().into_delaunay()
                     .refine_with_circumcenters(max_area)
+                    .refine_by_max_edge_length(max_edge)
                     .centroid_net(0);
             }
         }
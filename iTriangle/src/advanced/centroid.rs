@@ -136,7 +136,7 @@ impl IntTriangle {
 }
 
 #[inline]
-fn middle(a: IntPoint, b: IntPoint) -> IntPoint {
+pub(crate) fn middle(a: IntPoint, b: IntPoint) -> IntPoint {
     let x = a.x as i64 + b.x as i64;
     let y = a.y as i64 + b.y as i64;
     IntPoint::new((x / 2) as i32, (y / 2) as i32)
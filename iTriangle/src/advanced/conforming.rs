@@ -0,0 +1,274 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::index::Index;
+use crate::int::triangulation::RawIntTriangulation;
+use crate::tessellation::quality::{abc_by_edge, grid_point_on_segment, is_encroached_by};
+use i_overlay::i_float::int::point::IntPoint;
+use std::collections::{HashMap, HashSet};
+
+impl RawIntTriangulation {
+    /// Converts an int triangle mesh into a conforming Delaunay triangulation.
+    ///
+    /// [`RawIntTriangulation::into_delaunay`] keeps contour and constrained edges, so triangles
+    /// next to them may still have other points inside their circumcircle.
+    /// Here such edges are split by their middle until no point lies inside the circle
+    /// with the edge as a diameter, then every triangle is Delaunay against all points of the mesh.
+    /// As in Ruppert's refinement, a constrained edge is tested against the third vertices of the
+    /// triangles next to it, a boundary edge also against the points across it, found by a grid.
+    ///
+    /// Split points lie exactly on the edges, so the mesh covers exactly the same area.
+    /// An edge without an int point strictly inside it can not be split and stays encroached,
+    /// [`IntDelaunay::encroached_edges`] returns such edges. Small angles between edges may need
+    /// many splits until that happens.
+    pub fn into_conforming_delaunay(self) -> IntDelaunay {
+        let mut delaunay = self.into_delaunay();
+        delaunay.conform();
+        delaunay
+    }
+}
+
+impl IntDelaunay {
+    /// The boundary and constrained edges encroached by a point, tested the same way as
+    /// [`RawIntTriangulation::into_conforming_delaunay`] does, as sorted point index pairs without duplicates.
+    pub fn encroached_edges(&self) -> Vec<[usize; 2]> {
+        let grid = PointGrid::new(&self.points);
+        let mut edges = Vec::new();
+        for abc_index in 0..self.triangles.len() {
+            for k in 0..3 {
+                if self.is_encroached_segment(&grid, abc_index, k) {
+                    let t = &self.triangles[abc_index];
+                    let a = t.vertices[(k + 1) % 3].index;
+                    let b = t.vertices[(k + 2) % 3].index;
+                    edges.push(if a < b { [a, b] } else { [b, a] });
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    fn conform(&mut self) {
+        let mut unchecked = HashSet::with_capacity(16);
+        let mut buffer = Vec::with_capacity(16);
+        let mut grid = PointGrid::new(&self.points);
+
+        // every split takes one of the finitely many grid points of the edges, so this ends
+        loop {
+            let mut is_changed = false;
+
+            let mut abc_index = 0;
+            while abc_index < self.triangles.len() {
+                for k in 0..3 {
+                    if !self.is_encroached_segment(&grid, abc_index, k) {
+                        continue;
+                    }
+                    let t = &self.triangles[abc_index];
+                    let a = t.vertices[(k + 1) % 3].point;
+                    let b = t.vertices[(k + 2) % 3].point;
+                    if let Some(m) = grid_point_on_segment(a, b, 0.5) {
+                        let abc = abc_by_edge(t, k);
+                        self.split_edge(abc_index, abc, m, &mut buffer);
+                        self.fix_triangles(&mut buffer, &mut unchecked);
+                        grid.insert(m);
+                        is_changed = true;
+                    }
+                }
+                abc_index += 1;
+            }
+
+            if !is_changed {
+                break;
+            }
+        }
+    }
+
+    /// True if the edge `k` of the triangle is a segment encroached by the opposite vertex,
+    /// or a boundary edge encroached by any point. An inner segment is tested from both sides.
+    #[inline]
+    fn is_encroached_segment(&self, grid: &PointGrid, abc_index: usize, k: usize) -> bool {
+        if !self.is_segment(abc_index, k) {
+            return false;
+        }
+        let t = &self.triangles[abc_index];
+        let a = t.vertices[(k + 1) % 3].point;
+        let b = t.vertices[(k + 2) % 3].point;
+        is_encroached_by(t.vertices[k].point, a, b) || (!t.neighbors[k].is_not_nil() && grid.is_encroached(a, b))
+    }
+}
+
+/// Points bucketed by a uniform grid to find the ones near an edge, new points are added in place.
+struct PointGrid {
+    cell: i64,
+    cells: HashMap<(i64, i64), Vec<IntPoint>>,
+}
+
+impl PointGrid {
+    fn new(points: &[IntPoint]) -> Self {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (i64::MAX, i64::MAX, i64::MIN, i64::MIN);
+        for p in points.iter() {
+            min_x = min_x.min(p.x as i64);
+            min_y = min_y.min(p.y as i64);
+            max_x = max_x.max(p.x as i64);
+            max_y = max_y.max(p.y as i64);
+        }
+
+        // about one point per cell
+        let cell = if points.is_empty() {
+            1
+        } else {
+            let area = (max_x - min_x + 1) as f64 * (max_y - min_y + 1) as f64;
+            ((area / points.len() as f64).sqrt().ceil() as i64).max(1)
+        };
+
+        let mut grid = Self {
+            cell,
+            cells: HashMap::with_capacity(points.len()),
+        };
+        for &p in points.iter() {
+            grid.insert(p);
+        }
+        grid
+    }
+
+    #[inline]
+    fn key(&self, x: i64, y: i64) -> (i64, i64) {
+        (x.div_euclid(self.cell), y.div_euclid(self.cell))
+    }
+
+    #[inline]
+    fn insert(&mut self, p: IntPoint) {
+        let key = self.key(p.x as i64, p.y as i64);
+        self.cells.entry(key).or_default().push(p);
+    }
+
+    /// True if any point lies strictly inside the circle with the diameter `ab`.
+    fn is_encroached(&self, a: IntPoint, b: IntPoint) -> bool {
+        let radius = 0.5 * (a.sqr_distance(b) as f64).sqrt();
+        let cx = 0.5 * (a.x as f64 + b.x as f64);
+        let cy = 0.5 * (a.y as f64 + b.y as f64);
+        let (c0, r0) = self.key((cx - radius).floor() as i64, (cy - radius).floor() as i64);
+        let (c1, r1) = self.key((cx + radius).ceil() as i64, (cy + radius).ceil() as i64);
+
+        // a circle wider than the point set is cheaper to test point by point
+        if ((c1 - c0 + 1) * (r1 - r0 + 1)) as usize > self.cells.len() {
+            return self.cells.values().flatten().any(|&p| is_encroached_by(p, a, b));
+        }
+
+        for c in c0..=c1 {
+            for r in r0..=r1 {
+                if let Some(points) = self.cells.get(&(c, r)) {
+                    if points.iter().any(|&p| is_encroached_by(p, a, b)) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    // triangles are wound counter-clockwise in the math sense
+    fn is_inside_circle(p: IntPoint, a: IntPoint, b: IntPoint, c: IntPoint) -> bool {
+        let row = |q: IntPoint| {
+            let x = q.x as i128 - p.x as i128;
+            let y = q.y as i128 - p.y as i128;
+            [x, y, x * x + y * y]
+        };
+        let [a, b, c] = [row(a), row(b), row(c)];
+        let det = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0]);
+        det > 0
+    }
+
+    fn is_delaunay(delaunay: &IntDelaunay) -> bool {
+        delaunay.triangles.iter().all(|t| {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            delaunay.points.iter().all(|&p| !is_inside_circle(p, a, b, c))
+        })
+    }
+
+    #[test]
+    fn test_0() {
+        // a flat triangle and a square below, a square corner is inside the triangle circumcircle
+        let shapes = vec![
+            vec![path(&[[0, 0], [4096, 0], [2048, 512]])],
+            vec![path(&[[1024, -2048], [3072, -2048], [3072, -256], [1024, -256]])],
+        ];
+        assert!(!is_delaunay(&shapes.triangulate().into_delaunay()));
+
+        let conforming = shapes.triangulate().into_conforming_delaunay();
+        conforming.validate();
+        assert!(is_delaunay(&conforming));
+        assert_eq!(conforming.area(), shapes.triangulate().into_delaunay().area());
+    }
+
+    #[test]
+    fn test_1() {
+        // an already conforming mesh is not changed
+        let contour = path(&[[0, 0], [100, 0], [100, 100], [0, 100]]);
+        let conforming = contour.triangulate().into_conforming_delaunay();
+        assert_eq!(conforming.points.len(), 4);
+        assert!(is_delaunay(&conforming));
+    }
+
+    #[test]
+    fn test_2() {
+        // a short segment over the middle of a long one is inside the circumcircles of the triangles below
+        let contour = path(&[[0, 0], [4096, 0], [4096, 2048], [0, 2048]]);
+        let segments = [
+            [IntPoint::new(0, 1024), IntPoint::new(4096, 1024)],
+            [IntPoint::new(2040, 1100), IntPoint::new(2056, 1100)],
+        ];
+        assert!(!is_delaunay(
+            &contour.triangulate_with_constraints(&segments).into_delaunay()
+        ));
+
+        let conforming = contour
+            .triangulate_with_constraints(&segments)
+            .into_conforming_delaunay();
+        conforming.validate();
+        assert!(is_delaunay(&conforming));
+        assert!(conforming.encroached_edges().is_empty());
+        assert_eq!(conforming.area(), contour.area_two());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            // random rectangular holes in a square, one per grid cell at most
+            let mut shape = vec![path(&[[0, 0], [16384, 0], [16384, 16384], [0, 16384]])];
+            for i in 0..4 {
+                for j in 0..4 {
+                    if rng.random_bool(0.5) {
+                        continue;
+                    }
+                    let x0 = 4096 * i + 256 * rng.random_range(1..8);
+                    let y0 = 4096 * j + 256 * rng.random_range(1..8);
+                    let x1 = 4096 * i + 256 * rng.random_range(9..16);
+                    let y1 = 4096 * j + 256 * rng.random_range(9..16);
+                    shape.push(path(&[[x0, y0], [x0, y1], [x1, y1], [x1, y0]]));
+                }
+            }
+
+            let conforming = shape.triangulate().into_conforming_delaunay();
+            conforming.validate();
+            assert!(is_delaunay(&conforming));
+            assert!(conforming.encroached_edges().is_empty());
+            assert_eq!(conforming.area(), shape.area_two());
+        }
+    }
+}
@@ -1,10 +1,40 @@
 use crate::advanced::delaunay::IntDelaunay;
 use crate::geom::triangle::IntTriangle;
-use crate::index::Index;
+use crate::index::{Index, NIL_INDEX};
+use crate::int::triangulation::RawIntTriangulation;
 use i_overlay::i_float::int::point::IntPoint;
 use i_overlay::i_shape::int::shape::IntContour;
 use i_overlay::i_shape::int::simple::Simplify;
 
+/// How [`IntDelaunay::to_convex_polygons_with_options`] groups triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConvexMode {
+    /// Grows every polygon from a triangle while it stays convex, like [`IntDelaunay::to_convex_polygons`].
+    #[default]
+    Greedy,
+    /// Starts from the triangles and removes every diagonal that keeps both polygons around it convex,
+    /// the longest ones first. Gives at most four times the minimum number of polygons.
+    HertelMehlhorn,
+    /// The minimum number of polygons cut by diagonals between contour points.
+    ///
+    /// Solved exactly for a part without holes and at most [`EXACT_MAX_VERTICES`] contour corners,
+    /// other parts fall back to [`ConvexMode::HertelMehlhorn`].
+    /// Points in the middle of straight contour sides and inner points are not used as vertices.
+    Exact,
+}
+
+/// The biggest contour handled by [`ConvexMode::Exact`], the time grows as the fifth power of it.
+pub const EXACT_MAX_VERTICES: usize = 24;
+
+/// Controls [`IntDelaunay::to_convex_polygons_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConvexOptions {
+    /// The maximum number of vertices of a polygon, e.g. 8 for physics engines.
+    /// Values below 3 are treated as 3. `None` means no limit.
+    pub max_vertices: Option<usize>,
+    pub mode: ConvexMode,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct Node {
     next: usize,
@@ -24,13 +54,18 @@ struct Edge {
 struct ConvexPolygonBuilder {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
+    max_vertices: usize,
+    // the number of not collinear corners
+    corners: usize,
 }
 
 impl ConvexPolygonBuilder {
-    fn new() -> Self {
+    fn new(max_vertices: usize) -> Self {
         Self {
             nodes: Vec::with_capacity(16),
             edges: Vec::with_capacity(16),
+            max_vertices,
+            corners: 0,
         }
     }
 
@@ -52,6 +87,7 @@ impl ConvexPolygonBuilder {
     fn start(&mut self, triangle_index: usize, triangle: &IntTriangle) {
         self.nodes.clear();
         self.edges.clear();
+        self.corners = 3;
 
         let bc = triangle.neighbors[0];
         let ca = triangle.neighbors[1];
@@ -140,6 +176,18 @@ impl ConvexPolygonBuilder {
             return false;
         }
 
+        if self.max_vertices < usize::MAX {
+            // corners at a1 and b1 may become collinear, p is always a corner
+            let ab = vb1.subtract(va1);
+            let was_a = (aa.cross_product(ab) != 0) as usize;
+            let was_b = (ab.cross_product(bb) != 0) as usize;
+            let corners = self.corners + 1 + (apa != 0) as usize + (bpb != 0) as usize - was_a - was_b;
+            if corners > self.max_vertices {
+                return false;
+            }
+            self.corners = corners;
+        }
+
         let prev_neighbor = triangle.neighbors[(v_index + 2) % 3];
         let next_neighbor = triangle.neighbors[(v_index + 1) % 3];
 
@@ -183,6 +231,14 @@ impl ConvexPolygonBuilder {
     }
 }
 
+/// Convex polygons of a mesh together with their adjacency graph.
+#[derive(Debug, Clone, Default)]
+pub struct ConvexDecomposition<C = IntContour> {
+    pub polygons: Vec<C>,
+    /// `adjacency[i]` lists the polygons sharing a side (or a part of it) with `polygons[i]`, in increasing order.
+    pub adjacency: Vec<Vec<usize>>,
+}
+
 impl IntDelaunay {
     /// Groups adjacent triangles into convex polygons in counter-clockwise order.
     ///
@@ -207,51 +263,219 @@ impl IntDelaunay {
     /// let polygons = triangulation.to_convex_polygons();
     /// assert!(!polygons.is_empty());
     /// ```
+    #[inline]
     pub fn to_convex_polygons(&self) -> Vec<IntContour> {
-        let mut result = Vec::new();
+        self.to_convex_decomposition(&ConvexOptions::default()).polygons
+    }
+
+    /// Groups the mesh into convex polygons as [`ConvexOptions`] ask.
+    ///
+    /// [`ConvexMode::Greedy`] and [`ConvexMode::HertelMehlhorn`] polygons are unions of mesh triangles,
+    /// [`ConvexMode::Exact`] polygons are cut by diagonals between contour points.
+    /// Vertices are ordered like in [`IntDelaunay::to_convex_polygons`].
+    #[inline]
+    pub fn to_convex_polygons_with_options(&self, options: &ConvexOptions) -> Vec<IntContour> {
+        self.to_convex_decomposition(options).polygons
+    }
+
+    /// Groups the mesh into convex polygons like [`IntDelaunay::to_convex_polygons_with_options`]
+    /// and also returns which polygons are adjacent.
+    #[inline]
+    pub fn to_convex_decomposition(&self, options: &ConvexOptions) -> ConvexDecomposition {
+        ConvexMesh::new(&self.triangles, &self.points).decompose(options)
+    }
+}
+
+impl RawIntTriangulation {
+    /// Groups adjacent triangles into convex polygons without building the Delaunay mesh first,
+    /// see [`IntDelaunay::to_convex_polygons`].
+    #[inline]
+    pub fn to_convex_polygons(&self) -> Vec<IntContour> {
+        self.to_convex_decomposition(&ConvexOptions::default()).polygons
+    }
+
+    /// Groups the mesh into convex polygons as [`ConvexOptions`] ask,
+    /// see [`IntDelaunay::to_convex_polygons_with_options`].
+    #[inline]
+    pub fn to_convex_polygons_with_options(&self, options: &ConvexOptions) -> Vec<IntContour> {
+        self.to_convex_decomposition(options).polygons
+    }
+
+    /// Groups the mesh into convex polygons and also returns which polygons are adjacent,
+    /// see [`IntDelaunay::to_convex_decomposition`].
+    #[inline]
+    pub fn to_convex_decomposition(&self, options: &ConvexOptions) -> ConvexDecomposition {
+        ConvexMesh::new(&self.triangles, &self.points).decompose(options)
+    }
+}
+
+/// The triangles and points of a mesh to decompose.
+pub(crate) struct ConvexMesh<'a> {
+    pub(crate) triangles: &'a [IntTriangle],
+    pub(crate) points: &'a [IntPoint],
+}
+
+impl<'a> ConvexMesh<'a> {
+    #[inline]
+    fn new(triangles: &'a [IntTriangle], points: &'a [IntPoint]) -> Self {
+        Self { triangles, points }
+    }
+
+    fn decompose(&self, options: &ConvexOptions) -> ConvexDecomposition {
+        let max_vertices = options.max_vertices.map_or(usize::MAX, |m| m.max(3));
+        match options.mode {
+            ConvexMode::Greedy => self.greedy_convex_polygons(max_vertices),
+            ConvexMode::HertelMehlhorn => {
+                let triangles: Vec<usize> = (0..self.triangles.len()).collect();
+                self.hertel_mehlhorn_polygons(&triangles, max_vertices)
+            }
+            ConvexMode::Exact => self.exact_convex_polygons(max_vertices),
+        }
+    }
+
+    fn greedy_convex_polygons(&self, max_vertices: usize) -> ConvexDecomposition {
+        let mut polygons = Vec::new();
         let n = self.triangles.len();
 
-        let mut visited = vec![false; n];
+        let mut owners = vec![NIL_INDEX; n];
 
-        let mut builder = ConvexPolygonBuilder::new();
+        let mut builder = ConvexPolygonBuilder::new(max_vertices);
 
         for i in 0..n {
-            if visited[i] {
+            if owners[i].is_not_nil() {
                 continue;
             }
 
+            let owner = polygons.len();
             let first = &self.triangles[i];
             builder.start(i, first);
-            visited[i] = true;
+            owners[i] = owner;
 
             while let Some(edge) = builder.edges.pop() {
-                if visited[edge.neighbor] {
+                if owners[edge.neighbor].is_not_nil() {
                     continue;
                 }
                 let triangle = &self.triangles[edge.neighbor];
                 if builder.add(edge, triangle) {
-                    visited[edge.neighbor] = true;
+                    owners[edge.neighbor] = owner;
                 }
             }
 
-            result.push(builder.to_contour())
+            polygons.push(builder.to_contour())
+        }
+
+        let adjacency = self.adjacency(&owners, polygons.len());
+        ConvexDecomposition { polygons, adjacency }
+    }
+
+    /// Links the polygons whose triangles are neighbors, `owners` keeps the polygon of every triangle.
+    pub(crate) fn adjacency(&self, owners: &[usize], count: usize) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); count];
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            let owner = owners[t];
+            if owner == NIL_INDEX {
+                continue;
+            }
+            for &n in triangle.neighbors.iter() {
+                if n < self.triangles.len() && owners[n].is_not_nil() && owners[n] != owner {
+                    adjacency[owner].push(owners[n]);
+                }
+            }
         }
+        for list in adjacency.iter_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+        adjacency
+    }
+}
 
-        result
+impl<C> ConvexDecomposition<C> {
+    /// Appends the polygons of another part of the mesh.
+    pub(crate) fn append(&mut self, other: ConvexDecomposition<C>) {
+        let offset = self.polygons.len();
+        self.polygons.extend(other.polygons);
+        self.adjacency.extend(
+            other
+                .adjacency
+                .into_iter()
+                .map(|list| list.into_iter().map(|i| i + offset).collect()),
+        );
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::advanced::convex::{ConvexDecomposition, ConvexMode, ConvexOptions};
     use crate::int::triangulatable::IntTriangulatable;
     use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
     use i_overlay::i_shape::int::area::Area;
     use i_overlay::i_shape::int::path::IntPath;
+    use i_overlay::i_shape::int::shape::IntContour;
+    use rand::Rng;
 
     fn path(slice: &[[i32; 2]]) -> IntPath {
         slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
     }
 
+    fn regular(n: usize, radius: f64) -> IntPath {
+        (0..n)
+            .map(|i| {
+                let a = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+                IntPoint::new((radius * a.cos()) as i32, (radius * a.sin()) as i32)
+            })
+            .collect()
+    }
+
+    fn check_convex(polygons: &[IntContour], max_vertices: usize, area: i64) {
+        let mut sum = 0;
+        for polygon in polygons.iter() {
+            let n = polygon.len();
+            assert!((3..=max_vertices).contains(&n));
+            for i in 0..n {
+                let a = polygon[(i + n - 1) % n];
+                let b = polygon[i];
+                let c = polygon[(i + 1) % n];
+                assert!(Triangle::area_two_point(a, b, c) < 0);
+            }
+            sum += polygon.area_two();
+        }
+        assert_eq!(sum, area);
+    }
+
+    // two polygons are adjacent if their sides overlap by more than a point
+    fn check_adjacency(decomposition: &ConvexDecomposition) {
+        let polygons = &decomposition.polygons;
+        assert_eq!(decomposition.adjacency.len(), polygons.len());
+        for i in 0..polygons.len() {
+            for j in 0..polygons.len() {
+                let is_adjacent = i != j && is_sharing_side(&polygons[i], &polygons[j]);
+                assert_eq!(decomposition.adjacency[i].contains(&j), is_adjacent);
+            }
+        }
+    }
+
+    fn is_sharing_side(p: &IntContour, q: &IntContour) -> bool {
+        for i in 0..p.len() {
+            let (a, b) = (p[i], p[(i + 1) % p.len()]);
+            for j in 0..q.len() {
+                let (c, d) = (q[j], q[(j + 1) % q.len()]);
+                if Triangle::area_two_point(a, b, c) != 0 || Triangle::area_two_point(a, b, d) != 0 {
+                    continue;
+                }
+                // project on the side `ab`
+                let ab = b.subtract(a);
+                let t = |x: IntPoint| x.subtract(a).dot_product(ab);
+                let (c0, c1) = (t(c).min(t(d)), t(c).max(t(d)));
+                if c0.max(0) < c1.min(t(b)) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
     #[test]
     fn test_0() {
         let path = path(&[[0, 0], [-2, 0], [-2, -2], [2, -2], [2, 2], [0, 2]]);
@@ -297,4 +521,197 @@ mod tests {
         assert!(polygons[1].area_two() < 0);
         assert!(polygons[2].area_two() < 0);
     }
+
+    #[test]
+    fn test_3() {
+        let contour = regular(16, 1000.0);
+        let delaunay = contour.triangulate().into_delaunay();
+        for mode in [ConvexMode::Greedy, ConvexMode::HertelMehlhorn, ConvexMode::Exact] {
+            let options = ConvexOptions {
+                max_vertices: Some(4),
+                mode,
+            };
+            let polygons = delaunay.to_convex_polygons_with_options(&options);
+            check_convex(&polygons, 4, contour.area_two());
+        }
+
+        // a polygon of n corners needs at least (n - 2) / (m - 2) polygons of m corners
+        let options = ConvexOptions {
+            max_vertices: Some(4),
+            mode: ConvexMode::Exact,
+        };
+        assert_eq!(delaunay.to_convex_polygons_with_options(&options).len(), 7);
+    }
+
+    #[test]
+    fn test_4() {
+        // a cross needs three polygons
+        let contour = path(&[
+            [-3, 1],
+            [-3, -1],
+            [-1, -1],
+            [-1, -3],
+            [1, -3],
+            [1, -1],
+            [3, -1],
+            [3, 1],
+            [1, 1],
+            [1, 3],
+            [-1, 3],
+            [-1, 1],
+        ]);
+        let delaunay = contour.triangulate().into_delaunay();
+        for mode in [ConvexMode::HertelMehlhorn, ConvexMode::Exact] {
+            let options = ConvexOptions {
+                max_vertices: None,
+                mode,
+            };
+            let polygons = delaunay.to_convex_polygons_with_options(&options);
+            check_convex(&polygons, usize::MAX, contour.area_two());
+            assert_eq!(polygons.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_5() {
+        // a comb, the exact mode cuts it into its teeth and the spine
+        let contour = path(&[
+            [0, 0],
+            [70, 0],
+            [70, 30],
+            [60, 30],
+            [60, 10],
+            [50, 10],
+            [50, 30],
+            [40, 30],
+            [40, 10],
+            [30, 10],
+            [30, 30],
+            [20, 30],
+            [20, 10],
+            [10, 10],
+            [10, 30],
+            [0, 30],
+        ]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let options = ConvexOptions {
+            max_vertices: None,
+            mode: ConvexMode::Exact,
+        };
+        let polygons = delaunay.to_convex_polygons_with_options(&options);
+        check_convex(&polygons, usize::MAX, contour.area_two());
+        assert_eq!(polygons.len(), 5);
+
+        // a hole falls back to Hertel-Mehlhorn
+        let shape = vec![
+            path(&[[0, 0], [10, 0], [10, 10], [0, 10]]),
+            path(&[[4, 4], [4, 6], [6, 6], [6, 4]]),
+        ];
+        let polygons = shape.triangulate().into_delaunay().to_convex_polygons_with_options(&options);
+        check_convex(&polygons, usize::MAX, shape.area_two());
+    }
+
+    #[test]
+    fn test_6() {
+        // the raw triangulation is decomposed without the Delaunay pass
+        let contour = path(&[
+            [-3, 1],
+            [-3, -1],
+            [-1, -1],
+            [-1, -3],
+            [1, -3],
+            [1, -1],
+            [3, -1],
+            [3, 1],
+            [1, 1],
+            [1, 3],
+            [-1, 3],
+            [-1, 1],
+        ]);
+        let raw = contour.triangulate();
+        assert_eq!(raw.to_convex_polygons().len(), 3);
+        for mode in [ConvexMode::Greedy, ConvexMode::HertelMehlhorn, ConvexMode::Exact] {
+            let options = ConvexOptions {
+                max_vertices: None,
+                mode,
+            };
+            let decomposition = raw.to_convex_decomposition(&options);
+            check_convex(&decomposition.polygons, usize::MAX, contour.area_two());
+            check_adjacency(&decomposition);
+        }
+
+        // the bar is next to both arms
+        let options = ConvexOptions {
+            max_vertices: None,
+            mode: ConvexMode::Exact,
+        };
+        let decomposition = raw.to_convex_decomposition(&options);
+        let mut degrees: Vec<usize> = decomposition.adjacency.iter().map(|list| list.len()).collect();
+        degrees.sort_unstable();
+        assert_eq!(degrees, vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            // a star shaped polygon
+            let n = rng.random_range(3..=16);
+            let mut angles: Vec<f64> = (0..n).map(|_| rng.random_range(0.0..std::f64::consts::TAU)).collect();
+            angles.sort_by(|a, b| a.total_cmp(b));
+            let contour: IntPath = angles
+                .iter()
+                .map(|&a| {
+                    let r = rng.random_range(100.0..1000.0);
+                    IntPoint::new((r * a.cos()) as i32, (r * a.sin()) as i32)
+                })
+                .collect();
+            let delaunay = contour.triangulate().into_delaunay();
+            let area = delaunay.area();
+
+            let max_vertices = rng.random_range(3..=8);
+            let mut counts = [0; 3];
+            for (i, mode) in [ConvexMode::Greedy, ConvexMode::HertelMehlhorn, ConvexMode::Exact].into_iter().enumerate() {
+                let options = ConvexOptions {
+                    max_vertices: Some(max_vertices),
+                    mode,
+                };
+                let polygons = delaunay.to_convex_polygons_with_options(&options);
+                check_convex(&polygons, max_vertices, area);
+                counts[i] = polygons.len();
+            }
+            assert!(counts[2] <= counts[1]);
+            assert!(counts[2] <= counts[0]);
+        }
+    }
+
+    #[test]
+    fn test_random_1() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(3..=16);
+            let mut angles: Vec<f64> = (0..n).map(|_| rng.random_range(0.0..std::f64::consts::TAU)).collect();
+            angles.sort_by(|a, b| a.total_cmp(b));
+            let contour: IntPath = angles
+                .iter()
+                .map(|&a| {
+                    let r = rng.random_range(100.0..1000.0);
+                    IntPoint::new((r * a.cos()) as i32, (r * a.sin()) as i32)
+                })
+                .collect();
+            let raw = contour.triangulate();
+            let area = raw.to_convex_polygons().iter().map(|p| p.area_two()).sum();
+
+            let max_vertices = rng.random_range(3..=8);
+            for mode in [ConvexMode::Greedy, ConvexMode::HertelMehlhorn, ConvexMode::Exact] {
+                let options = ConvexOptions {
+                    max_vertices: Some(max_vertices),
+                    mode,
+                };
+                let decomposition = raw.to_convex_decomposition(&options);
+                check_convex(&decomposition.polygons, max_vertices, area);
+                check_adjacency(&decomposition);
+            }
+        }
+    }
 }
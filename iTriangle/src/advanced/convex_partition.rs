@@ -0,0 +1,468 @@
+use crate::advanced::convex::{ConvexDecomposition, ConvexMesh, EXACT_MAX_VERTICES};
+use crate::index::NIL_INDEX;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use i_overlay::i_shape::int::shape::IntContour;
+use i_overlay::i_shape::int::simple::Simplify;
+use std::collections::HashMap;
+
+impl ConvexMesh<'_> {
+    /// Merges the given triangles by removing diagonals while both polygons around them stay convex.
+    pub(crate) fn hertel_mehlhorn_polygons(&self, triangles: &[usize], max_vertices: usize) -> ConvexDecomposition {
+        let mut is_used = vec![false; self.triangles.len()];
+        for &t in triangles {
+            is_used[t] = true;
+        }
+
+        // every triangle starts as its own polygon, a polygon is kept by its root triangle
+        let mut roots: Vec<usize> = (0..self.triangles.len()).collect();
+        let mut polygons: Vec<Vec<usize>> = self
+            .triangles
+            .iter()
+            .map(|t| t.vertices.iter().map(|v| v.index).collect())
+            .collect();
+
+        let mut diagonals = Vec::with_capacity(triangles.len() * 3 / 2);
+        for &t in triangles {
+            let triangle = &self.triangles[t];
+            for k in 0..3 {
+                let n = triangle.neighbors[k];
+                if n < self.triangles.len() && t < n && is_used[n] {
+                    let a = triangle.vertices[(k + 1) % 3].index;
+                    let b = triangle.vertices[(k + 2) % 3].index;
+                    let sqr_len = self.points[a].sqr_distance(self.points[b]);
+                    diagonals.push((sqr_len, t, n, a, b));
+                }
+            }
+        }
+        diagonals.sort_unstable_by(|x, y| y.0.cmp(&x.0).then(x.1.cmp(&y.1)).then(x.2.cmp(&y.2)));
+
+        for &(_, t, n, a, b) in diagonals.iter() {
+            let p = find_root(&mut roots, t);
+            let q = find_root(&mut roots, n);
+            if p == q {
+                continue;
+            }
+            if let Some(merged) = self.merge_polygons(&polygons[p], &polygons[q], a, b, max_vertices) {
+                polygons[p] = merged;
+                polygons[q].clear();
+                roots[q] = p;
+            }
+        }
+
+        let mut result = Vec::new();
+        let mut owners = vec![NIL_INDEX; self.triangles.len()];
+        for &t in triangles {
+            if find_root(&mut roots, t) == t {
+                owners[t] = result.len();
+                let mut contour: IntContour = polygons[t].iter().map(|&i| self.points[i]).collect();
+                contour.simplify_contour();
+                result.push(contour);
+            }
+        }
+        for &t in triangles {
+            owners[t] = owners[find_root(&mut roots, t)];
+        }
+
+        let adjacency = self.adjacency(&owners, result.len());
+        ConvexDecomposition {
+            polygons: result,
+            adjacency,
+        }
+    }
+
+    /// Joins `p` and `q` along their common edge `ab` if the result is convex.
+    fn merge_polygons(&self, p: &[usize], q: &[usize], a: usize, b: usize, max_vertices: usize) -> Option<Vec<usize>> {
+        // `p` goes x -> y, `q` goes y -> x
+        let pi = p.iter().position(|&i| i == a)?;
+        let (x, y) = if p[(pi + 1) % p.len()] == b { (a, b) } else { (b, a) };
+        let px = p.iter().position(|&i| i == x)?;
+        let qy = q.iter().position(|&i| i == y)?;
+        if q[(qy + 1) % q.len()] != x {
+            return None;
+        }
+
+        let mut merged = Vec::with_capacity(p.len() + q.len() - 2);
+        // p from y around to x, then q from after x around to before y
+        for i in 1..=p.len() {
+            merged.push(p[(px + i) % p.len()]);
+        }
+        for i in 2..q.len() {
+            merged.push(q[(qy + i) % q.len()]);
+        }
+
+        let n = merged.len();
+        let mut corners = 0;
+        for i in 0..n {
+            let o = self.points[merged[(i + n - 1) % n]];
+            let c = self.points[merged[i]];
+            let d = self.points[merged[(i + 1) % n]];
+            let area = Triangle::area_two_point(o, c, d);
+            if area > 0 {
+                return None;
+            }
+            corners += (area != 0) as usize;
+        }
+
+        if corners > max_vertices {
+            return None;
+        }
+
+        Some(merged)
+    }
+
+    /// Solves every small part without holes exactly, the rest by [`ConvexMesh::hertel_mehlhorn_polygons`].
+    pub(crate) fn exact_convex_polygons(&self, max_vertices: usize) -> ConvexDecomposition {
+        let mut result = ConvexDecomposition::default();
+        let mut rest = Vec::new();
+
+        for part in self.parts() {
+            let polygon = self.simple_boundary(&part);
+            match polygon.filter(|p| p.len() <= EXACT_MAX_VERTICES) {
+                Some(polygon) => {
+                    let partition = ExactPartition::new(&polygon, max_vertices);
+                    result.append(partition.solve());
+                }
+                None => rest.extend(part),
+            }
+        }
+
+        if !rest.is_empty() {
+            result.append(self.hertel_mehlhorn_polygons(&rest, max_vertices));
+        }
+
+        result
+    }
+
+    /// The triangles of every connected part of the mesh.
+    fn parts(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.triangles.len()];
+        let mut parts = Vec::new();
+        for start in 0..self.triangles.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut part = vec![start];
+            let mut i = 0;
+            while i < part.len() {
+                for &n in self.triangles[part[i]].neighbors.iter() {
+                    if n < self.triangles.len() && !visited[n] {
+                        visited[n] = true;
+                        part.push(n);
+                    }
+                }
+                i += 1;
+            }
+            parts.push(part);
+        }
+        parts
+    }
+
+    /// The contour of a part with a single simple boundary, without collinear points.
+    fn simple_boundary(&self, part: &[usize]) -> Option<Vec<IntPoint>> {
+        let mut next = HashMap::new();
+        for &t in part {
+            let triangle = &self.triangles[t];
+            for k in 0..3 {
+                if triangle.neighbors[k] < self.triangles.len() {
+                    continue;
+                }
+                let a = triangle.vertices[(k + 1) % 3].index;
+                let b = triangle.vertices[(k + 2) % 3].index;
+                if next.insert(a, b).is_some() {
+                    // the boundary touches itself
+                    return None;
+                }
+            }
+        }
+
+        let &start = next.keys().min()?;
+        let mut contour = Vec::with_capacity(next.len());
+        let mut i = start;
+        loop {
+            contour.push(self.points[i]);
+            i = next[&i];
+            if i == start {
+                break;
+            }
+        }
+
+        // a hole makes a second loop
+        if contour.len() != next.len() {
+            return None;
+        }
+
+        contour.simplify_contour();
+        Some(contour)
+    }
+}
+
+#[inline]
+fn find_root(roots: &mut [usize], mut i: usize) -> usize {
+    while roots[i] != i {
+        roots[i] = roots[roots[i]];
+        i = roots[i];
+    }
+    i
+}
+
+const INF: u32 = u32::MAX;
+
+/// The minimum convex partition of a simple polygon by its diagonals.
+///
+/// `best(i, j)` is the minimum number of convex polygons covering the sub-polygon `i..=j`
+/// closed by the chord `ji`. The polygon with the chord is `i, k1, .., j`, the chain is
+/// built vertex by vertex and every step adds the cost of the sub-polygon it cuts off.
+/// With a vertex limit a chain also keeps the number of its corners, straight ones are not counted.
+struct ExactPartition<'a> {
+    points: &'a [IntPoint],
+    n: usize,
+    max_vertices: usize,
+    // the size of the corner count dimension, it is only needed with a vertex limit
+    counts: usize,
+    is_chord: Vec<bool>,
+    best: Vec<u32>,
+    chains: Vec<Vec<usize>>,
+}
+
+impl<'a> ExactPartition<'a> {
+    fn new(points: &'a [IntPoint], max_vertices: usize) -> Self {
+        let n = points.len();
+        let counts = if max_vertices < n { max_vertices + 1 } else { 1 };
+        let mut partition = Self {
+            points,
+            n,
+            max_vertices,
+            counts,
+            is_chord: vec![false; n * n],
+            best: vec![INF; n * n],
+            chains: vec![Vec::new(); n * n],
+        };
+
+        for a in 0..n {
+            for b in a + 1..n {
+                partition.is_chord[a * n + b] = b == a + 1 || (a == 0 && b == n - 1) || partition.is_diagonal(a, b);
+            }
+        }
+
+        partition
+    }
+
+    #[inline]
+    fn index(&self, k1: usize, a: usize, b: usize, count: usize) -> usize {
+        let n = self.n;
+        ((k1 * n + a) * n + b) * self.counts + if self.counts > 1 { count } else { 0 }
+    }
+
+    fn solve(mut self) -> ConvexDecomposition {
+        let n = self.n;
+        if n < 3 {
+            return ConvexDecomposition::default();
+        }
+
+        let size = n * n * n * self.counts;
+        let mut cost = vec![INF; size];
+        let mut back = vec![NIL_INDEX; size];
+
+        for i in (0..n).rev() {
+            cost.fill(INF);
+
+            for b in i + 1..n {
+                if b == i + 1 {
+                    self.best[i * n + b] = 0;
+                } else if self.is_chord[i * n + b] {
+                    self.close(i, b, &cost, &back);
+                }
+
+                // start the chains with the edge ib, no corner is known yet
+                if self.is_chord[i * n + b] && self.best[i * n + b] < INF {
+                    let s = self.index(b, i, b, 0);
+                    cost[s] = self.best[i * n + b];
+                    back[s] = NIL_INDEX;
+                }
+
+                // extend every chain ending at b, the corner at b becomes known
+                for k1 in i + 1..=b {
+                    let from = if k1 == b { i..i + 1 } else { k1..b };
+                    for a in from {
+                        for count in 0..self.counts {
+                            let s = self.index(k1, a, b, count);
+                            if cost[s] == INF {
+                                continue;
+                            }
+                            for e in b + 1..n {
+                                let be = self.best[b * n + e];
+                                if !self.is_chord[b * n + e] || be == INF {
+                                    continue;
+                                }
+                                let Some(corner) = self.corner(a, b, e) else {
+                                    continue;
+                                };
+                                let next_count = count + corner;
+                                if self.counts > 1 && next_count > self.max_vertices {
+                                    continue;
+                                }
+                                let t = self.index(k1, b, e, next_count);
+                                let c = cost[s] + be;
+                                if c < cost[t] {
+                                    cost[t] = c;
+                                    back[t] = a;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // every sub-polygon cut off by a diagonal is adjacent to the polygon it is cut from
+        let mut result = ConvexDecomposition::default();
+        let mut stack = vec![(0, n - 1, NIL_INDEX)];
+        while let Some((i, j, parent)) = stack.pop() {
+            let index = result.polygons.len();
+            let chain = &self.chains[i * n + j];
+            let mut contour: IntContour = chain.iter().map(|&k| self.points[k]).collect();
+            contour.simplify_contour();
+            result.polygons.push(contour);
+            result.adjacency.push(Vec::new());
+            if parent != NIL_INDEX {
+                result.adjacency[parent].push(index);
+                result.adjacency[index].push(parent);
+            }
+            for w in chain.windows(2) {
+                if w[1] > w[0] + 1 {
+                    stack.push((w[0], w[1], index));
+                }
+            }
+        }
+
+        for list in result.adjacency.iter_mut() {
+            list.sort_unstable();
+        }
+
+        result
+    }
+
+    /// Finds the best polygon with the chord `ji`.
+    fn close(&mut self, i: usize, j: usize, cost: &[u32], back: &[usize]) {
+        let n = self.n;
+        let mut best = INF;
+        let mut best_state = (0, 0, 0);
+        for k1 in i + 1..j {
+            let Some(corner_i) = self.corner(j, i, k1) else {
+                continue;
+            };
+            for a in k1..j {
+                let Some(corner_j) = self.corner(a, j, i) else {
+                    continue;
+                };
+                for count in 0..self.counts {
+                    let c = cost[self.index(k1, a, j, count)];
+                    if c == INF || c + 1 >= best {
+                        continue;
+                    }
+                    if self.counts > 1 {
+                        let corners = count + corner_i + corner_j;
+                        if corners < 3 || corners > self.max_vertices {
+                            continue;
+                        }
+                    }
+                    best = c + 1;
+                    best_state = (k1, a, count);
+                }
+            }
+        }
+
+        if best == INF {
+            return;
+        }
+        self.best[i * n + j] = best;
+
+        // walk the chain back: j, a, .., k1, i
+        let (k1, mut x, mut count) = best_state;
+        let mut y = j;
+        let mut chain = vec![j];
+        loop {
+            chain.push(x);
+            let p = back[self.index(k1, x, y, count)];
+            if p == NIL_INDEX {
+                break;
+            }
+            if self.counts > 1 {
+                count -= self.corner(p, x, y).unwrap_or(0);
+            }
+            y = x;
+            x = p;
+        }
+        chain.reverse();
+        self.chains[i * n + j] = chain;
+    }
+
+    /// `Some(1)` for a corner `abc` turning like the contour, `Some(0)` for a straight one
+    /// and `None` for a reflex or a turned back one.
+    #[inline]
+    fn corner(&self, a: usize, b: usize, c: usize) -> Option<usize> {
+        let (pa, pb, pc) = (self.points[a], self.points[b], self.points[c]);
+        let area = Triangle::area_two_point(pa, pb, pc);
+        if area < 0 {
+            Some(1)
+        } else if area == 0 && pb.subtract(pa).dot_product(pc.subtract(pb)) > 0 {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// True if the segment `ab` lies strictly inside the polygon.
+    fn is_diagonal(&self, a: usize, b: usize) -> bool {
+        if !self.is_in_cone(a, b) || !self.is_in_cone(b, a) {
+            return false;
+        }
+        let pa = self.points[a];
+        let pb = self.points[b];
+        for e in 0..self.n {
+            let f = (e + 1) % self.n;
+            if e == a || e == b || f == a || f == b {
+                continue;
+            }
+            if is_touching(pa, pb, self.points[e], self.points[f]) {
+                return false;
+            }
+        }
+        // an edge touching `a` or `b` only at its other end is checked by the cones
+        true
+    }
+
+    /// True if `b` is strictly inside the inner angle at `a`.
+    #[inline]
+    fn is_in_cone(&self, a: usize, b: usize) -> bool {
+        let prev = self.points[(a + self.n - 1) % self.n];
+        let next = self.points[(a + 1) % self.n];
+        let pa = self.points[a];
+        let pb = self.points[b];
+        if Triangle::area_two_point(prev, pa, next) < 0 {
+            Triangle::area_two_point(pa, next, pb) < 0 && Triangle::area_two_point(prev, pa, pb) < 0
+        } else {
+            !(Triangle::area_two_point(pa, next, pb) >= 0 && Triangle::area_two_point(prev, pa, pb) >= 0)
+        }
+    }
+}
+
+/// True if the closed segments `ab` and `cd` have a common point.
+#[inline]
+fn is_touching(a: IntPoint, b: IntPoint, c: IntPoint, d: IntPoint) -> bool {
+    let abc = Triangle::area_two_point(a, b, c).signum();
+    let abd = Triangle::area_two_point(a, b, d).signum();
+    let cda = Triangle::area_two_point(c, d, a).signum();
+    let cdb = Triangle::area_two_point(c, d, b).signum();
+
+    if abc * abd < 0 && cda * cdb < 0 {
+        return true;
+    }
+
+    let on = |p: IntPoint, q: IntPoint, r: IntPoint| {
+        r.x >= p.x.min(q.x) && r.x <= p.x.max(q.x) && r.y >= p.y.min(q.y) && r.y <= p.y.max(q.y)
+    };
+    (abc == 0 && on(a, b, c)) || (abd == 0 && on(a, b, d)) || (cda == 0 && on(c, d, a)) || (cdb == 0 && on(c, d, b))
+}
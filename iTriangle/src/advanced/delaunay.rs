@@ -1,7 +1,11 @@
 use std::collections::HashSet;
-use crate::geom::triangle::IntTriangle;
+use crate::geom::triangle::{IntTriangle, TriangleNet};
+use crate::int::constraint::ConstrainedEdges;
+use crate::int::provenance::VertexSource;
 use crate::int::triangulation::RawIntTriangulation;
+use crate::int::triangulator::Triangulator;
 use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
 use i_overlay::i_float::u128::UInt128;
 
 /// A 2D integer-based Delaunay triangulation.
@@ -11,9 +15,15 @@ use i_overlay::i_float::u128::UInt128;
 /// - `triangles`: A list of `IntTriangle` elements (triangle vertex indices and neighbors)
 /// - `points`: A list of `IntPoint` elements (original and inserted points)
 ///
+/// Constrained edges (see [`Triangulator::triangulate_shape_with_constraints`]) are never flipped.
+///
+/// [`Triangulator::triangulate_shape_with_constraints`]: crate::int::triangulator::Triangulator::triangulate_shape_with_constraints
 pub struct IntDelaunay {
     pub triangles: Vec<IntTriangle>,
     pub points: Vec<IntPoint>,
+    pub(crate) constraints: ConstrainedEdges,
+    pub(crate) shape_ids: Vec<usize>,
+    pub(crate) vertex_sources: Vec<VertexSource>,
 }
 
 impl RawIntTriangulation {
@@ -22,6 +32,7 @@ impl RawIntTriangulation {
     ///
     /// The mesh is refined in-place by checking local angle conditions and
     /// flipping edges until the Delaunay criterion is satisfied.
+    /// With the `rayon` feature, independent parts of the mesh are processed in parallel.
     ///
     /// # Returns
     /// A new [`IntDelaunay`] structure with updated triangle connectivity.
@@ -30,6 +41,9 @@ impl RawIntTriangulation {
         let mut delaunay = IntDelaunay {
             triangles: self.triangles,
             points: self.points,
+            constraints: self.constraints,
+            shape_ids: self.shape_ids,
+            vertex_sources: self.vertex_sources,
         };
 
         delaunay.build();
@@ -39,7 +53,42 @@ impl RawIntTriangulation {
 }
 
 impl IntDelaunay {
+    /// Builds the Delaunay triangulation of a point cloud over its convex hull.
+    ///
+    /// Duplicate points are merged, points lying on the hull are kept as hull vertices.
+    /// The result is empty if there are fewer than three distinct points or all of them are collinear.
+    ///
+    /// Points of the result are not in input order, match them by position.
+    pub fn from_points(points: &[IntPoint]) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let hull = convex_hull(&sorted);
+        if hull.len() < 3 {
+            return RawIntTriangulation::empty().into_delaunay();
+        }
+
+        let mut inner = sorted;
+        let mut on_hull = hull.clone();
+        on_hull.sort_unstable();
+        inner.retain(|p| on_hull.binary_search(p).is_err());
+
+        Triangulator::default()
+            .unchecked_triangulate_contour_with_steiner_points(&hull, &inner)
+            .into_delaunay()
+    }
+
     pub(crate) fn build(&mut self) {
+        #[cfg(feature = "rayon")]
+        if self.par_for_each_part(IntDelaunay::build_serial) {
+            return;
+        }
+
+        self.build_serial();
+    }
+
+    pub(crate) fn build_serial(&mut self) {
         let mut unchecked = HashSet::with_capacity(self.triangles.len() / 4);
 
         for abc_index in 0..self.triangles.len() {
@@ -49,6 +98,7 @@ impl IntDelaunay {
         if !unchecked.is_empty() {
             let mut buffer = Vec::with_capacity(unchecked.len());
             buffer.extend(unchecked.drain());
+            buffer.sort_unstable();
             self.fix_triangles(&mut buffer, &mut unchecked);
         }
     }
@@ -60,7 +110,29 @@ impl IntDelaunay {
                 self.fix_triangle(abc_index, unchecked);
             }
             buffer.clear();
+            // the set order is random, the mesh must not depend on it
             buffer.extend(unchecked.drain());
+            buffer.sort_unstable();
+        }
+    }
+
+    /// Same as [`IntDelaunay::fix_triangles`], also appends every checked triangle to `touched`.
+    pub(crate) fn fix_triangles_and_collect(
+        &mut self,
+        buffer: &mut Vec<usize>,
+        unchecked: &mut HashSet<usize>,
+        touched: &mut Vec<usize>,
+    ) {
+        debug_assert!(unchecked.is_empty());
+        while !buffer.is_empty() {
+            touched.extend_from_slice(buffer);
+            for &abc_index in buffer.iter() {
+                self.fix_triangle(abc_index, unchecked);
+            }
+            buffer.clear();
+            // the set order is random, the mesh must not depend on it
+            buffer.extend(unchecked.drain());
+            buffer.sort_unstable();
         }
     }
 
@@ -93,6 +165,10 @@ impl IntDelaunay {
         let t_abc = unsafe { self.triangles.get_unchecked(abc_index) };
         let t_pcb = unsafe { self.triangles.get_unchecked(pcb_index) };
         let abc = t_abc.abc_by_neighbor(pcb_index);
+        if self.constraints.contains(abc.v1.vertex.index, abc.v2.vertex.index) {
+            return false;
+        }
+
         let pcb = t_pcb.abc_by_neighbor(abc_index);
         if Self::is_flip_not_required(
             pcb.v0.vertex.point, // p
@@ -103,35 +179,30 @@ impl IntDelaunay {
             return false;
         }
 
-        // abc and pcb are clock-wised ordered triangles
-
-        // abc -> abp
-        // pcb -> pca
+        self.triangles.flip(abc_index, pcb_index, &abc, &pcb);
 
-        self.update_neighbor(abc.v1.neighbor, abc_index, pcb_index);
-        self.update_neighbor(pcb.v1.neighbor, pcb_index, abc_index);
-
-        let abp = &mut self.triangles[abc_index];
-        abp.neighbors[abc.v0.position] = pcb.v1.neighbor;
-        abp.neighbors[abc.v1.position] = pcb_index;
-        abp.neighbors[abc.v2.position] = abc.v2.neighbor;
-        abp.vertices[abc.v2.position] = pcb.v0.vertex;
+        true
+    }
 
-        let pca = &mut self.triangles[pcb_index];
-        pca.neighbors[pcb.v0.position] = abc.v1.neighbor;
-        pca.neighbors[pcb.v1.position] = abc_index;
-        pca.neighbors[pcb.v2.position] = pcb.v2.neighbor;
-        pca.vertices[pcb.v2.position] = abc.v0.vertex;
+    /// Gives a new triangle pushed at the end the shape id of the triangle it was split from.
+    #[inline]
+    pub(crate) fn inherit_shape_id(&mut self, parent_index: usize) {
+        if let Some(&shape_id) = self.shape_ids.get(parent_index) {
+            self.shape_ids.push(shape_id);
+        }
+    }
 
-        true
+    /// Records the source of a point pushed by refinement.
+    #[inline]
+    pub(crate) fn add_refinement_source(&mut self) {
+        if !self.vertex_sources.is_empty() {
+            self.vertex_sources.push(VertexSource::Refinement);
+        }
     }
 
     #[inline]
     pub(crate) fn update_neighbor(&mut self, neighbor_index: usize, old_index: usize, new_index: usize) {
-        if neighbor_index >= self.triangles.len() {
-            return;
-        }
-        self.triangles[neighbor_index].update_neighbor(old_index, new_index);
+        self.triangles.update_neighbor(neighbor_index, old_index, new_index);
     }
 
     // if p is inside circumscribe circle of a, b, c return false
@@ -188,23 +259,47 @@ impl IntDelaunay {
     }
 }
 
-impl IntTriangle {
-    #[inline]
-    fn update_neighbor(&mut self, old_index: usize, new_index: usize) {
-        if self.neighbors[0] == old_index {
-            self.neighbors[0] = new_index;
-        } else if self.neighbors[1] == old_index {
-            self.neighbors[1] = new_index;
+/// Andrew's monotone chain over sorted unique points, keeps collinear hull points.
+/// The hull is wound like an outer contour, it is empty if all points are collinear.
+fn convex_hull(sorted: &[IntPoint]) -> Vec<IntPoint> {
+    let (first, last) = match (sorted.first(), sorted.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Vec::new(),
+    };
+    if sorted.iter().all(|&p| Triangle::area_two_point(first, last, p) == 0) {
+        return Vec::new();
+    }
+
+    let mut hull = Vec::with_capacity(sorted.len() + 1);
+    for &p in sorted.iter() {
+        push_convex(&mut hull, p, 2);
+    }
+    let lower = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        push_convex(&mut hull, p, lower);
+    }
+    hull.pop();
+
+    hull
+}
+
+/// Drops the last points of the chain while it turns clockwise, never below `min_len`.
+#[inline]
+fn push_convex(chain: &mut Vec<IntPoint>, p: IntPoint, min_len: usize) {
+    while chain.len() >= min_len {
+        let n = chain.len();
+        if Triangle::area_two_point(chain[n - 2], chain[n - 1], p) > 0 {
+            chain.pop();
         } else {
-            debug_assert_eq!(self.neighbors[2], old_index);
-            self.neighbors[2] = new_index;
+            break;
         }
     }
+    chain.push(p);
 }
 
 #[cfg(test)]
 impl IntDelaunay {
-    fn validate(&self) {
+    pub(crate) fn validate(&self) {
         use i_overlay::i_float::triangle::Triangle;
 
         for (i, t) in self.triangles.iter().enumerate() {
@@ -230,7 +325,7 @@ impl IntDelaunay {
         }
     }
 
-    fn area(&self) -> i64 {
+    pub(crate) fn area(&self) -> i64 {
         use i_overlay::i_float::triangle::Triangle;
         let mut s = 0;
         for t in self.triangles.iter() {
@@ -271,7 +366,7 @@ mod tests {
         let p = IntPoint::new(0, -4);
 
         let is_flip_not_required = IntDelaunay::is_flip_not_required(p, a, b, c);
-        assert_eq!(is_flip_not_required, true);
+        assert!(is_flip_not_required);
     }
 
     #[test]
@@ -283,7 +378,7 @@ mod tests {
         let p = IntPoint::new(0, -2);
 
         let is_flip_not_required = IntDelaunay::is_flip_not_required(p, a, b, c);
-        assert_eq!(is_flip_not_required, true);
+        assert!(is_flip_not_required);
     }
 
     #[test]
@@ -294,7 +389,7 @@ mod tests {
         let p = IntPoint::new(0, -1);
 
         let is_flip_not_required = IntDelaunay::is_flip_not_required(p, a, b, c);
-        assert_eq!(is_flip_not_required, false);
+        assert!(!is_flip_not_required);
     }
 
     #[test]
@@ -305,7 +400,7 @@ mod tests {
         let p = IntPoint::new(0, -1);
 
         let is_flip_not_required = IntDelaunay::is_flip_not_required(p, a, b, c);
-        assert_eq!(is_flip_not_required, false);
+        assert!(!is_flip_not_required);
     }
 
     #[test]
@@ -373,6 +468,9 @@ mod tests {
                 },
             ],
             points,
+            constraints: Default::default(),
+            shape_ids: vec![],
+            vertex_sources: vec![],
         };
 
         let is_swapped = delaunay.swap_triangles(0, 1);
@@ -460,6 +558,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_6() {
+        let points = path(&[[0, 0], [10, 0], [10, 10], [0, 10], [5, 5], [5, 0], [0, 0], [5, 5], [3, 7]]);
+        let delaunay = IntDelaunay::from_points(&points);
+        delaunay.validate();
+
+        // 7 unique points, 5 of them on the hull
+        assert_eq!(delaunay.points.len(), 7);
+        assert_eq!(delaunay.triangles.len(), 2 * 7 - 2 - 5);
+        assert_eq!(delaunay.area(), -200);
+    }
+
+    #[test]
+    fn test_7() {
+        let collinear = path(&[[0, 0], [1, 1], [2, 2], [5, 5], [1, 1]]);
+        assert!(IntDelaunay::from_points(&collinear).triangles.is_empty());
+        assert!(IntDelaunay::from_points(&path(&[[0, 0], [1, 0]])).triangles.is_empty());
+        assert!(IntDelaunay::from_points(&[]).triangles.is_empty());
+    }
+
+    #[test]
+    fn test_random_3() {
+        let mut rng = rand::rng();
+        for _ in 0..2_000 {
+            let n = rng.random_range(1..40);
+            let points = random(20, n);
+            let delaunay = IntDelaunay::from_points(&points);
+            if delaunay.triangles.is_empty() {
+                continue;
+            }
+            delaunay.validate();
+
+            for p in points.iter() {
+                assert!(delaunay.points.contains(p));
+            }
+
+            // every edge satisfies the Delaunay condition
+            for (i, t) in delaunay.triangles.iter().enumerate() {
+                for &n in t.neighbors.iter() {
+                    if n >= delaunay.triangles.len() {
+                        continue;
+                    }
+                    let abc = t.abc_by_neighbor(n);
+                    let pcb = delaunay.triangles[n].abc_by_neighbor(i);
+                    assert!(IntDelaunay::is_flip_not_required(
+                        pcb.v0.vertex.point,
+                        abc.v0.vertex.point,
+                        abc.v1.vertex.point,
+                        abc.v2.vertex.point,
+                    ));
+                }
+            }
+        }
+    }
+
     fn random(radius: i32, n: usize) -> IntPath {
         let a = radius / 2;
         let mut points = Vec::with_capacity(n);
@@ -0,0 +1,318 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::triangle::IntTriangle;
+use crate::index::{Index, NIL_INDEX};
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::fix_vec::FixVec;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use i_overlay::i_shape::int::shape::IntContour;
+use std::collections::HashMap;
+
+/// The role of an edge in a triangle mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// A boundary edge of an outer contour.
+    OuterBoundary,
+    /// A boundary edge of a hole (or a slit inside the shape).
+    HoleBoundary,
+    /// An inner edge with at least one end strictly inside the mesh (a Steiner or refinement point).
+    SteinerInternal,
+    /// An inner edge connecting two boundary points.
+    InteriorDiagonal,
+}
+
+/// An undirected mesh edge, see [`RawIntTriangulation::edges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshEdge {
+    /// The first point index, the edge goes from `a` to `b` in the winding of `triangle`.
+    pub a: usize,
+    /// The second point index.
+    pub b: usize,
+    /// The triangle on one side of the edge.
+    pub triangle: usize,
+    /// The triangle on the other side, `None` for a boundary edge.
+    pub neighbor: Option<usize>,
+    pub kind: EdgeKind,
+}
+
+impl RawIntTriangulation {
+    /// Returns every undirected edge of the mesh exactly once, in triangle order.
+    ///
+    /// Boundary loops are classified by their winding: loops wound like the triangles
+    /// are outer contours, the others are holes.
+    #[inline]
+    pub fn edges(&self) -> Vec<MeshEdge> {
+        collect_edges(&self.triangles, &self.points)
+    }
+}
+
+impl IntDelaunay {
+    /// Returns every undirected edge of the mesh exactly once, see [`RawIntTriangulation::edges`].
+    #[inline]
+    pub fn edges(&self) -> Vec<MeshEdge> {
+        collect_edges(&self.triangles, &self.points)
+    }
+}
+
+fn collect_edges(triangles: &[IntTriangle], points: &[IntPoint]) -> Vec<MeshEdge> {
+    let (mut edges, loops) = collect_edges_and_loops(triangles, points);
+
+    for boundary_loop in loops.iter() {
+        let origin = points[edges[boundary_loop[0]].a];
+        let area = boundary_loop.iter().fold(0, |s, &i| {
+            let e = &edges[i];
+            s + Triangle::area_two_point(origin, points[e.a], points[e.b])
+        });
+
+        // triangles have a negative area, an outer loop is wound the same way
+        let kind = if area < 0 {
+            EdgeKind::OuterBoundary
+        } else {
+            EdgeKind::HoleBoundary
+        };
+        for &i in boundary_loop.iter() {
+            edges[i].kind = kind;
+        }
+    }
+
+    edges
+}
+
+/// Returns the boundary loops of the mesh as contours, outer loops are wound like the triangles.
+pub(crate) fn boundary_contours(triangles: &[IntTriangle], points: &[IntPoint]) -> Vec<IntContour> {
+    let (edges, loops) = collect_edges_and_loops(triangles, points);
+    loops
+        .iter()
+        .map(|boundary_loop| boundary_loop.iter().map(|&i| points[edges[i].a]).collect())
+        .collect()
+}
+
+/// Returns unclassified edges and the boundary loops as lists of edge indices.
+fn collect_edges_and_loops(triangles: &[IntTriangle], points: &[IntPoint]) -> (Vec<MeshEdge>, Vec<Vec<usize>>) {
+    let mut edges = Vec::with_capacity(3 * triangles.len() / 2 + 2);
+    // index of every boundary edge in `edges`, by triangle and edge position
+    let mut boundary = vec![[NIL_INDEX; 3]; triangles.len()];
+    let mut outgoing_count = vec![0u32; points.len()];
+
+    for (i, t) in triangles.iter().enumerate() {
+        for (k, &neighbor) in t.neighbors.iter().enumerate() {
+            if neighbor.is_not_nil() && neighbor < i {
+                continue;
+            }
+            let a = t.vertices[(k + 1) % 3].index;
+            let b = t.vertices[(k + 2) % 3].index;
+            if !neighbor.is_not_nil() {
+                boundary[i][k] = edges.len();
+                outgoing_count[a] += 1;
+            }
+            edges.push(MeshEdge {
+                a,
+                b,
+                triangle: i,
+                neighbor: if neighbor.is_not_nil() { Some(neighbor) } else { None },
+                kind: EdgeKind::InteriorDiagonal,
+            });
+        }
+    }
+
+    for e in edges.iter_mut() {
+        if e.neighbor.is_some() && (outgoing_count[e.a] == 0 || outgoing_count[e.b] == 0) {
+            e.kind = EdgeKind::SteinerInternal;
+        }
+    }
+
+    let loops = BoundaryLoops {
+        triangles,
+        points,
+        boundary,
+        outgoing_count,
+    }
+    .trace(&edges);
+
+    (edges, loops)
+}
+
+struct BoundaryLoops<'a> {
+    triangles: &'a [IntTriangle],
+    points: &'a [IntPoint],
+    boundary: Vec<[usize; 3]>,
+    outgoing_count: Vec<u32>,
+}
+
+impl BoundaryLoops<'_> {
+    fn trace(&self, edges: &[MeshEdge]) -> Vec<Vec<usize>> {
+        // boundary edges leaving points where several boundary loops touch
+        let mut pinched: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (index, e) in edges.iter().enumerate() {
+            if e.neighbor.is_none() && self.outgoing_count[e.a] > 1 {
+                pinched.entry(e.a).or_default().push(index);
+            }
+        }
+
+        let mut visited = vec![false; edges.len()];
+        let mut loops = Vec::new();
+        for first in 0..edges.len() {
+            if edges[first].neighbor.is_some() || visited[first] {
+                continue;
+            }
+
+            let mut boundary_loop = Vec::new();
+            let mut index = first;
+            while !visited[index] {
+                visited[index] = true;
+                boundary_loop.push(index);
+
+                let e = &edges[index];
+                let next = self.next_in_fan(e);
+                index = if let Some(candidates) = pinched.get(&e.b) {
+                    self.next_outside(e, next, candidates, edges)
+                } else {
+                    next
+                };
+            }
+            loops.push(boundary_loop);
+        }
+
+        loops
+    }
+
+    /// Rotates around the end of the edge through its triangles until the next boundary edge.
+    fn next_in_fan(&self, e: &MeshEdge) -> usize {
+        let mut t = e.triangle;
+        loop {
+            let triangle = &self.triangles[t];
+            let k = (triangle.vertex_position(e.b) + 2) % 3;
+            let next = triangle.neighbors[k];
+            if !next.is_not_nil() {
+                return self.boundary[t][k];
+            }
+            t = next;
+        }
+    }
+
+    /// At a point shared by several loops, the next edge is the one reached by rotating
+    /// outside of the mesh, i.e. the opposite extreme of the edge found inside the fan.
+    fn next_outside(&self, e: &MeshEdge, in_fan: usize, candidates: &[usize], edges: &[MeshEdge]) -> usize {
+        let o = self.points[e.b];
+        let r = self.points[e.a].subtract(o);
+        let ccw_cmp = |i: &usize, j: &usize| {
+            let u = self.points[edges[*i].b].subtract(o);
+            let v = self.points[edges[*j].b].subtract(o);
+            let (hu, hv) = (half_plane(r, u), half_plane(r, v));
+            if hu != hv {
+                hu.cmp(&hv)
+            } else {
+                0.cmp(&u.cross_product(v))
+            }
+        };
+
+        let min = *candidates.iter().min_by(|i, j| ccw_cmp(i, j)).unwrap();
+        let max = *candidates.iter().max_by(|i, j| ccw_cmp(i, j)).unwrap();
+        if in_fan == min {
+            max
+        } else {
+            min
+        }
+    }
+}
+
+/// Orders directions counter-clockwise starting right after `r`, the direction of `r` itself is the last.
+#[inline]
+fn half_plane(r: FixVec, v: FixVec) -> u8 {
+    let cross = r.cross_product(v);
+    if cross > 0 {
+        0
+    } else if cross < 0 || r.dot_product(v) < 0 {
+        1
+    } else {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::edges::{EdgeKind, MeshEdge};
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn count(edges: &[MeshEdge], kind: EdgeKind) -> usize {
+        edges.iter().filter(|e| e.kind == kind).count()
+    }
+
+    #[test]
+    fn test_0() {
+        let shape = vec![
+            path(&[[0, 0], [10, 0], [10, 10], [0, 10]]),
+            path(&[[3, 3], [3, 7], [7, 7], [7, 3]]),
+        ];
+        let edges = shape.triangulate().edges();
+
+        assert_eq!(count(&edges, EdgeKind::OuterBoundary), 4);
+        assert_eq!(count(&edges, EdgeKind::HoleBoundary), 4);
+        assert_eq!(count(&edges, EdgeKind::SteinerInternal), 0);
+        // 8 triangles, 3 * 8 = 2 * inner + boundary
+        assert_eq!(count(&edges, EdgeKind::InteriorDiagonal), 8);
+    }
+
+    #[test]
+    fn test_1() {
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let delaunay = contour.triangulate_with_steiner_points(&[IntPoint::new(5, 5)]).into_delaunay();
+        let edges = delaunay.edges();
+
+        assert_eq!(edges.len(), 8);
+        assert_eq!(count(&edges, EdgeKind::OuterBoundary), 4);
+        assert_eq!(count(&edges, EdgeKind::SteinerInternal), 4);
+        for e in edges.iter() {
+            let t = &delaunay.triangles[e.triangle];
+            assert!(t.vertices.iter().any(|v| v.index == e.a));
+            assert!(t.vertices.iter().any(|v| v.index == e.b));
+            assert_eq!(e.neighbor.is_none(), e.kind == EdgeKind::OuterBoundary);
+        }
+    }
+
+    #[test]
+    fn test_2() {
+        // the hole touches the outer contour at a point
+        let shape = vec![
+            path(&[[0, 0], [10, 0], [10, 10], [0, 10]]),
+            path(&[[0, 0], [5, 8], [8, 5]]),
+        ];
+        let edges = shape.triangulate().edges();
+
+        assert_eq!(count(&edges, EdgeKind::OuterBoundary), 4);
+        assert_eq!(count(&edges, EdgeKind::HoleBoundary), 3);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let n = rng.random_range(3..20);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-20..=20), rng.random_range(-20..=20)))
+                .collect();
+
+            let raw = contour.triangulate();
+            let edges = raw.edges();
+            let boundary = edges.iter().filter(|e| e.neighbor.is_none()).count();
+            let inner = edges.len() - boundary;
+            assert_eq!(2 * inner + boundary, 3 * raw.triangles.len());
+            for e in edges.iter() {
+                let is_boundary = matches!(e.kind, EdgeKind::OuterBoundary | EdgeKind::HoleBoundary);
+                assert_eq!(is_boundary, e.neighbor.is_none());
+            }
+
+            let mut keys: Vec<_> = edges.iter().map(|e| (e.a.min(e.b), e.a.max(e.b))).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            assert_eq!(keys.len(), edges.len());
+        }
+    }
+}
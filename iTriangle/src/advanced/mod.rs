@@ -2,4 +2,12 @@ pub mod delaunay;
 pub mod triangulation;
 pub mod convex;
 pub mod centroid;
+pub mod topology;
+pub mod edges;
+pub mod voronoi;
+pub mod conforming;
+pub mod quad;
+mod convex_partition;
 
+#[cfg(feature = "rayon")]
+mod parallel;
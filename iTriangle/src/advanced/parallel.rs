@@ -0,0 +1,506 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::triangle::IntTriangle;
+use crate::index::Index;
+use crate::tessellation::pass::Passes;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+/// Smaller meshes are processed on the current thread, splitting them costs more than it saves.
+const MIN_PARALLEL_TRIANGLES: usize = 1024;
+
+/// A visit that added triangles or points, the part lengths after it.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    round: usize,
+    creator: usize,
+    triangles: usize,
+    points: usize,
+}
+
+/// How the independent parts of a mesh were cut out of it.
+struct Layout {
+    /// The triangles of every part.
+    ranges: Vec<Range<usize>>,
+    /// The mesh index of every point of every part.
+    points: Vec<Vec<usize>>,
+}
+
+impl IntDelaunay {
+    /// Runs `f` on every independent part of the mesh in parallel and merges the parts back.
+    ///
+    /// A part is a contiguous range of triangles with its own points, e.g. the triangulation of one shape
+    /// produced by [`Triangulator::unchecked_triangulate_shapes`](crate::int::triangulator::Triangulator::unchecked_triangulate_shapes).
+    /// `f` must not add triangles or points, the mesh keeps its order.
+    /// Returns `false` and leaves the mesh untouched if it cannot be split.
+    pub(crate) fn par_for_each_part<F: Fn(&mut IntDelaunay) + Sync>(&mut self, f: F) -> bool {
+        if self.triangles.len() < MIN_PARALLEL_TRIANGLES {
+            return false;
+        }
+
+        let (mut parts, layout) = if let Some(split) = self.split_parts() {
+            split
+        } else {
+            return false;
+        };
+
+        parts.par_iter_mut().for_each(&f);
+
+        let parts = parts.into_iter().map(|part| (part, Vec::new())).collect();
+        self.merge_parts(parts, layout);
+
+        true
+    }
+
+    /// Runs `passes` on every independent part of the mesh in parallel, one pass at a time,
+    /// and merges the parts back in the order [`IntDelaunay::run_passes`] on the whole mesh would give.
+    ///
+    /// Returns `false` and leaves the mesh untouched if it cannot be split.
+    pub(crate) fn par_run_passes<P: Passes>(&mut self, passes: &P) -> bool {
+        if self.triangles.len() < MIN_PARALLEL_TRIANGLES {
+            return false;
+        }
+
+        let (parts, layout) = if let Some(split) = self.split_parts() {
+            split
+        } else {
+            return false;
+        };
+
+        let mut runs: Vec<_> = parts
+            .into_iter()
+            .map(|part| {
+                let state = passes.state(&part);
+                (part, state, Vec::new())
+            })
+            .collect();
+
+        let mut pass = 0;
+        let mut changes = self.triangles.len();
+        loop {
+            let triangles = runs.iter().map(|(part, _, _)| part.triangles.len()).sum();
+            if !passes.is_next(pass, changes, triangles) {
+                break;
+            }
+
+            // a parallel refinement never gets full
+            changes = runs
+                .par_iter_mut()
+                .map(|(part, state, steps)| {
+                    let on_add = |round, creator, part: &IntDelaunay| {
+                        steps.push(Step {
+                            round,
+                            creator,
+                            triangles: part.triangles.len(),
+                            points: part.points.len(),
+                        })
+                    };
+                    part.run_pass(passes, state, pass, on_add).unwrap_or(0)
+                })
+                .sum();
+            pass += 1;
+        }
+
+        let parts = runs.into_iter().map(|(part, _, steps)| (part, steps)).collect();
+        self.merge_parts(parts, layout);
+
+        true
+    }
+
+    fn split_parts(&self) -> Option<(Vec<IntDelaunay>, Layout)> {
+        let ranges = independent_ranges(&self.triangles);
+        if ranges.len() < 2 {
+            return None;
+        }
+
+        // every point must be used by exactly one part
+        let mut owners = vec![usize::MAX; self.points.len()];
+        let mut counts = vec![0; ranges.len()];
+        for (part, range) in ranges.iter().enumerate() {
+            for t in self.triangles[range.clone()].iter() {
+                for v in t.vertices.iter() {
+                    let owner = &mut owners[v.index];
+                    if *owner == usize::MAX {
+                        *owner = part;
+                        counts[part] += 1;
+                    } else if *owner != part {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        if counts.iter().sum::<usize>() != self.points.len() {
+            return None;
+        }
+
+        let mut parts: Vec<IntDelaunay> = ranges
+            .iter()
+            .zip(counts.iter())
+            .map(|(range, &count)| IntDelaunay {
+                triangles: Vec::with_capacity(range.len()),
+                points: Vec::with_capacity(count),
+                constraints: Default::default(),
+                shape_ids: vec![],
+                vertex_sources: vec![],
+            })
+            .collect();
+        let mut points: Vec<Vec<usize>> = counts.iter().map(|&count| Vec::with_capacity(count)).collect();
+
+        // local indices keep the global order of points
+        let mut local = vec![0; self.points.len()];
+        for (i, (&owner, &p)) in owners.iter().zip(self.points.iter()).enumerate() {
+            let part = &mut parts[owner];
+            local[i] = part.points.len();
+            part.points.push(p);
+            points[owner].push(i);
+            if let Some(&source) = self.vertex_sources.get(i) {
+                part.vertex_sources.push(source);
+            }
+        }
+
+        for (part, range) in parts.iter_mut().zip(ranges.iter()) {
+            let offset = range.start;
+            if !self.shape_ids.is_empty() {
+                part.shape_ids.extend_from_slice(&self.shape_ids[range.clone()]);
+            }
+            part.triangles.extend(self.triangles[range.clone()].iter().map(|t| {
+                let mut t = t.clone();
+                for v in t.vertices.iter_mut() {
+                    v.index = local[v.index];
+                }
+                for n in t.neighbors.iter_mut() {
+                    if n.is_not_nil() {
+                        *n -= offset;
+                    }
+                }
+                t
+            }));
+        }
+
+        for &(a, b) in self.constraints.iter() {
+            parts[owners[a]].constraints.insert(local[a], local[b]);
+        }
+
+        Some((parts, Layout { ranges, points }))
+    }
+
+    /// Puts the parts back into the mesh.
+    ///
+    /// The parts keep the places they were cut from. Triangles and points added by the steps
+    /// are numbered as one run over the whole mesh would add them: round by round,
+    /// in the mesh order of the triangles that added them.
+    fn merge_parts(&mut self, parts: Vec<(IntDelaunay, Vec<Step>)>, layout: Layout) {
+        let Layout { ranges, points } = layout;
+        let mut triangle_maps: Vec<Vec<usize>> = ranges.into_iter().map(|range| range.collect()).collect();
+        let mut point_maps = points;
+
+        let mut triangles_count = self.triangles.len();
+        let mut points_count = self.points.len();
+
+        let mut cursors = vec![0; parts.len()];
+        let mut heap = BinaryHeap::with_capacity(parts.len());
+        for (i, (_, steps)) in parts.iter().enumerate() {
+            if let Some(step) = steps.first() {
+                heap.push(Reverse((step.round, triangle_maps[i][step.creator], i)));
+            }
+        }
+
+        while let Some(Reverse((_, _, i))) = heap.pop() {
+            let steps = &parts[i].1;
+            let step = steps[cursors[i]];
+
+            let triangle_map = &mut triangle_maps[i];
+            let added = step.triangles - triangle_map.len();
+            triangle_map.extend(triangles_count..triangles_count + added);
+            triangles_count += added;
+
+            let point_map = &mut point_maps[i];
+            let added = step.points - point_map.len();
+            point_map.extend(points_count..points_count + added);
+            points_count += added;
+
+            cursors[i] += 1;
+            if let Some(next) = steps.get(cursors[i]) {
+                // the creator is an old triangle or was added by an earlier step of the part
+                heap.push(Reverse((next.round, triangle_map[next.creator], i)));
+            }
+        }
+
+        let has_shape_ids = !self.shape_ids.is_empty();
+        let has_vertex_sources = !self.vertex_sources.is_empty();
+
+        let mut triangles = vec![None; triangles_count];
+        let mut points = vec![None; points_count];
+        let mut shape_ids = vec![0; if has_shape_ids { triangles_count } else { 0 }];
+        let mut vertex_sources = vec![None; if has_vertex_sources { points_count } else { 0 }];
+
+        self.constraints = Default::default();
+
+        for (((mut part, _), triangle_map), point_map) in parts.into_iter().zip(triangle_maps).zip(point_maps) {
+            debug_assert_eq!(part.triangles.len(), triangle_map.len());
+            debug_assert_eq!(part.points.len(), point_map.len());
+
+            for (mut t, &index) in part.triangles.into_iter().zip(triangle_map.iter()) {
+                for v in t.vertices.iter_mut() {
+                    v.index = point_map[v.index];
+                }
+                for n in t.neighbors.iter_mut() {
+                    if n.is_not_nil() {
+                        *n = triangle_map[*n];
+                    }
+                }
+                triangles[index] = Some(t);
+            }
+
+            for (&p, &index) in part.points.iter().zip(point_map.iter()) {
+                points[index] = Some(p);
+            }
+
+            if has_shape_ids {
+                for (&id, &index) in part.shape_ids.iter().zip(triangle_map.iter()) {
+                    shape_ids[index] = id;
+                }
+            }
+
+            if has_vertex_sources {
+                for (&source, &index) in part.vertex_sources.iter().zip(point_map.iter()) {
+                    vertex_sources[index] = Some(source);
+                }
+            }
+
+            part.constraints.remap(&point_map);
+            self.constraints.append(&mut part.constraints);
+        }
+
+        // every index is taken by exactly one part
+        debug_assert!(triangles.iter().all(Option::is_some) && points.iter().all(Option::is_some));
+        self.triangles = triangles.into_iter().flatten().collect();
+        self.points = points.into_iter().flatten().collect();
+        self.shape_ids = shape_ids;
+        self.vertex_sources = vertex_sources.into_iter().flatten().collect();
+    }
+}
+
+/// Splits triangles into contiguous ranges, no triangle has a neighbor outside its range.
+fn independent_ranges(triangles: &[IntTriangle]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut reach = 0;
+    for (i, t) in triangles.iter().enumerate() {
+        for &n in t.neighbors.iter() {
+            if n.is_not_nil() {
+                if n < start {
+                    // a neighbor behind the closed range, the mesh is not split by shapes
+                    return Vec::new();
+                }
+                reach = reach.max(n);
+            }
+        }
+        if reach <= i {
+            ranges.push(start..i + 1);
+            start = i + 1;
+            reach = start;
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulator::Triangulator;
+    use crate::tessellation::circumcenter::{CircumcenterPasses, EdgeSelector, RefinementOptions, SelectBiggerAngle};
+    use crate::tessellation::quality::MinAnglePasses;
+    use crate::tessellation::sizing::SizingPasses;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::shape::IntShape;
+    use rand::Rng;
+
+    fn grid_shape(x: i32, y: i32, n: i32) -> IntShape {
+        let mut contour = Vec::new();
+        for i in 0..n {
+            contour.push(IntPoint::new(x + 10 * i, y));
+        }
+        for i in 0..n {
+            contour.push(IntPoint::new(x + 10 * n, y + 10 * i));
+        }
+        for i in 0..n {
+            contour.push(IntPoint::new(x + 10 * (n - i), y + 10 * n));
+        }
+        for i in 0..n {
+            contour.push(IntPoint::new(x, y + 10 * (n - i)));
+        }
+        vec![contour]
+    }
+
+    #[test]
+    fn test_0() {
+        let shapes: Vec<IntShape> = (0..16).map(|i| grid_shape(1000 * i, 0, 40)).collect();
+        let triangulator = Triangulator::default();
+
+        let mut delaunay = triangulator.unchecked_triangulate_shapes(&shapes).into_delaunay();
+        let (parts, _) = delaunay.split_parts().unwrap();
+        assert_eq!(parts.len(), shapes.len());
+
+        let triangles = delaunay.triangles.len();
+        let points = delaunay.points.clone();
+        assert!(delaunay.par_for_each_part(|_| {}));
+        assert_eq!(delaunay.triangles.len(), triangles);
+        assert_eq!(delaunay.points, points);
+    }
+
+    #[test]
+    fn test_1() {
+        let shapes: Vec<IntShape> = (0..8).map(|i| grid_shape(1000 * i, 0, 60)).collect();
+        let delaunay = Triangulator::default()
+            .unchecked_triangulate_shapes(&shapes)
+            .into_delaunay();
+        let count = delaunay.points.len();
+
+        let refined = delaunay.refine_with_circumcenters(64);
+        validate(&refined);
+        assert!(refined.points.len() > count);
+
+        let area = shapes.iter().fold(0, |s, shape| s + shape[0].area_two());
+        assert_eq!(mesh_area(&refined), area);
+    }
+
+    #[test]
+    fn test_2() {
+        // the parallel path gives exactly the mesh of one run over the whole mesh
+        let shapes: Vec<IntShape> = (0..12).map(|i| grid_shape(1000 * i, 7 * i, 20 + 3 * i)).collect();
+        let triangulator = Triangulator::default();
+
+        let raw = triangulator.triangulate_shapes(&shapes);
+        let mut serial = IntDelaunay {
+            triangles: raw.triangles,
+            points: raw.points,
+            constraints: raw.constraints,
+            shape_ids: raw.shape_ids,
+            vertex_sources: raw.vertex_sources,
+        };
+        serial.build_serial();
+        let parallel = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        assert_same(&serial, &parallel);
+
+        let options = RefinementOptions {
+            min_area: 20,
+            ..Default::default()
+        };
+        let mut parallel = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        assert!(parallel.par_run_passes(&CircumcenterPasses {
+            options: &options,
+            limits: options.limits(),
+            selector: &SelectBiggerAngle,
+        }));
+        let serial = triangulator
+            .triangulate_shapes(&shapes)
+            .into_delaunay()
+            .refine_with_options(&options, |i, p| SelectBiggerAngle.select(i, p));
+        assert!(serial.points.len() > raw_points(&shapes));
+        assert_same(&serial, &parallel);
+
+        let passes = MinAnglePasses {
+            min_angle: 30.0,
+            max_area: 400,
+        };
+        let mut parallel = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        assert!(parallel.par_run_passes(&passes));
+        let mut serial = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        serial.run_passes_serial(&passes);
+        assert_same(&serial, &parallel);
+
+        let field = |p: IntPoint| 6.0 + (p.x % 1000).abs() as f64 / 20.0;
+        let passes = SizingPasses { field: &field };
+        let mut parallel = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        assert!(parallel.par_run_passes(&passes));
+        let mut serial = triangulator.triangulate_shapes(&shapes).into_delaunay();
+        serial.run_passes_serial(&passes);
+        assert_same(&serial, &parallel);
+    }
+
+    #[test]
+    fn test_3() {
+        // a selector reading indices sees the indices of the whole mesh
+        let shapes: Vec<IntShape> = (0..8).map(|i| grid_shape(1000 * i, 0, 40)).collect();
+        let delaunay = Triangulator::default().triangulate_shapes(&shapes).into_delaunay();
+        let count = delaunay.points.len();
+
+        let last = delaunay.triangles.len() - 1;
+        let mask = |i: usize, p: [IntPoint; 3]| {
+            if i == last {
+                SelectBiggerAngle.select(i, p)
+            } else {
+                None
+            }
+        };
+        let options = RefinementOptions {
+            max_iterations: 1,
+            ..Default::default()
+        };
+        let refined = delaunay.refine_with_options(&options, mask);
+        validate(&refined);
+
+        assert_eq!(refined.points.len(), count + 1);
+        let m = refined.points[count];
+        assert!(m.x >= 7000 && m.x <= 7400);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let triangulator = Triangulator::default();
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let count = rng.random_range(2..12);
+            let shapes: Vec<IntShape> = (0..count)
+                .map(|i| grid_shape(1000 * i, rng.random_range(-50..50), rng.random_range(3..60)))
+                .collect();
+
+            let delaunay = triangulator.unchecked_triangulate_shapes(&shapes).into_delaunay();
+            validate(&delaunay);
+
+            let refined = delaunay.refine_with_circumcenters_by_obtuse_angle(32);
+            validate(&refined);
+        }
+    }
+
+    fn raw_points(shapes: &[IntShape]) -> usize {
+        shapes.iter().map(|shape| shape[0].len()).sum()
+    }
+
+    fn assert_same(a: &IntDelaunay, b: &IntDelaunay) {
+        assert_eq!(a.points, b.points);
+        assert_eq!(a.triangles.len(), b.triangles.len());
+        for (ta, tb) in a.triangles.iter().zip(b.triangles.iter()) {
+            assert_eq!(ta.vertices.map(|v| v.index), tb.vertices.map(|v| v.index));
+            assert_eq!(ta.neighbors, tb.neighbors);
+        }
+        assert_eq!(a.shape_ids, b.shape_ids);
+        assert_eq!(a.vertex_sources, b.vertex_sources);
+    }
+
+    fn validate(delaunay: &IntDelaunay) {
+        for (i, t) in delaunay.triangles.iter().enumerate() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            assert!(Triangle::area_two_point(a, b, c) < 0);
+            for v in t.vertices.iter() {
+                assert_eq!(delaunay.points[v.index], v.point);
+            }
+            for &n in t.neighbors.iter() {
+                if n < delaunay.triangles.len() {
+                    assert!(delaunay.triangles[n].neighbors.contains(&i));
+                }
+            }
+        }
+    }
+
+    fn mesh_area(delaunay: &IntDelaunay) -> i64 {
+        delaunay.triangles.iter().fold(0, |s, t| {
+            s + Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point)
+        })
+    }
+}
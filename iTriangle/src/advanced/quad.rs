@@ -0,0 +1,689 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::index::NIL_INDEX;
+use crate::tessellation::quality::grid_point_on_segment;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use std::collections::HashMap;
+
+/// Quads and the triangles left without a pair, see [`IntDelaunay::to_quad_dominant`].
+#[derive(Debug, Clone, Default)]
+pub struct IntQuadMesh {
+    pub points: Vec<IntPoint>,
+    /// Point indices of every quad, wound like the mesh triangles.
+    pub quads: Vec<[usize; 4]>,
+    /// Point indices of the triangles without a pair.
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl IntDelaunay {
+    /// Pairs adjacent triangles into convex quads, the rest stays triangles.
+    ///
+    /// The pairs are a maximum matching of the triangle adjacency: no other pairing gives more quads.
+    /// Quad quality (the biggest minimal sine of a corner) only orders the greedy starting matching,
+    /// the augmenting paths that make it maximum may trade its best pairs for more quads.
+    /// Triangles are never paired across a constrained edge.
+    /// The points are the mesh points.
+    pub fn to_quad_dominant(&self) -> IntQuadMesh {
+        let mates = QuadMatching::new(self).solve();
+
+        let mut quads = Vec::with_capacity(self.triangles.len() / 2);
+        let mut triangles = Vec::new();
+        for (t, &mate) in mates.iter().enumerate() {
+            if mate == NIL_INDEX {
+                triangles.push(self.triangles[t].vertices.map(|v| v.index));
+            } else if t < mate {
+                quads.push(self.quad(t, mate));
+            }
+        }
+
+        IntQuadMesh {
+            points: self.points.clone(),
+            quads,
+            triangles,
+        }
+    }
+
+    /// Builds an all-quad mesh from [`IntDelaunay::to_quad_dominant`].
+    ///
+    /// Every quad and every triangle is split into quads by the middles of its sides and its center.
+    /// A middle is shared by both polygons of a side, so the mesh stays conforming.
+    /// New points follow the mesh points. A middle is the grid point of the side nearest its middle,
+    /// so the boundary never moves, a center is rounded to the nearest int point.
+    ///
+    /// A side without grid points inside is not split, the corners next to it become triangles
+    /// `[corner, next, center]` instead of quads. A polygon which can not be split into valid pieces
+    /// (e.g. a small one whose rounded center falls on a side) is kept whole and its sides are not split.
+    /// So the result may have triangles, e.g. for small triangles with odd coordinates.
+    pub fn to_all_quad(&self) -> IntQuadMesh {
+        let dominant = self.to_quad_dominant();
+        let polygons: Vec<&[usize]> = dominant
+            .quads
+            .iter()
+            .map(|q| q.as_slice())
+            .chain(dominant.triangles.iter().map(|t| t.as_slice()))
+            .collect();
+
+        let side = |polygon: &[usize], k: usize| {
+            let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+            (a.min(b), a.max(b))
+        };
+
+        // the middle of every side and the polygons on it, a side without a middle is not split
+        let mut sides: HashMap<(usize, usize), (Option<IntPoint>, Vec<usize>)> = HashMap::new();
+        for (i, polygon) in polygons.iter().enumerate() {
+            for k in 0..polygon.len() {
+                let (a, b) = side(polygon, k);
+                sides
+                    .entry((a, b))
+                    .or_insert_with(|| {
+                        let m = grid_point_on_segment(dominant.points[a], dominant.points[b], 0.5);
+                        (m, Vec::with_capacity(2))
+                    })
+                    .1
+                    .push(i);
+            }
+        }
+
+        // a kept polygon takes the middles off its sides, so its neighbors are checked again
+        let mut is_kept = vec![false; polygons.len()];
+        let mut stack: Vec<usize> = (0..polygons.len()).rev().collect();
+        while let Some(i) = stack.pop() {
+            if is_kept[i] {
+                continue;
+            }
+            let polygon = polygons[i];
+            let middles: Vec<Option<IntPoint>> = (0..polygon.len()).map(|k| sides[&side(polygon, k)].0).collect();
+            // a polygon without middles is kept as it is
+            if middles.iter().any(Option::is_some) {
+                let corners: Vec<IntPoint> = polygon.iter().map(|&i| dominant.points[i]).collect();
+                if is_valid_split(&corners, &middles) {
+                    continue;
+                }
+            }
+
+            is_kept[i] = true;
+            for k in 0..polygon.len() {
+                if let Some((middle, owners)) = sides.get_mut(&side(polygon, k)) {
+                    if middle.take().is_some() {
+                        stack.extend(owners.iter().filter(|&&j| j != i));
+                    }
+                }
+            }
+        }
+
+        let mut points = dominant.points;
+        let mut middles = HashMap::new();
+        let mut quads = Vec::with_capacity(4 * dominant.quads.len() + 3 * dominant.triangles.len());
+        let mut triangles = Vec::new();
+        for (polygon, &is_kept) in polygons.iter().zip(is_kept.iter()) {
+            let n = polygon.len();
+            if is_kept {
+                if n == 4 {
+                    quads.push([polygon[0], polygon[1], polygon[2], polygon[3]]);
+                } else {
+                    triangles.push([polygon[0], polygon[1], polygon[2]]);
+                }
+                continue;
+            }
+
+            let center = points.len();
+            points.push(center_point(polygon.iter().map(|&i| points[i])));
+
+            let m: Vec<Option<usize>> = (0..n)
+                .map(|k| {
+                    let key = side(polygon, k);
+                    let middle = sides[&key].0?;
+                    Some(*middles.entry(key).or_insert_with(|| {
+                        points.push(middle);
+                        points.len() - 1
+                    }))
+                })
+                .collect();
+
+            for (piece, is_quad) in pieces(n, |k| m[k].is_some()) {
+                let index = |slot| match slot {
+                    Slot::Corner(k) => polygon[k],
+                    Slot::Middle(k) => m[k].unwrap_or(center),
+                    Slot::Center => center,
+                };
+                if is_quad {
+                    quads.push(piece.map(index));
+                } else {
+                    triangles.push([index(piece[0]), index(piece[1]), index(piece[2])]);
+                }
+            }
+        }
+
+        IntQuadMesh {
+            points,
+            quads,
+            triangles,
+        }
+    }
+
+    /// The quad of the triangle `t` and its neighbor `n`.
+    #[inline]
+    fn quad(&self, t: usize, n: usize) -> [usize; 4] {
+        let abc = &self.triangles[t];
+        let bdc = &self.triangles[n];
+        let k = abc.opposite(n);
+        let a = abc.vertices[k].index;
+        let b = abc.vertices[(k + 1) % 3].index;
+        let c = abc.vertices[(k + 2) % 3].index;
+        let d = bdc.vertices[bdc.opposite(t)].index;
+        [a, b, d, c]
+    }
+
+    /// The minimal sine of a corner of the quad, zero or less if it is not convex.
+    #[inline]
+    fn quad_quality(&self, quad: [usize; 4]) -> f64 {
+        let mut quality = f64::MAX;
+        for i in 0..4 {
+            let p = self.points[quad[(i + 3) % 4]];
+            let o = self.points[quad[i]];
+            let q = self.points[quad[(i + 1) % 4]];
+            // a convex corner turns like the triangles, its area is negative
+            let area = -Triangle::area_two_point(p, o, q) as f64;
+            let len = (p.sqr_distance(o) as f64).sqrt() * (q.sqr_distance(o) as f64).sqrt();
+            quality = quality.min(area / len);
+        }
+        quality
+    }
+}
+
+/// A point of a piece of a split polygon.
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Corner(usize),
+    /// The middle of the side starting at the corner.
+    Middle(usize),
+    Center,
+}
+
+/// The pieces of a polygon with `n` corners split by its center and the middles of the sides `is_split` accepts.
+///
+/// A corner between two split sides gives a quad `[corner, middle, center, middle]`,
+/// other corners give triangles. A quad is returned with `true`, a triangle fills the first three slots.
+fn pieces(n: usize, is_split: impl Fn(usize) -> bool) -> Vec<([Slot; 4], bool)> {
+    let mut pieces = Vec::with_capacity(2 * n);
+    for k in 0..n {
+        let before = (k + n - 1) % n;
+        if is_split(before) && is_split(k) {
+            pieces.push((
+                [Slot::Corner(k), Slot::Middle(k), Slot::Center, Slot::Middle(before)],
+                true,
+            ));
+            continue;
+        }
+        if is_split(before) {
+            pieces.push((
+                [Slot::Middle(before), Slot::Corner(k), Slot::Center, Slot::Center],
+                false,
+            ));
+        }
+        let next = if is_split(k) {
+            Slot::Middle(k)
+        } else {
+            Slot::Corner((k + 1) % n)
+        };
+        pieces.push(([Slot::Corner(k), next, Slot::Center, Slot::Center], false));
+    }
+    pieces
+}
+
+/// True if the pieces of the polygon split by its rounded center and the given middles are valid:
+/// convex quads and triangles wound like the mesh.
+fn is_valid_split(corners: &[IntPoint], middles: &[Option<IntPoint>]) -> bool {
+    let center = center_point(corners.iter().copied());
+    pieces(corners.len(), |k| middles[k].is_some())
+        .into_iter()
+        .all(|(piece, is_quad)| {
+            let point = |slot| match slot {
+                Slot::Corner(k) => corners[k],
+                Slot::Middle(k) => middles[k].unwrap_or(center),
+                Slot::Center => center,
+            };
+            if is_quad {
+                is_convex(piece.map(point))
+            } else {
+                Triangle::area_two_point(point(piece[0]), point(piece[1]), point(piece[2])) < 0
+            }
+        })
+}
+
+/// True if every corner of the quad turns like the mesh triangles.
+#[inline]
+fn is_convex(quad: [IntPoint; 4]) -> bool {
+    (0..4).all(|i| Triangle::area_two_point(quad[(i + 3) % 4], quad[i], quad[(i + 1) % 4]) < 0)
+}
+
+#[inline]
+fn center_point(points: impl Iterator<Item = IntPoint>) -> IntPoint {
+    let (mut x, mut y, mut n) = (0i64, 0i64, 0i64);
+    for p in points {
+        x += p.x as i64;
+        y += p.y as i64;
+        n += 1;
+    }
+    IntPoint::new(
+        (x as f64 / n as f64).round() as i32,
+        (y as f64 / n as f64).round() as i32,
+    )
+}
+
+/// A maximum matching of the triangles by Edmonds' blossom algorithm, started from a greedy one.
+struct QuadMatching {
+    // the triangles a triangle may be paired with
+    candidates: Vec<[usize; 3]>,
+    mates: Vec<usize>,
+    parents: Vec<usize>,
+    bases: Vec<usize>,
+    is_even: Vec<bool>,
+    is_touched: Vec<bool>,
+    is_blossom: Vec<bool>,
+    is_marked: Vec<bool>,
+    touched: Vec<usize>,
+    queue: Vec<usize>,
+}
+
+impl QuadMatching {
+    fn new(delaunay: &IntDelaunay) -> Self {
+        let n = delaunay.triangles.len();
+        let mut candidates = vec![[NIL_INDEX; 3]; n];
+        let mut pairs = Vec::new();
+        for (t, abc) in delaunay.triangles.iter().enumerate() {
+            for (k, &m) in abc.neighbors.iter().enumerate() {
+                if m >= n {
+                    continue;
+                }
+                let b = abc.vertices[(k + 1) % 3].index;
+                let c = abc.vertices[(k + 2) % 3].index;
+                if delaunay.constraints.contains(b, c) {
+                    continue;
+                }
+                let quality = delaunay.quad_quality(delaunay.quad(t, m));
+                if quality > 0.0 {
+                    candidates[t][k] = m;
+                    if t < m {
+                        pairs.push((quality, t, m));
+                    }
+                }
+            }
+        }
+
+        // the best quads go first
+        pairs.sort_unstable_by(|x, y| y.0.total_cmp(&x.0).then(x.1.cmp(&y.1)).then(x.2.cmp(&y.2)));
+        let mut mates = vec![NIL_INDEX; n];
+        for &(_, t, m) in pairs.iter() {
+            if mates[t] == NIL_INDEX && mates[m] == NIL_INDEX {
+                mates[t] = m;
+                mates[m] = t;
+            }
+        }
+
+        Self {
+            candidates,
+            mates,
+            parents: vec![NIL_INDEX; n],
+            bases: (0..n).collect(),
+            is_even: vec![false; n],
+            is_touched: vec![false; n],
+            is_blossom: vec![false; n],
+            is_marked: vec![false; n],
+            touched: Vec::new(),
+            queue: Vec::new(),
+        }
+    }
+
+    fn solve(mut self) -> Vec<usize> {
+        // a vertex without an augmenting path never gets one later, one pass is enough
+        for root in 0..self.mates.len() {
+            if self.mates[root] != NIL_INDEX || self.candidates[root].iter().all(|&c| c == NIL_INDEX) {
+                continue;
+            }
+            let mut v = self.find_path(root);
+            while v != NIL_INDEX {
+                let pv = self.parents[v];
+                let next = self.mates[pv];
+                self.mates[v] = pv;
+                self.mates[pv] = v;
+                v = next;
+            }
+        }
+        self.mates
+    }
+
+    #[inline]
+    fn touch(&mut self, v: usize) {
+        if !self.is_touched[v] {
+            self.is_touched[v] = true;
+            self.touched.push(v);
+        }
+    }
+
+    /// Grows the alternating tree from `root`, returns the free end of an augmenting path or NIL.
+    fn find_path(&mut self, root: usize) -> usize {
+        for &v in self.touched.iter() {
+            self.parents[v] = NIL_INDEX;
+            self.bases[v] = v;
+            self.is_even[v] = false;
+            self.is_touched[v] = false;
+        }
+        self.touched.clear();
+        self.queue.clear();
+
+        self.is_even[root] = true;
+        self.touch(root);
+        self.queue.push(root);
+
+        let mut head = 0;
+        while head < self.queue.len() {
+            let v = self.queue[head];
+            head += 1;
+            for to in self.candidates[v] {
+                if to == NIL_INDEX || self.bases[v] == self.bases[to] || self.mates[v] == to {
+                    continue;
+                }
+                if to == root || self.mates[to] != NIL_INDEX && self.parents[self.mates[to]] != NIL_INDEX {
+                    // an odd cycle, contract it into its base
+                    let base = self.lca(v, to);
+                    for &u in self.touched.iter() {
+                        self.is_blossom[u] = false;
+                    }
+                    self.mark_path(v, base, to);
+                    self.mark_path(to, base, v);
+                    for i in 0..self.touched.len() {
+                        let u = self.touched[i];
+                        if self.is_blossom[self.bases[u]] {
+                            self.bases[u] = base;
+                            if !self.is_even[u] {
+                                self.is_even[u] = true;
+                                self.queue.push(u);
+                            }
+                        }
+                    }
+                } else if self.parents[to] == NIL_INDEX {
+                    self.parents[to] = v;
+                    self.touch(to);
+                    let mate = self.mates[to];
+                    if mate == NIL_INDEX {
+                        return to;
+                    }
+                    self.is_even[mate] = true;
+                    self.touch(mate);
+                    self.queue.push(mate);
+                }
+            }
+        }
+
+        NIL_INDEX
+    }
+
+    /// The base of the blossom closed by the edge `ab`.
+    fn lca(&mut self, mut a: usize, mut b: usize) -> usize {
+        let mut path = Vec::new();
+        loop {
+            a = self.bases[a];
+            self.is_marked[a] = true;
+            path.push(a);
+            if self.mates[a] == NIL_INDEX {
+                break;
+            }
+            a = self.parents[self.mates[a]];
+        }
+        loop {
+            b = self.bases[b];
+            if self.is_marked[b] {
+                break;
+            }
+            b = self.parents[self.mates[b]];
+        }
+        for v in path {
+            self.is_marked[v] = false;
+        }
+        b
+    }
+
+    fn mark_path(&mut self, mut v: usize, base: usize, mut child: usize) {
+        while self.bases[v] != base {
+            let mate = self.mates[v];
+            self.is_blossom[self.bases[v]] = true;
+            self.is_blossom[self.bases[mate]] = true;
+            self.parents[v] = child;
+            child = mate;
+            v = self.parents[mate];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::advanced::quad::IntQuadMesh;
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    // every quad is convex and the polygons cover the mesh
+    fn check_mesh(mesh: &IntQuadMesh, area: i64) {
+        let mut sum = 0;
+        for quad in mesh.quads.iter() {
+            let points: IntPath = quad.iter().map(|&i| mesh.points[i]).collect();
+            for i in 0..4 {
+                assert!(Triangle::area_two_point(points[(i + 3) % 4], points[i], points[(i + 1) % 4]) < 0);
+            }
+            sum += points.area_two();
+        }
+        for triangle in mesh.triangles.iter() {
+            let [a, b, c] = triangle.map(|i| mesh.points[i]);
+            sum += Triangle::area_two_point(a, b, c);
+        }
+        assert_eq!(sum, area);
+    }
+
+    // a side of a polygon is a side of another one or lies on a boundary edge of the mesh
+    fn check_conforming(delaunay: &IntDelaunay, mesh: &IntQuadMesh) {
+        let polygons: Vec<Vec<usize>> = mesh
+            .quads
+            .iter()
+            .map(|q| q.to_vec())
+            .chain(mesh.triangles.iter().map(|t| t.to_vec()))
+            .collect();
+        let mut sides = std::collections::HashSet::new();
+        for polygon in polygons.iter() {
+            for k in 0..polygon.len() {
+                assert!(sides.insert((polygon[k], polygon[(k + 1) % polygon.len()])));
+            }
+        }
+        for &(a, b) in sides.iter() {
+            if sides.contains(&(b, a)) {
+                continue;
+            }
+            let [a, b] = [a, b].map(|i| mesh.points[i]);
+            let is_on_boundary = delaunay.triangles.iter().any(|t| {
+                (0..3).any(|k| {
+                    let p = t.vertices[(k + 1) % 3].point;
+                    let q = t.vertices[(k + 2) % 3].point;
+                    let is_on = |x: IntPoint| {
+                        Triangle::area_two_point(p, q, x) == 0 && x.subtract(p).dot_product(x.subtract(q)) <= 0
+                    };
+                    t.neighbors[k] >= delaunay.triangles.len() && is_on(a) && is_on(b)
+                })
+            });
+            assert!(is_on_boundary);
+        }
+    }
+
+    // the greedy part is maximal: no two free neighbors may form a quad
+    fn check_maximal(delaunay: &IntDelaunay, mesh: &IntQuadMesh) {
+        let free: Vec<usize> = (0..delaunay.triangles.len())
+            .filter(|&t| {
+                let abc = delaunay.triangles[t].vertices.map(|v| v.index);
+                mesh.triangles.contains(&abc)
+            })
+            .collect();
+        for &t in free.iter() {
+            for &n in delaunay.triangles[t].neighbors.iter() {
+                if free.contains(&n) {
+                    assert!(delaunay.quad_quality(delaunay.quad(t, n)) <= 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [1200, 0], [1200, 1200], [0, 1200]]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_quad_dominant();
+        assert_eq!(mesh.quads.len(), 1);
+        assert!(mesh.triangles.is_empty());
+        check_mesh(&mesh, contour.area_two());
+
+        let mesh = delaunay.to_all_quad();
+        assert_eq!(mesh.quads.len(), 4);
+        assert_eq!(mesh.points.len(), 9);
+        check_mesh(&mesh, contour.area_two());
+    }
+
+    #[test]
+    fn test_1() {
+        let contour = path(&[[0, 0], [1200, 0], [600, 900]]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_quad_dominant();
+        assert!(mesh.quads.is_empty());
+        assert_eq!(mesh.triangles.len(), 1);
+
+        let mesh = delaunay.to_all_quad();
+        assert_eq!(mesh.quads.len(), 3);
+        check_mesh(&mesh, contour.area_two());
+    }
+
+    #[test]
+    fn test_3() {
+        // no side has a grid point inside, the triangle is kept
+        let contour = path(&[[0, 0], [1, 0], [0, 1]]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_all_quad();
+        assert!(mesh.quads.is_empty());
+        assert_eq!(mesh.triangles.len(), 1);
+        assert_eq!(mesh.points.len(), 3);
+        check_mesh(&mesh, contour.area_two());
+
+        // the rounded center (1, 1) is on a side, the triangle is kept
+        let contour = path(&[[0, 0], [2, 0], [0, 2]]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_all_quad();
+        assert!(mesh.quads.is_empty());
+        assert_eq!(mesh.triangles.len(), 1);
+        assert_eq!(mesh.points.len(), 3);
+
+        // the side without grid points gives triangles, the rest is split into quads
+        let contour = path(&[
+            [0, 0],
+            [1200, 0],
+            [2400, 0],
+            [3600, 0],
+            [3601, 600],
+            [3600, 1200],
+            [2400, 1200],
+            [1200, 1200],
+            [0, 1200],
+        ]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_all_quad();
+        assert!(mesh.quads.len() >= 9);
+        assert!(!mesh.triangles.is_empty());
+        check_mesh(&mesh, contour.area_two());
+        check_conforming(&delaunay, &mesh);
+    }
+
+    #[test]
+    fn test_2() {
+        // a strip of six triangles gives three quads
+        let contour = path(&[
+            [0, 0],
+            [100, 0],
+            [200, 0],
+            [300, 0],
+            [300, 100],
+            [200, 100],
+            [100, 100],
+            [0, 100],
+        ]);
+        let delaunay = contour.triangulate().into_delaunay();
+        let mesh = delaunay.to_quad_dominant();
+        assert_eq!(mesh.quads.len(), 3);
+        assert!(mesh.triangles.is_empty());
+        check_mesh(&mesh, contour.area_two());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let contour = path(&[[0, 0], [4096, 0], [4096, 4096], [0, 4096]]);
+            let steiner: Vec<IntPoint> = (0..rng.random_range(0..40))
+                .map(|_| IntPoint::new(rng.random_range(1..4096), rng.random_range(1..4096)))
+                .collect();
+            let delaunay = contour.triangulate_with_steiner_points(&steiner).into_delaunay();
+
+            let mesh = delaunay.to_quad_dominant();
+            assert_eq!(2 * mesh.quads.len() + mesh.triangles.len(), delaunay.triangles.len());
+            check_mesh(&mesh, delaunay.area());
+            check_maximal(&delaunay, &mesh);
+
+            let all = delaunay.to_all_quad();
+            check_mesh(&all, delaunay.area());
+            check_conforming(&delaunay, &all);
+            assert_eq!(&all.points[..delaunay.points.len()], delaunay.points.as_slice());
+        }
+    }
+
+    // the biggest number of pairs by trying all of them
+    fn max_pairs(delaunay: &IntDelaunay, t: usize, used: &mut [bool]) -> usize {
+        if t == used.len() {
+            return 0;
+        }
+        if used[t] {
+            return max_pairs(delaunay, t + 1, used);
+        }
+        let mut best = max_pairs(delaunay, t + 1, used);
+        used[t] = true;
+        for &n in delaunay.triangles[t].neighbors.iter() {
+            if n < used.len() && !used[n] && delaunay.quad_quality(delaunay.quad(t, n)) > 0.0 {
+                used[n] = true;
+                best = best.max(1 + max_pairs(delaunay, t + 1, used));
+                used[n] = false;
+            }
+        }
+        used[t] = false;
+        best
+    }
+
+    #[test]
+    fn test_random_1() {
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let n = rng.random_range(3..=10);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-100..=100), rng.random_range(-100..=100)))
+                .collect();
+            let delaunay = contour.triangulate().into_delaunay();
+            if delaunay.triangles.len() > 16 {
+                continue;
+            }
+
+            let mesh = delaunay.to_quad_dominant();
+            let mut used = vec![false; delaunay.triangles.len()];
+            assert_eq!(mesh.quads.len(), max_pairs(&delaunay, 0, &mut used));
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::triangle::IntTriangle;
+use crate::index::Index;
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::int::point::IntPoint;
+
+/// A read-only view over the adjacency of a triangle mesh.
+///
+/// Triangles and points are addressed by their indices in the mesh.
+/// Edge `i` of a triangle is the edge opposite its vertex `i`.
+///
+/// Created by [`RawIntTriangulation::topology`] or [`IntDelaunay::topology`]
+/// (and the float [`RawTriangulation::topology`](crate::float::triangulation::RawTriangulation::topology),
+/// [`Delaunay::topology`](crate::float::delaunay::Delaunay::topology)).
+pub struct Topology<'a> {
+    triangles: &'a [IntTriangle],
+    points: &'a [IntPoint],
+    // incident triangles of the vertex `v` are `incident[offsets[v]..offsets[v + 1]]`
+    offsets: Vec<usize>,
+    incident: Vec<usize>,
+}
+
+/// A chain of triangles around a vertex, see [`Topology::vertex_triangles`].
+pub(crate) struct Fan {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) is_closed: bool,
+}
+
+impl<'a> Topology<'a> {
+    fn new(triangles: &'a [IntTriangle], points: &'a [IntPoint]) -> Self {
+        let mut offsets = vec![0; points.len() + 1];
+        for t in triangles.iter() {
+            for v in t.vertices.iter() {
+                offsets[v.index + 1] += 1;
+            }
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        let mut fill = offsets.clone();
+        let mut incident = vec![0; 3 * triangles.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            for v in t.vertices.iter() {
+                incident[fill[v.index]] = i;
+                fill[v.index] += 1;
+            }
+        }
+
+        Self {
+            triangles,
+            points,
+            offsets,
+            incident,
+        }
+    }
+
+    /// Returns the number of triangles.
+    #[inline]
+    pub fn triangles_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Returns the mesh points.
+    #[inline]
+    pub fn points(&self) -> &'a [IntPoint] {
+        self.points
+    }
+
+    /// Returns the point indices of the triangle `triangle`, in the order of `triangle_indices`.
+    #[inline]
+    pub fn triangle(&self, triangle: usize) -> [usize; 3] {
+        self.triangles[triangle].vertices.map(|v| v.index)
+    }
+
+    /// Iterates over the point indices of all triangles.
+    #[inline]
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.triangles.iter().map(|t| t.vertices.map(|v| v.index))
+    }
+
+    /// Returns the triangle sharing the edge `edge` of `triangle`, `None` on the boundary.
+    #[inline]
+    pub fn neighbor(&self, triangle: usize, edge: usize) -> Option<usize> {
+        let neighbor = self.triangles[triangle].neighbors[edge];
+        if neighbor.is_not_nil() {
+            Some(neighbor)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the vertex of the neighbor across the edge `edge` of `triangle`
+    /// that does not lie on this edge, `None` on the boundary.
+    #[inline]
+    pub fn opposite_vertex(&self, triangle: usize, edge: usize) -> Option<usize> {
+        let neighbor = &self.triangles[self.neighbor(triangle, edge)?];
+        let t = &self.triangles[triangle];
+        let a = t.vertices[(edge + 1) % 3].index;
+        let b = t.vertices[(edge + 2) % 3].index;
+        Some(neighbor.vertices[neighbor.other_vertex(a, b)].index)
+    }
+
+    /// Returns true if the edge `edge` of `triangle` has no neighbor.
+    #[inline]
+    pub fn is_boundary_edge(&self, triangle: usize, edge: usize) -> bool {
+        !self.triangles[triangle].neighbors[edge].is_not_nil()
+    }
+
+    /// Returns true if the point `vertex` lies on a boundary edge.
+    pub fn is_boundary_vertex(&self, vertex: usize) -> bool {
+        self.incident(vertex).iter().any(|&i| {
+            let t = &self.triangles[i];
+            let position = t.vertex_position(vertex);
+            !t.neighbors[(position + 1) % 3].is_not_nil() || !t.neighbors[(position + 2) % 3].is_not_nil()
+        })
+    }
+
+    /// Iterates over all boundary edges as pairs of point indices, in triangle order.
+    pub fn boundary_edges(&self) -> impl Iterator<Item = [usize; 2]> + '_ {
+        self.triangles.iter().flat_map(|t| {
+            (0..3).filter(|&i| !t.neighbors[i].is_not_nil()).map(|i| {
+                [t.vertices[(i + 1) % 3].index, t.vertices[(i + 2) % 3].index]
+            })
+        })
+    }
+
+    /// Returns the triangles around the point `vertex` in rotation order.
+    ///
+    /// If the vertex joins several separate fans (e.g. a hole touching the outer contour),
+    /// the fans are listed one after another.
+    pub fn vertex_triangles(&self, vertex: usize) -> Vec<usize> {
+        let mut triangles = Vec::new();
+        self.collect_fans(vertex, &mut triangles);
+        triangles
+    }
+
+    /// Returns the points connected to the point `vertex` by an edge, in rotation order.
+    ///
+    /// The order follows [`Topology::vertex_triangles`].
+    pub fn vertex_neighbors(&self, vertex: usize) -> Vec<usize> {
+        let mut triangles = Vec::new();
+        let fans = self.collect_fans(vertex, &mut triangles);
+
+        let mut result = Vec::with_capacity(triangles.len() + fans.len());
+        for fan in fans.iter() {
+            let first = &self.triangles[triangles[fan.start]];
+            if !fan.is_closed {
+                result.push(first.vertices[(first.vertex_position(vertex) + 1) % 3].index);
+            }
+            for &i in triangles[fan.start..fan.end].iter() {
+                let t = &self.triangles[i];
+                result.push(t.vertices[(t.vertex_position(vertex) + 2) % 3].index);
+            }
+        }
+        result
+    }
+
+    #[inline]
+    fn incident(&self, vertex: usize) -> &[usize] {
+        &self.incident[self.offsets[vertex]..self.offsets[vertex + 1]]
+    }
+
+    /// The next triangle around `vertex`, crossing the edge from `vertex` to the next vertex.
+    #[inline]
+    fn next_around(&self, triangle: usize, vertex: usize) -> Option<usize> {
+        let t = &self.triangles[triangle];
+        self.neighbor(triangle, (t.vertex_position(vertex) + 1) % 3)
+    }
+
+    #[inline]
+    fn prev_around(&self, triangle: usize, vertex: usize) -> Option<usize> {
+        let t = &self.triangles[triangle];
+        self.neighbor(triangle, (t.vertex_position(vertex) + 2) % 3)
+    }
+
+    /// Appends the triangles around `vertex` to `triangles`, fan after fan, and returns the fan ranges.
+    pub(crate) fn collect_fans(&self, vertex: usize, triangles: &mut Vec<usize>) -> Vec<Fan> {
+        let incident = self.incident(vertex);
+        triangles.reserve(incident.len());
+
+        let mut fans = Vec::with_capacity(1);
+        for &i in incident.iter() {
+            if triangles.contains(&i) {
+                continue;
+            }
+
+            // rewind to the first triangle of an open fan
+            let mut first = i;
+            while let Some(prev) = self.prev_around(first, vertex) {
+                if prev == i {
+                    break;
+                }
+                first = prev;
+            }
+
+            let start = triangles.len();
+            let mut t = first;
+            let is_closed = loop {
+                triangles.push(t);
+                match self.next_around(t, vertex) {
+                    Some(next) if next == first => break true,
+                    Some(next) => t = next,
+                    None => break false,
+                }
+            };
+            fans.push(Fan {
+                start,
+                end: triangles.len(),
+                is_closed,
+            });
+        }
+
+        fans
+    }
+}
+
+impl RawIntTriangulation {
+    /// Returns a read-only [`Topology`] view for walking the mesh adjacency.
+    #[inline]
+    pub fn topology(&self) -> Topology<'_> {
+        Topology::new(&self.triangles, &self.points)
+    }
+}
+
+impl IntDelaunay {
+    /// Returns a read-only [`Topology`] view for walking the mesh adjacency.
+    #[inline]
+    pub fn topology(&self) -> Topology<'_> {
+        Topology::new(&self.triangles, &self.points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let raw = contour.triangulate_with_steiner_points(&[IntPoint::new(5, 5)]);
+        let topology = raw.topology();
+
+        assert_eq!(topology.triangles_count(), 4);
+        let center = topology.points().iter().position(|p| *p == IntPoint::new(5, 5)).unwrap();
+        assert!(!topology.is_boundary_vertex(center));
+        assert_eq!(topology.vertex_triangles(center).len(), 4);
+
+        let mut ring = topology.vertex_neighbors(center);
+        assert_eq!(ring.len(), 4);
+        ring.sort_unstable();
+        ring.dedup();
+        assert_eq!(ring.len(), 4);
+
+        assert_eq!(topology.boundary_edges().count(), 4);
+        for v in 0..topology.points().len() {
+            assert_eq!(topology.is_boundary_vertex(v), v != center);
+        }
+    }
+
+    #[test]
+    fn test_1() {
+        // the corner has an open fan
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let raw = contour.triangulate_with_steiner_points(&[IntPoint::new(5, 5)]);
+        let topology = raw.topology();
+
+        let corner = topology.points().iter().position(|p| *p == IntPoint::new(0, 0)).unwrap();
+        let triangles = topology.vertex_triangles(corner);
+        let ring = topology.vertex_neighbors(corner);
+        assert_eq!(ring.len(), triangles.len() + 1);
+
+        // consecutive ring vertices share a triangle with the corner
+        for (w, &t) in ring.windows(2).zip(triangles.iter()) {
+            let abc = topology.triangle(t);
+            assert!(abc.contains(&corner) && abc.contains(&w[0]) && abc.contains(&w[1]));
+        }
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let n = rng.random_range(3..20);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-20..=20), rng.random_range(-20..=20)))
+                .collect();
+
+            let delaunay = contour.triangulate().into_delaunay();
+            let topology = delaunay.topology();
+
+            let mut incident = 0;
+            for v in 0..topology.points().len() {
+                let triangles = topology.vertex_triangles(v);
+                incident += triangles.len();
+                for &t in triangles.iter() {
+                    assert!(topology.triangle(t).contains(&v));
+                }
+            }
+            assert_eq!(incident, 3 * topology.triangles_count());
+
+            for t in 0..topology.triangles_count() {
+                for e in 0..3 {
+                    match topology.neighbor(t, e) {
+                        Some(n) => {
+                            let o = topology.opposite_vertex(t, e).unwrap();
+                            assert!(topology.triangle(n).contains(&o));
+                            assert!(!topology.triangle(t).contains(&o));
+                        }
+                        None => assert!(topology.is_boundary_edge(t, e)),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use i_overlay::i_float::int::point::IntPoint;
 use crate::advanced::delaunay::IntDelaunay;
-use crate::int::triangulation::{IndexType, IntTriangulation};
+use crate::int::error::TriangulationError;
+use crate::int::provenance::VertexSource;
+use crate::int::triangulation::{collect_indices, IndexType, IntTriangulation};
 
 impl IntDelaunay {
     #[inline]
@@ -8,18 +10,49 @@ impl IntDelaunay {
         &self.points
     }
 
+    /// Returns the index of the source shape for every triangle, see [`RawIntTriangulation::shape_ids`].
+    ///
+    /// Delaunay flips and refinement keep the ids, a split triangle passes its id to the new triangles.
+    ///
+    /// [`RawIntTriangulation::shape_ids`]: crate::int::triangulation::RawIntTriangulation::shape_ids
     #[inline]
-    pub fn triangle_indices<I: IndexType>(&self) -> Vec<I> {
-        let mut result = Vec::with_capacity(3 * self.triangles.len());
-        for t in &self.triangles {
-            let v = &t.vertices;
-            let i0 = I::try_from(v[0].index).unwrap_or(I::ZERO);
-            let i1 = I::try_from(v[1].index).unwrap_or(I::ZERO);
-            let i2 = I::try_from(v[2].index).unwrap_or(I::ZERO);
-            
-            result.extend_from_slice(&[i0, i1, i2]);
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        if self.shape_ids.is_empty() {
+            None
+        } else {
+            Some(&self.shape_ids)
+        }
+    }
+
+    /// Returns the origin of every point, see [`RawIntTriangulation::with_vertex_sources`].
+    ///
+    /// Points inserted by refinement are marked as [`VertexSource::Refinement`].
+    ///
+    /// [`RawIntTriangulation::with_vertex_sources`]: crate::int::triangulation::RawIntTriangulation::with_vertex_sources
+    #[inline]
+    pub fn vertex_sources(&self) -> Option<&[VertexSource]> {
+        if self.vertex_sources.is_empty() {
+            None
+        } else {
+            Some(&self.vertex_sources)
         }
-        result
+    }
+
+    /// Returns a flat list of triangle vertex indices.
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all points, see [`IntDelaunay::try_triangle_indices`].
+    #[inline]
+    pub fn triangle_indices<I: IndexType>(&self) -> Vec<I> {
+        self.try_triangle_indices().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Returns a flat list of triangle vertex indices.
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_triangle_indices<I: IndexType>(&self) -> Result<Vec<I>, TriangulationError> {
+        collect_indices(&self.triangles, self.points.len())
     }
 
     #[inline]
@@ -27,6 +60,19 @@ impl IntDelaunay {
         IntTriangulation {
             indices: self.triangle_indices(),
             points: self.points,
+            shape_ids: self.shape_ids,
         }
     }
+
+    /// Converts the mesh into an index-based [`IntTriangulation`].
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_into_triangulation<I: IndexType>(self) -> Result<IntTriangulation<I>, TriangulationError> {
+        Ok(IntTriangulation {
+            indices: self.try_triangle_indices()?,
+            points: self.points,
+            shape_ids: self.shape_ids,
+        })
+    }
 }
\ No newline at end of file
@@ -0,0 +1,469 @@
+use crate::advanced::centroid::middle;
+use crate::advanced::delaunay::IntDelaunay;
+use crate::advanced::edges::boundary_contours;
+use crate::int::constraint::div_round;
+use crate::int::triangulatable::IntTriangulatable;
+use i_overlay::core::fill_rule::FillRule;
+use i_overlay::core::overlay::IntOverlayOptions;
+use i_overlay::core::simplify::Simplify;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use i_overlay::i_shape::int::shape::{IntContour, IntShape, IntShapes};
+use i_overlay::string::overlay::StringOverlay;
+use i_overlay::string::rule::StringRule;
+
+/// A Voronoi cell of a mesh point, see [`IntDelaunay::to_voronoi_cells`].
+#[derive(Debug, Clone)]
+pub struct IntVoronoiCell {
+    /// The index of the generating point in [`IntDelaunay::points`].
+    pub site: usize,
+    /// The cell clipped by the mesh boundary, it may fall apart into several shapes.
+    pub shapes: IntShapes,
+    /// The sites of the neighboring cells in rotation order, i.e. the points connected to `site` by a mesh edge.
+    pub neighbors: Vec<usize>,
+}
+
+impl IntDelaunay {
+    /// Builds the Voronoi cell of every mesh point from the circumcenters of its triangles.
+    ///
+    /// Cells are clipped by the mesh boundary, so holes and concave parts stay empty.
+    /// Cells reduced to nothing by clipping are skipped, unused points have no cell.
+    ///
+    /// A circumcenter is found exactly in integers and rounded once to the nearest grid point,
+    /// so a cell vertex is off by at most half a unit on each axis. The rounded center of a triangle
+    /// is shared by all the cells around it, so neighboring cells still fit together.
+    ///
+    /// The cells tile the shape only where the triangles are truly Delaunay. Boundary and constrained edges
+    /// are never flipped by [`RawIntTriangulation::into_delaunay`], next to them the cells may overlap.
+    ///
+    /// [`RawIntTriangulation::into_delaunay`]: crate::int::triangulation::RawIntTriangulation::into_delaunay
+    pub fn to_voronoi_cells(&self) -> Vec<IntVoronoiCell> {
+        let topology = self.topology();
+        let domain = Domain::new(boundary_contours(&self.triangles, &self.points));
+        let centers: Vec<[f64; 2]> = self
+            .triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                circumscribed_center_exact(a, b, c)
+            })
+            .collect();
+
+        let mut cells = Vec::with_capacity(self.points.len());
+        let mut outer_cells = Vec::new();
+        let mut triangles = Vec::with_capacity(16);
+        for site in 0..self.points.len() {
+            triangles.clear();
+            let fans = topology.collect_fans(site, &mut triangles);
+            if fans.is_empty() {
+                continue;
+            }
+
+            let mut is_inside = true;
+            let contours: Vec<IntContour> = fans
+                .iter()
+                .map(|fan| {
+                    let fan_triangles = &triangles[fan.start..fan.end];
+                    self.cell_contour(site, fan_triangles, fan.is_closed, &centers, &domain, &mut is_inside)
+                })
+                .collect();
+
+            if !is_inside {
+                outer_cells.push((site, contours));
+                continue;
+            }
+
+            let shapes = contours.simplify(FillRule::NonZero, IntOverlayOptions::default());
+            if !shapes.is_empty() {
+                cells.push(IntVoronoiCell {
+                    site,
+                    shapes,
+                    neighbors: topology.vertex_neighbors(site),
+                });
+            }
+        }
+
+        // the cells that may leave the mesh are clipped all together
+        let clipped = domain.clip(&outer_cells);
+        for ((site, _), shapes) in outer_cells.into_iter().zip(clipped) {
+            if !shapes.is_empty() {
+                cells.push(IntVoronoiCell {
+                    site,
+                    shapes,
+                    neighbors: topology.vertex_neighbors(site),
+                });
+            }
+        }
+        cells.sort_unstable_by_key(|cell| cell.site);
+
+        cells
+    }
+
+    /// Joins the circumcenters of a fan of triangles around `site`.
+    /// An open fan is closed by the site and the middles of its boundary edges.
+    fn cell_contour(
+        &self,
+        site: usize,
+        fan: &[usize],
+        is_closed: bool,
+        centers: &[[f64; 2]],
+        domain: &Domain,
+        is_inside: &mut bool,
+    ) -> IntContour {
+        let p = self.points[site];
+        let mut contour = Vec::with_capacity(fan.len() + 3);
+        if !is_closed {
+            let first = &self.triangles[fan[0]];
+            let next = first.vertices[(first.vertex_position(site) + 1) % 3].point;
+            contour.push(to_f64(p));
+            contour.push(to_f64(middle(p, next)));
+        }
+
+        for &i in fan.iter() {
+            let [a, b, c] = self.triangles[i].vertices.map(|v| v.point);
+            let center = centers[i];
+            // a center inside its own triangle keeps the cell inside the fan
+            *is_inside &= domain.is_near(center) && Triangle::is_contain_point(to_int(center), a, b, c);
+            contour.push(center);
+        }
+
+        if !is_closed {
+            let last = &self.triangles[fan[fan.len() - 1]];
+            let prev = last.vertices[(last.vertex_position(site) + 2) % 3].point;
+            contour.push(to_f64(middle(p, prev)));
+        }
+
+        if !contour.iter().all(|&p| domain.is_near(p)) {
+            // centers of nearly flat triangles may not fit into int coordinates
+            contour = domain.cut_far(contour);
+        }
+
+        // cocircular points share a center
+        let mut contour: IntContour = contour.into_iter().map(to_int).collect();
+        contour.dedup();
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        contour
+    }
+}
+
+/// The mesh boundary loops.
+struct Domain {
+    contours: Vec<IntContour>,
+    // the bounding box of the mesh grown by its size, everything outside is clipped anyway
+    near: [[f64; 2]; 2],
+}
+
+impl Domain {
+    fn new(contours: Vec<IntContour>) -> Self {
+        let near = if let Some([min, max]) = contours.iter().map(|c| bounding_box(c)).reduce(union_box) {
+            let size = (max.x as f64 - min.x as f64).max(max.y as f64 - min.y as f64) + 1.0;
+            let limit = i32::MAX as f64;
+            [
+                [(min.x as f64 - size).max(-limit), (min.y as f64 - size).max(-limit)],
+                [(max.x as f64 + size).min(limit), (max.y as f64 + size).min(limit)],
+            ]
+        } else {
+            [[0.0; 2]; 2]
+        };
+        Self { contours, near }
+    }
+
+    #[inline]
+    fn is_near(&self, p: [f64; 2]) -> bool {
+        let [min, max] = self.near;
+        min[0] <= p[0] && p[0] <= max[0] && min[1] <= p[1] && p[1] <= max[1]
+    }
+
+    /// Clips the contour by the near box, one box side after another.
+    fn cut_far(&self, mut contour: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+        let [min, max] = self.near;
+        let sides = [(0, min[0], 1.0), (0, max[0], -1.0), (1, min[1], 1.0), (1, max[1], -1.0)];
+        let mut buffer = Vec::with_capacity(contour.len() + 4);
+        for (axis, value, sign) in sides {
+            buffer.clear();
+            let inside = |p: &[f64; 2]| sign * (p[axis] - value) >= 0.0;
+            for (i, a) in contour.iter().enumerate() {
+                let b = &contour[(i + 1) % contour.len()];
+                if inside(a) {
+                    buffer.push(*a);
+                }
+                if inside(a) != inside(b) {
+                    let t = (value - a[axis]) / (b[axis] - a[axis]);
+                    let mut p = [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])];
+                    p[axis] = value;
+                    buffer.push([p[0].round(), p[1].round()]);
+                }
+            }
+            std::mem::swap(&mut contour, &mut buffer);
+        }
+        contour
+    }
+
+    /// Clips the cells by the mesh boundary in one go: the borders of all the cells slice the mesh
+    /// into pieces and every piece goes to the cells around it.
+    fn clip(&self, cells: &[(usize, Vec<IntContour>)]) -> Vec<IntShapes> {
+        let mut shapes: Vec<IntShapes> = vec![Vec::new(); cells.len()];
+        if cells.is_empty() {
+            return shapes;
+        }
+
+        let segments = cells.iter().flat_map(|(_, c)| c.iter()).chain(self.contours.iter());
+        let mut overlay = StringOverlay::new(segments.map(Vec::len).sum());
+        overlay.add_shape_contours(&self.contours);
+        for (_, contours) in cells.iter() {
+            overlay.add_string_contours(contours);
+        }
+        let pieces = overlay.into_graph(FillRule::NonZero).extract_shapes(StringRule::Slice);
+
+        // cell boxes sorted by their left side
+        let mut boxes: Vec<_> = cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, contours))| {
+                let non_empty = contours.iter().filter(|c| !c.is_empty());
+                Some((i, non_empty.map(|c| bounding_box(c)).reduce(union_box)?))
+            })
+            .collect();
+        boxes.sort_unstable_by_key(|(_, b)| b[0].x);
+        let widths = boxes.iter().map(|(_, b)| b[1].x as i64 - b[0].x as i64);
+        let width = widths.max().unwrap_or(0);
+
+        for piece in pieces {
+            let p = if let Some(p) = inner_point(&piece) {
+                p
+            } else {
+                continue;
+            };
+
+            let start = boxes.partition_point(|(_, b)| 3 * (b[0].x as i64 + width) < p[0]);
+            for (i, b) in boxes[start..].iter() {
+                if 3 * b[0].x as i64 > p[0] {
+                    break;
+                }
+                let is_in_box = p[0] <= 3 * b[1].x as i64 && 3 * b[0].y as i64 <= p[1] && p[1] <= 3 * b[1].y as i64;
+                if is_in_box && cells[*i].1.iter().map(|c| winding(c, p)).sum::<i32>() != 0 {
+                    shapes[*i].push(piece.clone());
+                }
+            }
+        }
+
+        // a cell crossed by the borders of its neighbors is made of several pieces
+        for cell_shapes in shapes.iter_mut() {
+            if cell_shapes.len() > 1 {
+                *cell_shapes = cell_shapes.simplify(FillRule::NonZero, IntOverlayOptions::default());
+            }
+        }
+
+        shapes
+    }
+}
+
+/// The circumcenter of a triangle rounded to the nearest grid point, computed exactly in `i128`.
+/// It is kept in `f64`, the center of a nearly flat triangle may not fit into int coordinates.
+fn circumscribed_center_exact(a: IntPoint, b: IntPoint, c: IntPoint) -> [f64; 2] {
+    let bx = b.x as i128 - a.x as i128;
+    let by = b.y as i128 - a.y as i128;
+    let cx = c.x as i128 - a.x as i128;
+    let cy = c.y as i128 - a.y as i128;
+
+    let d = 2 * (bx * cy - by * cx);
+    if d == 0 {
+        return [f64::INFINITY; 2];
+    }
+    let bb = bx * bx + by * by;
+    let cc = cx * cx + cy * cy;
+    let (x, y, d) = if d > 0 {
+        (cy * bb - by * cc, bx * cc - cx * bb, d)
+    } else {
+        (by * cc - cy * bb, cx * bb - bx * cc, -d)
+    };
+
+    // rounded in absolute coordinates, so the vertex order does not matter
+    [
+        div_round(a.x as i128 * d + x, d) as f64,
+        div_round(a.y as i128 * d + y, d) as f64,
+    ]
+}
+
+/// Three times the centroid of the biggest triangle of the shape, a point strictly inside it.
+fn inner_point(shape: &IntShape) -> Option<[i64; 2]> {
+    let [a, b, c] = shape
+        .triangulate()
+        .triangles
+        .iter()
+        .map(|t| t.vertices.map(|v| v.point))
+        .max_by_key(|&[a, b, c]| Triangle::area_two_point(a, b, c).abs())?;
+    Some([
+        a.x as i64 + b.x as i64 + c.x as i64,
+        a.y as i64 + b.y as i64 + c.y as i64,
+    ])
+}
+
+/// The winding number of the contour around `p`, given three times as big as the contour.
+fn winding(contour: &[IntPoint], p: [i64; 2]) -> i32 {
+    let triple = |q: IntPoint| [3 * q.x as i64, 3 * q.y as i64];
+    let mut winding = 0;
+    let mut a = if let Some(&last) = contour.last() {
+        triple(last)
+    } else {
+        return 0;
+    };
+    for &q in contour.iter() {
+        let b = triple(q);
+        let cross = (b[0] - a[0]) as i128 * (p[1] - a[1]) as i128 - (p[0] - a[0]) as i128 * (b[1] - a[1]) as i128;
+        if a[1] <= p[1] {
+            if p[1] < b[1] && cross > 0 {
+                winding += 1;
+            }
+        } else if b[1] <= p[1] && cross < 0 {
+            winding -= 1;
+        }
+        a = b;
+    }
+    winding
+}
+
+#[inline]
+fn to_f64(p: IntPoint) -> [f64; 2] {
+    [p.x as f64, p.y as f64]
+}
+
+#[inline]
+fn to_int(p: [f64; 2]) -> IntPoint {
+    IntPoint::new(p[0] as i32, p[1] as i32)
+}
+
+#[inline]
+fn union_box(a: [IntPoint; 2], b: [IntPoint; 2]) -> [IntPoint; 2] {
+    [min_point(a[0], b[0]), max_point(a[1], b[1])]
+}
+
+#[inline]
+fn bounding_box(contour: &[IntPoint]) -> [IntPoint; 2] {
+    contour
+        .iter()
+        .fold([contour[0], contour[0]], |b, &p| [min_point(b[0], p), max_point(b[1], p)])
+}
+
+#[inline]
+fn min_point(a: IntPoint, b: IntPoint) -> IntPoint {
+    IntPoint::new(a.x.min(b.x), a.y.min(b.y))
+}
+
+#[inline]
+fn max_point(a: IntPoint, b: IntPoint) -> IntPoint {
+    IntPoint::new(a.x.max(b.x), a.y.max(b.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::advanced::voronoi::{circumscribed_center_exact, IntVoronoiCell};
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn cells_area(cells: &[IntVoronoiCell]) -> i64 {
+        cells
+            .iter()
+            .flat_map(|cell| cell.shapes.iter())
+            .map(|shape| shape.area_two())
+            .sum()
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let delaunay = contour.triangulate_with_steiner_points(&[IntPoint::new(5, 5)]).into_delaunay();
+        let cells = delaunay.to_voronoi_cells();
+
+        assert_eq!(cells.len(), 5);
+        let center = delaunay.points.iter().position(|p| *p == IntPoint::new(5, 5)).unwrap();
+        let cell = cells.iter().find(|c| c.site == center).unwrap();
+        assert_eq!(cell.neighbors.len(), 4);
+        assert_eq!(cell.shapes.len(), 1);
+        assert_eq!(cell.shapes[0].area_two(), contour.area_two() / 2);
+
+        // the cells cover the square
+        assert_eq!(cells_area(&cells), contour.area_two());
+    }
+
+    #[test]
+    fn test_1() {
+        // obtuse triangles have circumcenters outside the shape
+        let shape = vec![
+            path(&[[0, 0], [4000, 0], [4000, 4000], [0, 4000]]),
+            path(&[[1000, 1000], [1000, 3000], [3000, 3000], [3000, 1000]]),
+        ];
+        let delaunay = shape.triangulate().into_delaunay().refine_with_circumcenters(160_000);
+        let cells = delaunay.to_voronoi_cells();
+        assert_eq!(cells.len(), delaunay.points.len());
+
+        for cell in cells {
+            for shape in cell.shapes.iter() {
+                for p in shape.iter().flatten() {
+                    let in_hole = 1000 < p.x && p.x < 3000 && 1000 < p.y && p.y < 3000;
+                    assert!(!in_hole);
+                    assert!(0 <= p.x && p.x <= 4000 && 0 <= p.y && p.y <= 4000);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_2() {
+        // far from the origin `f64` loses the fraction of the center (1e9 + 0.5, 1e9 + 1.5)
+        let a = IntPoint::new(1_000_000_000, 1_000_000_000);
+        let b = IntPoint::new(1_000_000_001, 1_000_000_000);
+        let c = IntPoint::new(1_000_000_000, 1_000_000_003);
+        let center = circumscribed_center_exact(a, b, c);
+        assert_eq!(center, [1_000_000_001.0, 1_000_000_002.0]);
+        assert_eq!(circumscribed_center_exact(b, c, a), center);
+        assert_eq!(circumscribed_center_exact(c, a, b), center);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let n = rng.random_range(3..40);
+            let points: Vec<IntPoint> = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000)))
+                .collect();
+            let delaunay = IntDelaunay::from_points(&points);
+            if delaunay.triangles.is_empty() {
+                continue;
+            }
+
+            let mesh_area: i64 = delaunay
+                .triangles
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.vertices.map(|v| v.point);
+                    Triangle::area_two_point(a, b, c)
+                })
+                .sum();
+
+            let cells = delaunay.to_voronoi_cells();
+            for cell in cells.iter() {
+                assert!(cell.neighbors.iter().all(|&n| n != cell.site));
+            }
+
+            // rounded circumcenters leave thin gaps and overlaps along the cell borders
+            let area = cells_area(&cells);
+            let (min_x, max_x) = points.iter().fold((i32::MAX, i32::MIN), |(a, b), p| (a.min(p.x), b.max(p.x)));
+            let (min_y, max_y) = points.iter().fold((i32::MAX, i32::MIN), |(a, b), p| (a.min(p.y), b.max(p.y)));
+            let border = 2 * (max_x - min_x + max_y - min_y) as i64;
+            assert!((area - mesh_area).abs() <= border);
+        }
+    }
+}
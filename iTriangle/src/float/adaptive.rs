@@ -0,0 +1,13 @@
+use crate::float::delaunay::Delaunay;
+use crate::tessellation::adaptive::RefinementStrategy;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Splits the given triangles and returns the children of every old triangle,
+    /// see [`IntDelaunay::refine_triangles`](crate::advanced::delaunay::IntDelaunay::refine_triangles).
+    #[inline]
+    pub fn refine_triangles(&mut self, triangles: &[usize], strategy: RefinementStrategy) -> Vec<Vec<usize>> {
+        self.delaunay.refine_triangles(triangles, strategy)
+    }
+}
@@ -1,9 +1,11 @@
 use crate::float::triangulation::Triangulation;
-use crate::int::triangulation::IndexType;
+use crate::int::error::TriangulationError;
+use crate::int::triangulation::{check_index_type, IndexType};
 
 pub struct TriangulationBuilder<P, I> {
     points: Vec<P>,
     indices: Vec<I>,
+    shape_ids: Vec<usize>,
 }
 
 impl<P, I: IndexType> TriangulationBuilder<P, I> {
@@ -11,28 +13,39 @@ impl<P, I: IndexType> TriangulationBuilder<P, I> {
     ///
     /// This method correctly offsets the indices of the appended triangulation
     /// based on the current number of points in the builder.
+    /// Shape ids are kept only if every appended triangulation has them.
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all points, see [`TriangulationBuilder::try_append`].
     pub fn append(&mut self, triangulation: Triangulation<P, I>) -> &mut Self {
-        let points_count = self.points.len() + triangulation.points.len();
-        if points_count > I::MAX {
-            panic!(
-                "Index type `{}` cannot hold {} points",
-                std::any::type_name::<I>(),
-                points_count
-            );
-        }
-        
+        self.try_append(triangulation).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Appends another `Triangulation` to the builder.
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] and leaves the builder unchanged
+    /// if the index type `I` cannot address all points.
+    pub fn try_append(&mut self, triangulation: Triangulation<P, I>) -> Result<&mut Self, TriangulationError> {
+        check_index_type::<I>(self.points.len() + triangulation.points.len())?;
+
         let offset = I::try_from(self.points.len()).unwrap_or(I::ZERO);
         self.points.extend(triangulation.points);
         self.indices
             .extend(triangulation.indices.iter().map(|&i|i.add(offset)));
-        self
+        self.shape_ids.extend(triangulation.shape_ids);
+        Ok(self)
     }
 
     /// Builds and returns the final `Triangulation`.
     pub fn build(self) -> Triangulation<P, I> {
+        let mut shape_ids = self.shape_ids;
+        if 3 * shape_ids.len() != self.indices.len() {
+            shape_ids.clear();
+        }
         Triangulation {
             points: self.points,
             indices: self.indices,
+            shape_ids,
         }
     }
 }
@@ -42,6 +55,7 @@ impl<P, I> Default for TriangulationBuilder<P, I> {
         Self {
             points: Vec::new(),
             indices: Vec::new(),
+            shape_ids: Vec::new(),
         }
     }
 }
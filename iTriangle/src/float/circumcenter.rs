@@ -1,6 +1,8 @@
 use crate::float::delaunay::Delaunay;
+use crate::tessellation::circumcenter::{EdgeSelector, RefinementOptions};
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
 use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_float::int::point::IntPoint;
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
     #[inline]
@@ -26,4 +28,77 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
         let int_area = self.adapter.sqr_float_to_int(min_area);
         self.delaunay.refine_with_circumcenters_by_obtuse_angle_mut(int_area);
     }
-}
\ No newline at end of file
+
+    /// Refines the mesh with `selector` choosing the edges to split,
+    /// see [`IntDelaunay::refine_with_options`](crate::advanced::delaunay::IntDelaunay::refine_with_options).
+    ///
+    /// The selector gets the int points of the mesh, use [`Delaunay::refine_with_options_by`] for float points.
+    #[inline]
+    pub fn refine_with_options<S: EdgeSelector>(mut self, options: &RefinementOptions<T>, selector: S) -> Self {
+        self.refine_with_options_mut(options, selector);
+        self
+    }
+
+    #[inline]
+    pub fn refine_with_options_mut<S: EdgeSelector>(&mut self, options: &RefinementOptions<T>, selector: S) {
+        let int_options = self.int_options(options);
+        self.delaunay.refine_with_options_mut(&int_options, selector);
+    }
+
+    /// Refines the mesh with a selector over float points, see [`Delaunay::refine_with_options`].
+    #[inline]
+    pub fn refine_with_options_by<F>(mut self, options: &RefinementOptions<T>, selector: F) -> Self
+    where
+        F: Fn(usize, [P; 3]) -> Option<usize> + Sync,
+    {
+        self.refine_with_options_by_mut(options, selector);
+        self
+    }
+
+    pub fn refine_with_options_by_mut<F>(&mut self, options: &RefinementOptions<T>, selector: F)
+    where
+        F: Fn(usize, [P; 3]) -> Option<usize> + Sync,
+    {
+        // the adapter is not `Sync`, the selector takes a copy of its transform
+        let inv_scale = self.adapter.inv_scale.to_f64();
+        let ox = self.adapter.offset.x().to_f64();
+        let oy = self.adapter.offset.y().to_f64();
+        let int_selector = move |triangle: usize, points: [IntPoint; 3]| {
+            let points = points.map(|p| {
+                let x = T::from_float(p.x as f64 * inv_scale + ox);
+                let y = T::from_float(p.y as f64 * inv_scale + oy);
+                P::from_xy(x, y)
+            });
+            selector(triangle, points)
+        };
+
+        let int_options = self.int_options(options);
+        self.delaunay.refine_with_options_mut(&int_options, int_selector);
+    }
+
+    /// Refines the worst triangles first,
+    /// see [`IntDelaunay::refine_worst_first`](crate::advanced::delaunay::IntDelaunay::refine_worst_first).
+    #[inline]
+    pub fn refine_worst_first<S: EdgeSelector>(mut self, options: &RefinementOptions<T>, selector: S) -> Self {
+        self.refine_worst_first_mut(options, selector);
+        self
+    }
+
+    #[inline]
+    pub fn refine_worst_first_mut<S: EdgeSelector>(&mut self, options: &RefinementOptions<T>, selector: S) {
+        let int_options = self.int_options(options);
+        self.delaunay.refine_worst_first_mut(&int_options, selector);
+    }
+
+    fn int_options(&self, options: &RefinementOptions<T>) -> RefinementOptions {
+        let scale = self.adapter.dir_scale.to_f64();
+        RefinementOptions {
+            max_iterations: options.max_iterations,
+            min_split_ratio: options.min_split_ratio,
+            target_triangle_count: options.target_triangle_count,
+            max_point_count: options.max_point_count,
+            min_area: self.adapter.sqr_float_to_int(options.min_area),
+            max_edge_length: options.max_edge_length.map(|l| (l.to_f64() * scale) as u64),
+        }
+    }
+}
@@ -1,4 +1,7 @@
+use crate::advanced::convex::{ConvexDecomposition, ConvexOptions};
 use crate::float::delaunay::Delaunay;
+use crate::float::triangulation::RawTriangulation;
+use i_overlay::i_float::adapter::FloatPointAdapter;
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
 use i_overlay::i_float::float::number::FloatNumber;
 use i_overlay::i_shape::base::data::Contour;
@@ -12,4 +15,50 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
     pub fn to_convex_polygons(&self) -> Vec<Contour<P>> {
         self.delaunay.to_convex_polygons().to_float(&self.adapter)
     }
+
+    /// Groups triangles into non-overlapping convex polygons as [`ConvexOptions`] ask.
+    ///
+    /// See [`IntDelaunay::to_convex_polygons_with_options`](crate::advanced::delaunay::IntDelaunay::to_convex_polygons_with_options).
+    #[inline]
+    pub fn to_convex_polygons_with_options(&self, options: &ConvexOptions) -> Vec<Contour<P>> {
+        self.delaunay.to_convex_polygons_with_options(options).to_float(&self.adapter)
+    }
+
+    /// Groups triangles into convex polygons and also returns which polygons are adjacent.
+    #[inline]
+    pub fn to_convex_decomposition(&self, options: &ConvexOptions) -> ConvexDecomposition<Contour<P>> {
+        to_float(self.delaunay.to_convex_decomposition(options), &self.adapter)
+    }
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> RawTriangulation<P, T> {
+    /// Groups triangles into non-overlapping convex polygons without building the Delaunay mesh first.
+    #[inline]
+    pub fn to_convex_polygons(&self) -> Vec<Contour<P>> {
+        self.raw.to_convex_polygons().to_float(&self.adapter)
+    }
+
+    /// Groups triangles into non-overlapping convex polygons as [`ConvexOptions`] ask,
+    /// without building the Delaunay mesh first.
+    #[inline]
+    pub fn to_convex_polygons_with_options(&self, options: &ConvexOptions) -> Vec<Contour<P>> {
+        self.raw.to_convex_polygons_with_options(options).to_float(&self.adapter)
+    }
+
+    /// Groups triangles into convex polygons and also returns which polygons are adjacent.
+    #[inline]
+    pub fn to_convex_decomposition(&self, options: &ConvexOptions) -> ConvexDecomposition<Contour<P>> {
+        to_float(self.raw.to_convex_decomposition(options), &self.adapter)
+    }
+}
+
+#[inline]
+fn to_float<P: FloatPointCompatible<T>, T: FloatNumber>(
+    decomposition: ConvexDecomposition,
+    adapter: &FloatPointAdapter<P, T>,
+) -> ConvexDecomposition<Contour<P>> {
+    ConvexDecomposition {
+        polygons: decomposition.polygons.to_float(adapter),
+        adjacency: decomposition.adjacency,
+    }
 }
@@ -0,0 +1,24 @@
+use crate::float::delaunay::Delaunay;
+use crate::tessellation::decimation::DecimationOptions;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Reduces the triangle count by collapsing short inner edges,
+    /// see [`IntDelaunay::decimate`](crate::advanced::delaunay::IntDelaunay::decimate).
+    #[inline]
+    pub fn decimate(mut self, options: &DecimationOptions<T>) -> Self {
+        self.decimate_mut(options);
+        self
+    }
+
+    #[inline]
+    pub fn decimate_mut(&mut self, options: &DecimationOptions<T>) {
+        let scale = self.adapter.dir_scale.to_f64();
+        let int_options = DecimationOptions {
+            target_triangle_count: options.target_triangle_count,
+            max_error: options.max_error.map(|e| (e.to_f64() * scale) as u64),
+        };
+        self.delaunay.decimate_mut(&int_options);
+    }
+}
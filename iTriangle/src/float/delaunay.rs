@@ -1,10 +1,15 @@
 use crate::advanced::delaunay::IntDelaunay;
+use crate::advanced::edges::MeshEdge;
+use crate::advanced::topology::Topology;
 use i_overlay::i_float::adapter::FloatPointAdapter;
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
 use i_overlay::i_float::float::number::FloatNumber;
-use i_overlay::i_shape::float::adapter::PathToFloat;
+use i_overlay::i_float::float::rect::FloatRect;
+use i_overlay::i_shape::float::adapter::{PathToFloat, PathToInt};
 use crate::float::triangulation::{RawTriangulation, Triangulation};
-use crate::int::triangulation::IndexType;
+use crate::int::error::TriangulationError;
+use crate::int::provenance::VertexSource;
+use crate::int::triangulation::{IndexType, RawIntTriangulation};
 
 /// A Delaunay-refined triangle mesh with float-mapped geometry.
 ///
@@ -23,27 +28,110 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> RawTriangulation<P, T> {
             adapter: self.adapter,
         }
     }
+
+    /// Converts the mesh into a conforming Delaunay triangulation,
+    /// see [`RawIntTriangulation::into_conforming_delaunay`].
+    #[inline]
+    pub fn into_conforming_delaunay(self) -> Delaunay<P, T> {
+        Delaunay {
+            delaunay: self.raw.into_conforming_delaunay(),
+            adapter: self.adapter,
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Builds the Delaunay triangulation of a point cloud over its convex hull,
+    /// see [`IntDelaunay::from_points`].
+    pub fn from_points(points: &[P]) -> Self {
+        if let Some(rect) = FloatRect::with_points(points) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let delaunay = IntDelaunay::from_points(&points.to_int(&adapter));
+            Self { delaunay, adapter }
+        } else {
+            Self {
+                delaunay: RawIntTriangulation::empty().into_delaunay(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
     /// Returns the float-mapped vertex positions in the triangulation.
     #[inline]
     pub fn points(&self) -> Vec<P> {
         self.delaunay.points.to_float(&self.adapter)
     }
 
+    /// Returns the index of the source shape for every triangle,
+    /// see [`IntDelaunay::shape_ids`].
+    #[inline]
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        self.delaunay.shape_ids()
+    }
+
+    /// Returns the origin of every point, see [`IntDelaunay::vertex_sources`].
+    #[inline]
+    pub fn vertex_sources(&self) -> Option<&[VertexSource]> {
+        self.delaunay.vertex_sources()
+    }
+
+    /// Returns the boundary and constrained edges encroached by a triangle next to them,
+    /// as index pairs into [`Delaunay::points`],
+    /// see [`IntDelaunay::encroached_edges`].
+    #[inline]
+    pub fn encroached_edges(&self) -> Vec<[usize; 2]> {
+        self.delaunay.encroached_edges()
+    }
+
+    /// Returns a read-only [`Topology`] view for walking the mesh adjacency.
+    ///
+    /// Point indices of the view match [`Delaunay::points`].
+    #[inline]
+    pub fn topology(&self) -> Topology<'_> {
+        self.delaunay.topology()
+    }
+
+    /// Returns every undirected mesh edge exactly once, with its classification.
+    ///
+    /// Point indices of the edges match [`Delaunay::points`].
+    #[inline]
+    pub fn edges(&self) -> Vec<MeshEdge> {
+        self.delaunay.edges()
+    }
+
     /// Returns indices forming counter-clockwise triangles.
     #[inline]
     pub fn triangle_indices<I: IndexType>(&self) -> Vec<I> {
         self.delaunay.triangle_indices()
     }
 
+    /// Returns indices forming counter-clockwise triangles.
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_triangle_indices<I: IndexType>(&self) -> Result<Vec<I>, TriangulationError> {
+        self.delaunay.try_triangle_indices()
+    }
+
     /// Converts this refined mesh into a flat float [`Triangulation`].
     #[inline]
     pub fn to_triangulation<I: IndexType>(&self) -> Triangulation<P, I> {
         Triangulation {
             indices: self.triangle_indices(),
             points: self.points(),
+            shape_ids: self.delaunay.shape_ids.clone(),
         }
     }
+
+    /// Converts this refined mesh into a flat float [`Triangulation`].
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_to_triangulation<I: IndexType>(&self) -> Result<Triangulation<P, I>, TriangulationError> {
+        Ok(Triangulation {
+            indices: self.try_triangle_indices()?,
+            points: self.points(),
+            shape_ids: self.delaunay.shape_ids.clone(),
+        })
+    }
 }
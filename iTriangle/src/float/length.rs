@@ -0,0 +1,25 @@
+use crate::float::delaunay::Delaunay;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Splits edges until no edge of the mesh, boundary or inner, is longer than `max_edge_length`.
+    ///
+    /// See [`IntDelaunay::refine_by_max_edge_length`](crate::advanced::delaunay::IntDelaunay::refine_by_max_edge_length).
+    #[inline]
+    pub fn refine_by_max_edge_length(mut self, max_edge_length: T) -> Self {
+        self.refine_by_max_edge_length_mut(max_edge_length);
+        self
+    }
+
+    /// A length that is not positive, NaN included, leaves the mesh as it is.
+    /// A positive length shorter than the grid step is raised to one grid step.
+    #[inline]
+    pub fn refine_by_max_edge_length_mut(&mut self, max_edge_length: T) {
+        let length = max_edge_length.to_f64() * self.adapter.dir_scale.to_f64();
+        if length.is_nan() || length <= 0.0 {
+            return;
+        }
+        self.delaunay.refine_by_max_edge_length_mut((length as u64).max(1));
+    }
+}
@@ -6,4 +6,12 @@ pub mod delaunay;
 pub mod convex;
 pub mod builder;
 pub mod circumcenter;
+pub mod quality;
+pub mod sizing;
+pub mod length;
+pub mod adaptive;
+pub mod decimation;
 pub mod centroid_net;
+pub mod voronoi;
+pub mod quad;
+pub mod trapezoid;
@@ -0,0 +1,45 @@
+use crate::advanced::quad::IntQuadMesh;
+use crate::float::delaunay::Delaunay;
+use i_overlay::i_float::adapter::FloatPointAdapter;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_shape::float::adapter::PathToFloat;
+
+/// Quads and the triangles left without a pair, see [`Delaunay::to_quad_dominant`].
+#[derive(Debug, Clone)]
+pub struct QuadMesh<P> {
+    pub points: Vec<P>,
+    /// Point indices of every quad, wound like the mesh triangles.
+    pub quads: Vec<[usize; 4]>,
+    /// Point indices of the triangles without a pair.
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Pairs adjacent triangles into convex quads, the rest stays triangles,
+    /// see [`IntDelaunay::to_quad_dominant`](crate::advanced::delaunay::IntDelaunay::to_quad_dominant).
+    #[inline]
+    pub fn to_quad_dominant(&self) -> QuadMesh<P> {
+        to_float(self.delaunay.to_quad_dominant(), &self.adapter)
+    }
+
+    /// Builds an all-quad mesh by splitting the quad-dominant one by the middles of its sides,
+    /// see [`IntDelaunay::to_all_quad`](crate::advanced::delaunay::IntDelaunay::to_all_quad)
+    /// for the triangles it may keep.
+    #[inline]
+    pub fn to_all_quad(&self) -> QuadMesh<P> {
+        to_float(self.delaunay.to_all_quad(), &self.adapter)
+    }
+}
+
+#[inline]
+fn to_float<P: FloatPointCompatible<T>, T: FloatNumber>(
+    mesh: IntQuadMesh,
+    adapter: &FloatPointAdapter<P, T>,
+) -> QuadMesh<P> {
+    QuadMesh {
+        points: mesh.points.to_float(adapter),
+        quads: mesh.quads,
+        triangles: mesh.triangles,
+    }
+}
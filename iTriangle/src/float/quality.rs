@@ -0,0 +1,21 @@
+use crate::float::delaunay::Delaunay;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Refines the mesh until no triangle has an angle below `min_angle` (in degrees)
+    /// or an area above `max_area` (zero means no area limit).
+    ///
+    /// See [`IntDelaunay::refine_to_min_angle`](crate::advanced::delaunay::IntDelaunay::refine_to_min_angle).
+    #[inline]
+    pub fn refine_to_min_angle(mut self, min_angle: f64, max_area: T) -> Self {
+        self.refine_to_min_angle_mut(min_angle, max_area);
+        self
+    }
+
+    #[inline]
+    pub fn refine_to_min_angle_mut(&mut self, min_angle: f64, max_area: T) {
+        let int_area = self.adapter.sqr_float_to_int(max_area);
+        self.delaunay.refine_to_min_angle_mut(min_angle, int_area);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::float::delaunay::Delaunay;
+use crate::tessellation::sizing::{DistanceField, GradedField, SizingField};
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::float::adapter::PathToInt;
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Splits triangles until every edge is not longer than `sizing` wants at the triangle centroid,
+    /// see [`IntDelaunay::refine_with_sizing`](crate::advanced::delaunay::IntDelaunay::refine_with_sizing).
+    #[inline]
+    pub fn refine_with_sizing<F: Fn(P) -> T + Sync>(mut self, sizing: F) -> Self {
+        self.refine_with_sizing_mut(sizing);
+        self
+    }
+
+    #[inline]
+    pub fn refine_with_sizing_mut<F: Fn(P) -> T + Sync>(&mut self, sizing: F) {
+        // the adapter is not `Sync`, the field takes a copy of its transform
+        let scale = self.adapter.dir_scale.to_f64();
+        let inv_scale = self.adapter.inv_scale.to_f64();
+        let ox = self.adapter.offset.x().to_f64();
+        let oy = self.adapter.offset.y().to_f64();
+        let field = move |p: IntPoint| {
+            let x = T::from_float(p.x as f64 * inv_scale + ox);
+            let y = T::from_float(p.y as f64 * inv_scale + oy);
+            sizing(P::from_xy(x, y)).to_f64() * scale
+        };
+        self.delaunay.refine_with_sizing_mut(field);
+    }
+
+    /// Refines by a field in int units, e.g. one made by [`Delaunay::boundary_distance_field`].
+    #[inline]
+    pub fn refine_with_sizing_field<S: SizingField>(mut self, field: S) -> Self {
+        self.refine_with_sizing_field_mut(field);
+        self
+    }
+
+    #[inline]
+    pub fn refine_with_sizing_field_mut<S: SizingField>(&mut self, field: S) {
+        self.delaunay.refine_with_sizing_mut(field);
+    }
+
+    /// The edges longer than a field in int units wants,
+    /// see [`IntDelaunay::long_edges`](crate::advanced::delaunay::IntDelaunay::long_edges).
+    #[inline]
+    pub fn long_edges<S: SizingField>(&self, field: &S) -> Vec<[usize; 2]> {
+        self.delaunay.long_edges(field)
+    }
+
+    /// A field growing with the distance to the boundary, see [`DistanceField`].
+    /// `growth` is the length added per unit of distance.
+    pub fn boundary_distance_field(&self, min_length: T, growth: f64, max_length: T) -> DistanceField {
+        let scale = self.adapter.dir_scale.to_f64();
+        self.delaunay
+            .boundary_distance_field(min_length.to_f64() * scale, growth, max_length.to_f64() * scale)
+    }
+
+    /// A field growing with the distance to the nearest of `points`, see [`DistanceField`].
+    pub fn point_distance_field(&self, points: &[P], min_length: T, growth: f64, max_length: T) -> DistanceField {
+        let scale = self.adapter.dir_scale.to_f64();
+        DistanceField::with_points(
+            &points.to_int(&self.adapter),
+            min_length.to_f64() * scale,
+            growth,
+            max_length.to_f64() * scale,
+        )
+    }
+
+    /// Limits how fast `field` changes over this mesh, see [`GradedField`].
+    #[inline]
+    pub fn graded_field<S: SizingField>(&self, field: S, gradation: f64) -> GradedField<S> {
+        GradedField::new(&self.delaunay, field, gradation)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::int::triangulator::Triangulator;
+use i_overlay::i_float::adapter::FloatPointAdapter;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_float::float::rect::FloatRect;
+use i_overlay::i_shape::base::data::{Contour, Shape};
+use i_overlay::i_shape::float::adapter::{PathToInt, ShapeToFloat, ShapeToInt, ShapesToInt};
+use i_overlay::i_shape::float::rect::RectInit;
+
+/// A trait for cutting float geometry into trapezoids with vertical left and right sides.
+///
+/// # Implemented For
+/// - `Contour<P>`
+/// - `[Contour<P>]`
+/// - `[Shape<P>]`
+pub trait Trapezoidable<P: FloatPointCompatible<T>, T: FloatNumber> {
+    /// Cuts the shape(s) into trapezoids using the default [`Triangulator`] validation,
+    /// see [`Triangulator::trapezoidate_shapes`].
+    ///
+    /// A trapezoid with a collapsed side is returned as a triangle.
+    fn trapezoidate(&self) -> Vec<Contour<P>>;
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Trapezoidable<P, T> for Contour<P> {
+    fn trapezoidate(&self) -> Vec<Contour<P>> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            Triangulator::default()
+                .trapezoidate_contour(&self.to_int(&adapter))
+                .to_float(&adapter)
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Trapezoidable<P, T> for [Contour<P>] {
+    fn trapezoidate(&self) -> Vec<Contour<P>> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            Triangulator::default()
+                .trapezoidate_shape(&self.to_int(&adapter))
+                .to_float(&adapter)
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Trapezoidable<P, T> for [Shape<P>] {
+    fn trapezoidate(&self) -> Vec<Contour<P>> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            Triangulator::default()
+                .trapezoidate_shapes(&self.to_int(&adapter))
+                .to_float(&adapter)
+        } else {
+            Vec::new()
+        }
+    }
+}
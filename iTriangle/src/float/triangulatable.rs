@@ -4,10 +4,15 @@ use i_overlay::i_float::adapter::FloatPointAdapter;
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
 use i_overlay::i_float::float::number::FloatNumber;
 use i_overlay::i_float::float::rect::FloatRect;
-use i_overlay::i_shape::base::data::{Contour, Shape};
+use i_overlay::i_shape::base::data::{Contour, Path, Shape};
 use i_overlay::i_shape::float::adapter::{PathToInt, ShapeToInt, ShapesToInt};
 use i_overlay::i_shape::float::rect::RectInit;
 use crate::float::triangulation::RawTriangulation;
+use crate::int::context::TriangulatorContext;
+use crate::int::error::TriangulationError;
+use crate::int::triangulation::IndexType;
+use crate::int::triangulator::Triangulator;
+use i_overlay::core::simplify::Simplify;
 
 /// A trait for triangulating float-based geometry with default validation.
 ///
@@ -28,6 +33,37 @@ pub trait Triangulatable<P: FloatPointCompatible<T>, T: FloatNumber> {
     ///
     /// Points must lie strictly within the interior of the geometry.
     fn triangulate_with_steiner_points(&self, points: &[P]) -> RawTriangulation<P, T>;
+
+    /// Triangulates the shape(s) and forces the given segments to appear as edges.
+    ///
+    /// Crossing segments are split at their intersections. Segments that leave
+    /// the geometry are cut at its boundary, segments that cannot be inserted are ignored.
+    fn triangulate_with_constraints(&self, segments: &[[P; 2]]) -> RawTriangulation<P, T>;
+
+    /// Triangulates the shape(s) and cuts the mesh along open polylines (slits).
+    ///
+    /// Triangles on opposite sides of a slit are disconnected and its vertices are duplicated.
+    fn triangulate_with_slits(&self, slits: &[Path<P>]) -> RawTriangulation<P, T>;
+
+    /// Triangulates the shape(s) and records the index of the source shape for every triangle.
+    ///
+    /// Each shape is simplified separately, so overlapping shapes keep their own triangles.
+    /// A single contour or shape gets the id `0`.
+    fn triangulate_with_shape_ids(&self) -> RawTriangulation<P, T>;
+
+    /// Triangulates the shape(s) and appends the result to caller-owned buffers.
+    ///
+    /// Validation still allocates, use [`UncheckedTriangulatable::unchecked_triangulate_into`]
+    /// for allocation-free triangulation of valid geometry.
+    /// On [`TriangulationError::IndexOverflow`] the buffers are left unchanged.
+    ///
+    /// [`UncheckedTriangulatable::unchecked_triangulate_into`]: crate::float::unchecked::UncheckedTriangulatable::unchecked_triangulate_into
+    fn triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError>;
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for Contour<P> {
@@ -59,6 +95,73 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for Contou
             }
         }
     }
+
+    fn triangulate_with_constraints(&self, segments: &[[P; 2]]) -> RawTriangulation<P, T> {
+        if let Some(mut rect) = FloatRect::with_path(self) {
+            // segments leaving the shape are cut at its boundary, their ends must fit the adapter too
+            for s in segments.iter() {
+                rect.add_point(&s[0]);
+                rect.add_point(&s[1]);
+            }
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_segments: Vec<_> = segments
+                .iter()
+                .map(|s| [adapter.float_to_int(&s[0]), adapter.float_to_int(&s[1])])
+                .collect();
+            let raw = self
+                .to_int(&adapter)
+                .triangulate_with_constraints(&int_segments);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_slits(&self, slits: &[Path<P>]) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_slits = slits.to_int(&adapter);
+            let raw = self.to_int(&adapter).triangulate_with_slits(&int_slits);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_shape_ids(&self) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let raw = self.to_int(&adapter).triangulate_with_shape_ids();
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let validation = Triangulator::default().validation;
+            let shapes = self.to_int(&adapter).simplify(validation.fill_rule, validation.options);
+            Triangulator::shapes_into(&shapes, context, points, indices, |p| adapter.int_to_float(&p))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for [Contour<P>] {
@@ -90,6 +193,73 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for [Conto
             }
         }
     }
+
+    fn triangulate_with_constraints(&self, segments: &[[P; 2]]) -> RawTriangulation<P, T> {
+        if let Some(mut rect) = FloatRect::with_paths(self) {
+            // segments leaving the shape are cut at its boundary, their ends must fit the adapter too
+            for s in segments.iter() {
+                rect.add_point(&s[0]);
+                rect.add_point(&s[1]);
+            }
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_segments: Vec<_> = segments
+                .iter()
+                .map(|s| [adapter.float_to_int(&s[0]), adapter.float_to_int(&s[1])])
+                .collect();
+            let raw = self
+                .to_int(&adapter)
+                .triangulate_with_constraints(&int_segments);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_slits(&self, slits: &[Path<P>]) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_slits = slits.to_int(&adapter);
+            let raw = self.to_int(&adapter).triangulate_with_slits(&int_slits);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_shape_ids(&self) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let raw = self.to_int(&adapter).triangulate_with_shape_ids();
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let validation = Triangulator::default().validation;
+            let shapes = self.to_int(&adapter).simplify(validation.fill_rule, validation.options);
+            Triangulator::shapes_into(&shapes, context, points, indices, |p| adapter.int_to_float(&p))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for [Shape<P>] {
@@ -121,4 +291,71 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> Triangulatable<P, T> for [Shape
             }
         }
     }
+
+    fn triangulate_with_constraints(&self, segments: &[[P; 2]]) -> RawTriangulation<P, T> {
+        if let Some(mut rect) = FloatRect::with_list_of_paths(self) {
+            // segments leaving the shape are cut at its boundary, their ends must fit the adapter too
+            for s in segments.iter() {
+                rect.add_point(&s[0]);
+                rect.add_point(&s[1]);
+            }
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_segments: Vec<_> = segments
+                .iter()
+                .map(|s| [adapter.float_to_int(&s[0]), adapter.float_to_int(&s[1])])
+                .collect();
+            let raw = self
+                .to_int(&adapter)
+                .triangulate_with_constraints(&int_segments);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_slits(&self, slits: &[Path<P>]) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let int_slits = slits.to_int(&adapter);
+            let raw = self.to_int(&adapter).triangulate_with_slits(&int_slits);
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_with_shape_ids(&self) -> RawTriangulation<P, T> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let raw = self.to_int(&adapter).triangulate_with_shape_ids();
+            RawTriangulation { raw, adapter }
+        } else {
+            RawTriangulation {
+                raw: RawIntTriangulation::empty(),
+                adapter: FloatPointAdapter::<P, T>::new(FloatRect::zero()),
+            }
+        }
+    }
+
+    fn triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let validation = Triangulator::default().validation;
+            let shapes = self.to_int(&adapter).simplify(validation.fill_rule, validation.options);
+            Triangulator::shapes_into(&shapes, context, points, indices, |p| adapter.int_to_float(&p))
+        } else {
+            Ok(())
+        }
+    }
 }
\ No newline at end of file
@@ -1,8 +1,13 @@
 use i_overlay::i_float::adapter::FloatPointAdapter;
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
 use i_overlay::i_float::float::number::FloatNumber;
-use i_overlay::i_shape::float::adapter::PathToFloat;
+use i_overlay::i_shape::base::data::Shape;
+use i_overlay::i_shape::float::adapter::{PathToFloat, PathToInt, ShapesToInt};
 use serde::Serialize;
+use crate::advanced::edges::MeshEdge;
+use crate::advanced::topology::Topology;
+use crate::int::error::TriangulationError;
+use crate::int::provenance::VertexSource;
 use crate::int::triangulation::{IndexType, RawIntTriangulation};
 
 /// A triangulation result based on integer computation, with float mapping.
@@ -25,6 +30,22 @@ pub struct RawTriangulation<P: FloatPointCompatible<T>, T: FloatNumber> {
 pub struct Triangulation<P, I> {
     pub points: Vec<P>,
     pub indices: Vec<I>,
+    /// The source shape index of every triangle, empty unless shape ids were requested.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) shape_ids: Vec<usize>,
+}
+
+impl<P, I> Triangulation<P, I> {
+    /// Returns the index of the source shape for every triangle, `None` unless shape ids were requested,
+    /// e.g. by the `triangulate_with_shape_ids` trait methods.
+    #[inline]
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        if self.shape_ids.is_empty() {
+            None
+        } else {
+            Some(&self.shape_ids)
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> RawTriangulation<P, T> {
@@ -36,18 +57,78 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> RawTriangulation<P, T> {
         self.raw.points.to_float(&self.adapter)
     }
 
+    /// Returns the index of the source shape for every triangle,
+    /// see [`RawIntTriangulation::shape_ids`].
+    #[inline]
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        self.raw.shape_ids()
+    }
+
+    /// Attaches the origin of every point, see [`RawIntTriangulation::with_vertex_sources`].
+    ///
+    /// Pass the same shapes and Steiner points that were triangulated.
+    pub fn with_vertex_sources(mut self, shapes: &[Shape<P>], steiner_points: &[P]) -> Self {
+        let int_shapes = shapes.to_int(&self.adapter);
+        let int_points = steiner_points.to_int(&self.adapter);
+        self.raw = self.raw.with_vertex_sources(&int_shapes, &int_points);
+        self
+    }
+
+    /// Returns the origin of every point, `None` unless [`RawTriangulation::with_vertex_sources`] was called.
+    #[inline]
+    pub fn vertex_sources(&self) -> Option<&[VertexSource]> {
+        self.raw.vertex_sources()
+    }
+
+    /// Returns a read-only [`Topology`] view for walking the mesh adjacency.
+    ///
+    /// Point indices of the view match [`RawTriangulation::points`].
+    #[inline]
+    pub fn topology(&self) -> Topology<'_> {
+        self.raw.topology()
+    }
+
+    /// Returns every undirected mesh edge exactly once, with its classification.
+    ///
+    /// Point indices of the edges match [`RawTriangulation::points`].
+    #[inline]
+    pub fn edges(&self) -> Vec<MeshEdge> {
+        self.raw.edges()
+    }
+
     /// Returns the triangle indices for the mesh, ordered counter-clockwise.
     #[inline]
     pub fn triangle_indices<I: IndexType>(&self) -> Vec<I> {
         self.raw.triangle_indices()
     }
 
+    /// Returns the triangle indices for the mesh, ordered counter-clockwise.
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_triangle_indices<I: IndexType>(&self) -> Result<Vec<I>, TriangulationError> {
+        self.raw.try_triangle_indices()
+    }
+
     /// Converts this raw triangulation into a flat [`Triangulation`] (points + indices).
     #[inline]
     pub fn to_triangulation<I: IndexType>(&self) -> Triangulation<P, I> {
         Triangulation {
             indices: self.triangle_indices(),
             points: self.points(),
+            shape_ids: self.raw.shape_ids.clone(),
         }
     }
+
+    /// Converts this raw triangulation into a flat [`Triangulation`] (points + indices).
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_to_triangulation<I: IndexType>(&self) -> Result<Triangulation<P, I>, TriangulationError> {
+        Ok(Triangulation {
+            indices: self.try_triangle_indices()?,
+            points: self.points(),
+            shape_ids: self.raw.shape_ids.clone(),
+        })
+    }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use crate::float::triangulation::RawTriangulation;
 use crate::int::triangulation::RawIntTriangulation;
+use crate::int::context::TriangulatorContext;
+use crate::int::error::TriangulationError;
+use crate::int::triangulation::IndexType;
 use crate::int::unchecked::IntUncheckedTriangulatable;
 use i_overlay::i_float::adapter::FloatPointAdapter;
 use i_overlay::i_float::float::compatible::FloatPointCompatible;
@@ -22,6 +25,26 @@ pub trait UncheckedTriangulatable<P: FloatPointCompatible<T>, T: FloatNumber> {
     fn unchecked_triangulate(&self) -> RawTriangulation<P, T>;
     /// Same as `unchecked_triangulate`, but inserts user-defined Steiner points.
     fn unchecked_triangulate_with_steiner_points(&self, points: &[P]) -> RawTriangulation<P, T>;
+
+    /// Triangulates float geometry without validation and returns indices into the input points,
+    /// with contours (and shapes) flattened in order.
+    ///
+    /// No points are copied, so the indices can be used with the caller's own vertex buffer.
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all input points.
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I>;
+
+    /// Triangulates float geometry without validation and appends the result to caller-owned buffers.
+    ///
+    /// Once `context` and the buffers have grown enough, this method does not allocate.
+    /// On [`TriangulationError::IndexOverflow`] the buffers are left unchanged.
+    fn unchecked_triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError>;
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> for [P] {
@@ -53,6 +76,30 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> f
             }
         }
     }
+
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            self.to_int(&adapter).unchecked_triangulate_indices()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn unchecked_triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_path(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let count = context.load_shape(std::iter::once(self), &adapter);
+            context.append_loaded(count, points, indices, |p| adapter.int_to_float(&p))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> for [Contour<P>] {
@@ -84,6 +131,30 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> f
             }
         }
     }
+
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            self.to_int(&adapter).unchecked_triangulate_indices()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn unchecked_triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let count = context.load_shape(self.iter().map(Vec::as_slice), &adapter);
+            context.append_loaded(count, points, indices, |p| adapter.int_to_float(&p))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> for [Shape<P>] {
@@ -115,4 +186,35 @@ impl<P: FloatPointCompatible<T>, T: FloatNumber> UncheckedTriangulatable<P, T> f
             }
         }
     }
+
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            self.to_int(&adapter).unchecked_triangulate_indices()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn unchecked_triangulate_into<I: IndexType>(
+        &self,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(rect) = FloatRect::with_list_of_paths(self) {
+            let adapter = FloatPointAdapter::<P, T>::new(rect);
+            let points_len = points.len();
+            let indices_len = indices.len();
+            for shape in self.iter() {
+                let count = context.load_shape(shape.iter().map(Vec::as_slice), &adapter);
+                if let Err(error) = context.append_loaded(count, points, indices, |p| adapter.int_to_float(&p)) {
+                    points.truncate(points_len);
+                    indices.truncate(indices_len);
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,32 @@
+use crate::float::delaunay::Delaunay;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_shape::base::data::Shapes;
+use i_overlay::i_shape::float::adapter::ShapesToFloat;
+
+/// A Voronoi cell of a mesh point, see [`Delaunay::to_voronoi_cells`].
+#[derive(Debug, Clone)]
+pub struct VoronoiCell<P> {
+    /// The index of the generating point in [`Delaunay::points`].
+    pub site: usize,
+    /// The cell clipped by the mesh boundary, it may fall apart into several shapes.
+    pub shapes: Shapes<P>,
+    /// The sites of the neighboring cells in rotation order.
+    pub neighbors: Vec<usize>,
+}
+
+impl<P: FloatPointCompatible<T>, T: FloatNumber> Delaunay<P, T> {
+    /// Builds the Voronoi cell of every mesh point clipped by the mesh boundary,
+    /// see [`IntDelaunay::to_voronoi_cells`](crate::advanced::delaunay::IntDelaunay::to_voronoi_cells).
+    pub fn to_voronoi_cells(&self) -> Vec<VoronoiCell<P>> {
+        self.delaunay
+            .to_voronoi_cells()
+            .into_iter()
+            .map(|cell| VoronoiCell {
+                site: cell.site,
+                shapes: cell.shapes.to_float(&self.adapter),
+                neighbors: cell.neighbors,
+            })
+            .collect()
+    }
+}
@@ -134,4 +134,66 @@ impl IntTriangle {
         };
         Abc { v0: a, v1: b, v2: c }
     }
+
+    #[inline]
+    pub(crate) fn update_neighbor(&mut self, old_index: usize, new_index: usize) {
+        if self.neighbors[0] == old_index {
+            self.neighbors[0] = new_index;
+        } else if self.neighbors[1] == old_index {
+            self.neighbors[1] = new_index;
+        } else {
+            debug_assert_eq!(self.neighbors[2], old_index);
+            self.neighbors[2] = new_index;
+        }
+    }
+}
+
+pub(crate) trait TriangleNet {
+    fn update_neighbor(&mut self, neighbor_index: usize, old_index: usize, new_index: usize);
+    fn flip(&mut self, abc_index: usize, pcb_index: usize, abc: &Abc, pcb: &Abc);
+    fn shift(&mut self, points_offset: usize, triangle_offset: usize);
+}
+
+impl TriangleNet for [IntTriangle] {
+    #[inline]
+    fn update_neighbor(&mut self, neighbor_index: usize, old_index: usize, new_index: usize) {
+        if neighbor_index >= self.len() {
+            return;
+        }
+        self[neighbor_index].update_neighbor(old_index, new_index);
+    }
+
+    // abc and pcb are clock-wised ordered triangles with a common edge bc
+
+    // abc -> abp
+    // pcb -> pca
+    #[inline]
+    fn flip(&mut self, abc_index: usize, pcb_index: usize, abc: &Abc, pcb: &Abc) {
+        self.update_neighbor(abc.v1.neighbor, abc_index, pcb_index);
+        self.update_neighbor(pcb.v1.neighbor, pcb_index, abc_index);
+
+        let abp = &mut self[abc_index];
+        abp.neighbors[abc.v0.position] = pcb.v1.neighbor;
+        abp.neighbors[abc.v1.position] = pcb_index;
+        abp.neighbors[abc.v2.position] = abc.v2.neighbor;
+        abp.vertices[abc.v2.position] = pcb.v0.vertex;
+
+        let pca = &mut self[pcb_index];
+        pca.neighbors[pcb.v0.position] = abc.v1.neighbor;
+        pca.neighbors[pcb.v1.position] = abc_index;
+        pca.neighbors[pcb.v2.position] = pcb.v2.neighbor;
+        pca.vertices[pcb.v2.position] = abc.v0.vertex;
+    }
+
+    #[inline]
+    fn shift(&mut self, points_offset: usize, triangle_offset: usize) {
+        for t in self.iter_mut() {
+            t.vertices[0].index += points_offset;
+            t.vertices[1].index += points_offset;
+            t.vertices[2].index += points_offset;
+            t.neighbors[0] = t.neighbors[0].saturating_add(triangle_offset);
+            t.neighbors[1] = t.neighbors[1].saturating_add(triangle_offset);
+            t.neighbors[2] = t.neighbors[2].saturating_add(triangle_offset);
+        }
+    }
 }
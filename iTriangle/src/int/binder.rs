@@ -1,3 +1,4 @@
+use crate::int::constraint::SplitSegments;
 use crate::int::monotone::v_segment::VSegment;
 use i_overlay::i_float::int::point::IntPoint;
 use i_overlay::i_float::triangle::Triangle;
@@ -5,6 +6,8 @@ use i_overlay::i_shape::int::shape::IntShape;
 use i_tree::key::exp::KeyExpCollection;
 use i_tree::key::tree::KeyExpTree;
 use i_tree::ExpiredKey;
+use i_overlay::string::line::IntLine;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy)]
 struct ShapeEdge {
@@ -34,6 +37,7 @@ impl ExpiredKey<i32> for VSegment {
 
 pub(super) trait SteinerInference {
     fn group_by_shapes(&self, points: &[IntPoint]) -> Vec<Vec<IntPoint>>;
+    fn group_segments_by_shapes(&self, segments: &[IntLine]) -> Vec<Vec<IntLine>>;
 }
 
 impl SteinerInference for [IntShape] {
@@ -43,7 +47,7 @@ impl SteinerInference for [IntShape] {
         }
 
         let mut points = points.to_vec();
-        points.sort_unstable_by(|a, b| a.x.cmp(&b.x));
+        points.sort_unstable_by_key(|p| p.x);
         points.dedup();
 
         let x_min = points[0].x;
@@ -89,9 +93,9 @@ impl SteinerInference for [IntShape] {
             return vec![Vec::new(); self.len()];
         }
 
-        segments.sort_unstable_by(|s0, s1| s0.v_segment.a.cmp(&s1.v_segment.a));
-        v_edges.sort_unstable_by(|e0, e1| e0.x.cmp(&e1.x));
-        x_points.sort_unstable_by(|p0, p1| p0.x.cmp(&p1.x));
+        segments.sort_unstable_by_key(|s| s.v_segment.a);
+        v_edges.sort_unstable_by_key(|e| e.x);
+        x_points.sort_unstable_by_key(|p| p.x);
 
         let mut groups = vec![Vec::new(); self.len()];
         let capacity = segments.len().ilog2() as usize;
@@ -164,6 +168,82 @@ impl SteinerInference for [IntShape] {
 
         groups
     }
+
+    fn group_segments_by_shapes(&self, segments: &[IntLine]) -> Vec<Vec<IntLine>> {
+        let segments = segments.split_at_crossings();
+        let mut groups = vec![Vec::new(); self.len()];
+        if segments.is_empty() {
+            return groups;
+        }
+
+        let mut points = Vec::with_capacity(2 * segments.len());
+        for s in segments.iter() {
+            points.extend_from_slice(s);
+        }
+
+        // a segment end is either strictly inside a shape or is one of its vertices
+        let mut owners = HashMap::with_capacity(points.len());
+        for (shape_index, group) in self.group_by_shapes(&points).into_iter().enumerate() {
+            for p in group {
+                owners.insert(p, shape_index);
+            }
+        }
+
+        for (shape_index, shape) in self.iter().enumerate() {
+            for p in shape.iter().flatten() {
+                owners.entry(*p).or_insert(shape_index);
+            }
+        }
+
+        // a piece never crosses a contour, but a piece between two vertices may lie outside or in a hole
+        for s in segments {
+            if let (Some(&i0), Some(&i1)) = (owners.get(&s[0]), owners.get(&s[1])) {
+                if i0 == i1 && contains_middle(&self[i0], &s) {
+                    groups[i0].push(s);
+                }
+            }
+        }
+
+        groups
+    }
+}
+
+/// Tests if the middle of the segment is inside the shape or on its boundary.
+fn contains_middle(shape: &IntShape, s: &IntLine) -> bool {
+    // doubled coordinates keep the middle an int point
+    let double = |q: IntPoint| [2 * q.x as i64, 2 * q.y as i64];
+    let m = [s[0].x as i64 + s[1].x as i64, s[0].y as i64 + s[1].y as i64];
+
+    let mut winding = 0;
+    for contour in shape.iter() {
+        let mut a = if let Some(&last) = contour.last() {
+            double(last)
+        } else {
+            continue;
+        };
+        for &q in contour.iter() {
+            let b = double(q);
+            let cross = (b[0] - a[0]) as i128 * (m[1] - a[1]) as i128 - (m[0] - a[0]) as i128 * (b[1] - a[1]) as i128;
+            if cross == 0
+                && a[0].min(b[0]) <= m[0]
+                && m[0] <= a[0].max(b[0])
+                && a[1].min(b[1]) <= m[1]
+                && m[1] <= a[1].max(b[1])
+            {
+                return true;
+            }
+            if a[1] <= m[1] {
+                if m[1] < b[1] && cross > 0 {
+                    winding += 1;
+                }
+            } else if b[1] <= m[1] && cross < 0 {
+                winding -= 1;
+            }
+            a = b;
+        }
+    }
+
+    winding != 0
 }
 
 impl ShapeEdge {
@@ -208,7 +288,7 @@ mod tests {
 
     #[test]
     fn test_0() {
-        let shapes = vec![vec![path(&[[0, 0], [10, 0], [10, 10], [0, 10]])]];
+        let shapes = [vec![path(&[[0, 0], [10, 0], [10, 10], [0, 10]])]];
 
         let groups = shapes.group_by_shapes(&[
             IntPoint::new(5, 5),
@@ -221,7 +301,7 @@ mod tests {
 
     #[test]
     fn test_1() {
-        let shapes = vec![
+        let shapes = [
             vec![path(&[[0, 0], [10, 0], [10, 10], [0, 10]])],
             vec![path(&[[20, 0], [30, 0], [30, 10], [20, 10]])],
         ];
@@ -239,7 +319,7 @@ mod tests {
 
     #[test]
     fn test_2() {
-        let shapes = vec![
+        let shapes = [
             vec![path(&[[0, 0], [10, 0], [10, 10], [0, 10]])],
             vec![path(&[[0, 20], [10, 20], [10, 30], [0, 30]])],
             vec![path(&[[0, 40], [10, 40], [10, 50], [0, 50]])],
@@ -263,7 +343,7 @@ mod tests {
 
     #[test]
     fn test_3() {
-        let shapes = vec![vec![path(&[[-10, 0], [0, -10], [10, 0], [0, 10]])]];
+        let shapes = [vec![path(&[[-10, 0], [0, -10], [10, 0], [0, 10]])]];
 
         let groups = shapes.group_by_shapes(&[IntPoint::new(-3, 7)]);
 
@@ -272,7 +352,7 @@ mod tests {
 
     #[test]
     fn test_4() {
-        let shapes = vec![vec![path(&[[3, -2], [-5, 3], [0, -1], [-2, -3]])]];
+        let shapes = [vec![path(&[[3, -2], [-5, 3], [0, -1], [-2, -3]])]];
         let groups = shapes.group_by_shapes(&[IntPoint::new(0, -1)]);
 
         assert_eq!(groups[0].len(), 0);
@@ -280,7 +360,7 @@ mod tests {
 
     #[test]
     fn test_5() {
-        let shapes = vec![vec![path(&[[-1, 2], [-5, -2], [2, -2], [3, 4]])]];
+        let shapes = [vec![path(&[[-1, 2], [-5, -2], [2, -2], [3, 4]])]];
         let groups = shapes.group_by_shapes(&[IntPoint::new(1, 5)]);
 
         assert_eq!(groups[0].len(), 0);
@@ -288,7 +368,7 @@ mod tests {
 
     #[test]
     fn test_6() {
-        let shapes = vec![vec![path(&[[-5, 0], [0, -5], [5, 0], [0, 5]])]];
+        let shapes = [vec![path(&[[-5, 0], [0, -5], [5, 0], [0, 5]])]];
         let groups = shapes.group_by_shapes(&[IntPoint::new(0, 0), IntPoint::new(0, 0)]);
 
         assert_eq!(groups[0].len(), 1);
@@ -296,7 +376,7 @@ mod tests {
 
     #[test]
     fn test_7() {
-        let shapes = vec![vec![path(&[[-5, 0], [0, -5], [5, 0], [0, 5]])]];
+        let shapes = [vec![path(&[[-5, 0], [0, -5], [5, 0], [0, 5]])]];
         let groups = shapes.group_by_shapes(&[IntPoint::new(-4, 3), IntPoint::new(0, 3)]);
 
         assert_eq!(groups[0].len(), 1);
@@ -304,7 +384,7 @@ mod tests {
 
     #[test]
     fn test_8() {
-        let shapes = vec![vec![path(&[
+        let shapes = [vec![path(&[
             [1, 0],
             [-4, -2],
             [3, 0],
@@ -319,7 +399,7 @@ mod tests {
 
     #[test]
     fn test_9() {
-        let shapes = vec![vec![path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]])]];
+        let shapes = [vec![path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]])]];
         let groups = shapes.group_by_shapes(&[
             IntPoint::new(-10, 10),
             IntPoint::new(-10, 5),
@@ -335,4 +415,21 @@ mod tests {
 
         assert_eq!(groups[0].len(), 0);
     }
+
+    #[test]
+    fn test_segments_0() {
+        // the chord of the notch and the diagonal of the hole join vertices but lie outside
+        let shapes = [vec![
+            path(&[[-10, -10], [10, -10], [10, 10], [0, 2], [-10, 10]]),
+            path(&[[-5, -8], [-5, -3], [5, -3], [5, -8]]),
+        ]];
+        let segments = [
+            [IntPoint::new(-10, 10), IntPoint::new(10, 10)],
+            [IntPoint::new(-5, -8), IntPoint::new(5, -3)],
+            [IntPoint::new(-10, -10), IntPoint::new(-5, -8)],
+        ];
+        let groups = shapes.group_segments_by_shapes(&segments);
+
+        assert_eq!(groups[0], vec![[IntPoint::new(-10, -10), IntPoint::new(-5, -8)]]);
+    }
 }
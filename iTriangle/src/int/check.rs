@@ -0,0 +1,152 @@
+use crate::int::error::InvalidInput;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::area::Area;
+use i_overlay::i_shape::int::shape::{IntContour, IntShape};
+
+#[derive(Clone, Copy)]
+struct Edge {
+    a: IntPoint,
+    b: IntPoint,
+}
+
+pub(super) trait ShapeCheck {
+    /// Verifies that the shape satisfies the requirements of the unchecked triangulation.
+    ///
+    /// Returns the doubled signed area of the shape.
+    fn check(&self, shape_index: usize) -> Result<i64, InvalidInput>;
+}
+
+impl ShapeCheck for IntShape {
+    fn check(&self, shape_index: usize) -> Result<i64, InvalidInput> {
+        let mut area = 0;
+        for (contour_index, contour) in self.iter().enumerate() {
+            let contour_area = contour.area_two();
+            let has_empty_edge = contour.windows(2).any(|w| w[0] == w[1]) || contour.first() == contour.last();
+            if contour.len() < 3 || contour_area == 0 || has_empty_edge {
+                return Err(InvalidInput::DegenerateContour { shape: shape_index, contour: contour_index });
+            }
+            // outer contour is counter-clockwise (negative area), holes are clockwise
+            if (contour_index == 0) != (contour_area < 0) {
+                return Err(InvalidInput::WrongOrientation { shape: shape_index, contour: contour_index });
+            }
+            area += contour_area;
+        }
+
+        if self.iter().any(has_self_touches) || has_intersections(self) {
+            return Err(InvalidInput::SelfIntersection { shape: shape_index });
+        }
+
+        // contours do not cross, so one point is enough to locate a hole
+        for (contour_index, hole) in self.iter().enumerate().skip(1) {
+            let p = [hole[0].x as i64 + hole[1].x as i64, hole[0].y as i64 + hole[1].y as i64];
+            let is_inside_outer = is_inside(&self[0], p);
+            let is_inside_hole = self
+                .iter()
+                .enumerate()
+                .skip(1)
+                .any(|(i, other)| i != contour_index && is_inside(other, p));
+            if !is_inside_outer || is_inside_hole {
+                return Err(InvalidInput::MisplacedHole { shape: shape_index, contour: contour_index });
+            }
+        }
+
+        Ok(area)
+    }
+}
+
+/// A contour may touch other contours, but it must not pass through the same point twice.
+fn has_self_touches(contour: &IntContour) -> bool {
+    let mut points = contour.clone();
+    points.sort_unstable();
+    points.windows(2).any(|w| w[0] == w[1])
+}
+
+fn has_intersections(shape: &IntShape) -> bool {
+    let mut edges = Vec::with_capacity(shape.iter().map(Vec::len).sum());
+    for contour in shape.iter() {
+        let mut a = *contour.last().unwrap();
+        for &b in contour.iter() {
+            edges.push(if a < b { Edge { a, b } } else { Edge { a: b, b: a } });
+            a = b;
+        }
+    }
+    edges.sort_unstable_by_key(|e| e.a);
+
+    // edges are sorted by a, so a.x is a lower bound for x
+    for (i, ei) in edges.iter().enumerate() {
+        let (min_y, max_y) = if ei.a.y < ei.b.y { (ei.a.y, ei.b.y) } else { (ei.b.y, ei.a.y) };
+        for ej in edges.iter().skip(i + 1) {
+            if ej.a.x > ei.b.x {
+                break;
+            }
+            if ej.a.y.max(ej.b.y) < min_y || ej.a.y.min(ej.b.y) > max_y {
+                continue;
+            }
+            if is_crossing(ei, ej) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns true if the edges have a common point that is not a shared end.
+fn is_crossing(e0: &Edge, e1: &Edge) -> bool {
+    let o0 = orientation(e0.a, e0.b, e1.a);
+    let o1 = orientation(e0.a, e0.b, e1.b);
+    let o2 = orientation(e1.a, e1.b, e0.a);
+    let o3 = orientation(e1.a, e1.b, e0.b);
+
+    if o0 * o1 > 0 || o2 * o3 > 0 {
+        return false;
+    }
+
+    if o0 == 0 && o1 == 0 {
+        // collinear, edges are ordered so compare ends
+        return e1.a < e0.b && e0.a < e1.b;
+    }
+
+    if o0 != 0 && o1 != 0 && o2 != 0 && o3 != 0 {
+        return true;
+    }
+
+    // one end touches the other edge, it is only allowed at the other edge ends
+    let is_shared = |p: IntPoint, e: &Edge| p == e.a || p == e.b;
+    (o0 == 0 && !is_shared(e1.a, e0))
+        || (o1 == 0 && !is_shared(e1.b, e0))
+        || (o2 == 0 && !is_shared(e0.a, e1))
+        || (o3 == 0 && !is_shared(e0.b, e1))
+}
+
+#[inline]
+fn orientation(a: IntPoint, b: IntPoint, p: IntPoint) -> i32 {
+    let abx = b.x as i128 - a.x as i128;
+    let aby = b.y as i128 - a.y as i128;
+    let apx = p.x as i128 - a.x as i128;
+    let apy = p.y as i128 - a.y as i128;
+    (abx * apy - aby * apx).signum() as i32
+}
+
+/// Even-odd test for a point given in doubled coordinates, the point must not be on the contour.
+fn is_inside(contour: &IntContour, p: [i64; 2]) -> bool {
+    let mut inside = false;
+    let mut a = *contour.last().unwrap();
+    for &b in contour.iter() {
+        let ay = 2 * a.y as i64;
+        let by = 2 * b.y as i64;
+        if (ay > p[1]) != (by > p[1]) {
+            let ax = 2 * a.x as i128;
+            let bx = 2 * b.x as i128;
+            // x of the crossing is ax + (p.y - ay) * (bx - ax) / (by - ay)
+            let dy = (by - ay) as i128;
+            let lhs = (p[0] as i128 - ax) * dy;
+            let rhs = (p[1] as i128 - ay as i128) * (bx - ax);
+            if (dy > 0 && lhs < rhs) || (dy < 0 && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+        a = b;
+    }
+    inside
+}
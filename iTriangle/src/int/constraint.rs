@@ -0,0 +1,848 @@
+use crate::geom::triangle::{IntTriangle, TriangleNet};
+use crate::index::Index;
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::shape::{IntContour, IntShape};
+use i_overlay::string::line::IntLine;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A set of undirected mesh edges (pairs of point indices) which must never be removed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConstrainedEdges {
+    edges: HashSet<(usize, usize)>,
+}
+
+impl ConstrainedEdges {
+    #[inline]
+    fn key(a: usize, b: usize) -> (usize, usize) {
+        if a < b { (a, b) } else { (b, a) }
+    }
+
+    #[inline]
+    pub(crate) fn insert(&mut self, a: usize, b: usize) {
+        self.edges.insert(Self::key(a, b));
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, a: usize, b: usize) -> bool {
+        !self.edges.is_empty() && self.edges.contains(&Self::key(a, b))
+    }
+
+    /// Replaces the edge `ab` by `am` and `mb` if `ab` is constrained.
+    #[inline]
+    pub(crate) fn split(&mut self, a: usize, b: usize, m: usize) {
+        if self.edges.is_empty() || !self.edges.remove(&Self::key(a, b)) {
+            return;
+        }
+        self.insert(a, m);
+        self.insert(m, b);
+    }
+
+    #[inline]
+    pub(crate) fn shift(&mut self, points_offset: usize) {
+        if self.edges.is_empty() || points_offset == 0 {
+            return;
+        }
+        self.edges = self
+            .edges
+            .drain()
+            .map(|(a, b)| (a + points_offset, b + points_offset))
+            .collect();
+    }
+
+    /// Moves the edges to the new point indices, `map[old] = new`.
+    #[inline]
+    pub(crate) fn remap(&mut self, map: &[usize]) {
+        if self.edges.is_empty() {
+            return;
+        }
+        self.edges = self
+            .edges
+            .drain()
+            .map(|(a, b)| Self::key(map[a], map[b]))
+            .collect();
+    }
+
+    #[inline]
+    pub(crate) fn append(&mut self, other: &mut ConstrainedEdges) {
+        self.edges.extend(other.edges.drain());
+    }
+
+    #[cfg(any(test, feature = "rayon"))]
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &(usize, usize)> {
+        self.edges.iter()
+    }
+}
+
+pub(crate) trait SplitSegments {
+    /// Splits segments at all mutual crossings and touches.
+    ///
+    /// A crossing point is rounded to an int point, so the pieces may cross again near it.
+    /// Such pieces are split again until no two pieces cross anywhere but at a shared end.
+    ///
+    /// Degenerate segments are dropped, the result has no duplicates and
+    /// every segment is ordered so that `a < b`.
+    fn split_at_crossings(&self) -> Vec<IntLine>;
+}
+
+impl SplitSegments for [IntLine] {
+    fn split_at_crossings(&self) -> Vec<IntLine> {
+        let mut segments: Vec<IntLine> = self
+            .iter()
+            .filter(|s| s[0] != s[1])
+            .map(|s| if s[0] < s[1] { [s[0], s[1]] } else { [s[1], s[0]] })
+            .collect();
+
+        segments.sort_unstable();
+        segments.dedup();
+
+        // every round cuts some pieces into shorter ones with int ends, so the rounds end
+        loop {
+            let mut splits: Vec<Vec<IntPoint>> = vec![Vec::new(); segments.len()];
+
+            // segments are sorted by a, so a.x is a lower bound for x
+            for i in 0..segments.len() {
+                let si = segments[i];
+                let max_x = si[1].x;
+                for (j, &sj) in segments.iter().enumerate().skip(i + 1) {
+                    if sj[0].x > max_x {
+                        break;
+                    }
+                    cross_segments(si, sj, i, j, &mut splits);
+                }
+            }
+
+            if splits.iter().all(|points| points.is_empty()) {
+                return segments;
+            }
+
+            segments = cut_segments(&segments, &mut splits);
+        }
+    }
+}
+
+/// Cuts every segment at its split points, the result is sorted and has no duplicates.
+fn cut_segments(segments: &[IntLine], splits: &mut [Vec<IntPoint>]) -> Vec<IntLine> {
+    let mut result = Vec::with_capacity(segments.len());
+    for (s, points) in segments.iter().zip(splits.iter_mut()) {
+        if points.is_empty() {
+            result.push(*s);
+            continue;
+        }
+        points.sort_unstable();
+        points.dedup();
+
+        // rounded points with the same x may be out of order along the segment
+        points.sort_unstable_by_key(|p| s[0].sqr_distance(*p));
+
+        let mut a = s[0];
+        for &p in points.iter() {
+            if p != a && p != s[1] {
+                result.push(if a < p { [a, p] } else { [p, a] });
+                a = p;
+            }
+        }
+        result.push(if a < s[1] { [a, s[1]] } else { [s[1], a] });
+    }
+
+    result.sort_unstable();
+    result.dedup();
+
+    result
+}
+
+fn cross_segments(s0: IntLine, s1: IntLine, i0: usize, i1: usize, splits: &mut [Vec<IntPoint>]) {
+    let d0 = s0[1].subtract(s0[0]);
+    let d1 = s1[1].subtract(s1[0]);
+    let d01 = s1[0].subtract(s0[0]);
+
+    let denominator = d0.cross_product(d1);
+    if denominator == 0 {
+        if d01.cross_product(d0) != 0 {
+            // parallel
+            return;
+        }
+        // collinear, split each one by the ends of the other
+        for p in s1 {
+            if s0[0] < p && p < s0[1] {
+                splits[i0].push(p);
+            }
+        }
+        for p in s0 {
+            if s1[0] < p && p < s1[1] {
+                splits[i1].push(p);
+            }
+        }
+        return;
+    }
+
+    let (t, u, d) = if denominator > 0 {
+        (d01.cross_product(d1), d01.cross_product(d0), denominator)
+    } else {
+        (-d01.cross_product(d1), -d01.cross_product(d0), -denominator)
+    };
+
+    if t < 0 || t > d || u < 0 || u > d {
+        return;
+    }
+
+    let p = if t == 0 {
+        s0[0]
+    } else if t == d {
+        s0[1]
+    } else if u == 0 {
+        s1[0]
+    } else if u == d {
+        s1[1]
+    } else {
+        let x = s0[0].x as i128 + div_round(d0.x as i128 * t as i128, d as i128);
+        let y = s0[0].y as i128 + div_round(d0.y as i128 * t as i128, d as i128);
+        IntPoint::new(x as i32, y as i32)
+    };
+
+    if s0[0] != p && s0[1] != p {
+        splits[i0].push(p);
+    }
+    if s1[0] != p && s1[1] != p {
+        splits[i1].push(p);
+    }
+}
+
+/// `a / b` rounded to the nearest integer, halves away from zero.
+#[inline]
+pub(crate) fn div_round(a: i128, b: i128) -> i128 {
+    debug_assert!(b > 0);
+    if a >= 0 {
+        (2 * a + b) / (2 * b)
+    } else {
+        -((-2 * a + b) / (2 * b))
+    }
+}
+
+/// Collects segment ends which are not vertices of the contours, they must become Steiner points.
+pub(crate) fn segment_inner_points(contours: &[IntContour], segments: &[IntLine]) -> Vec<IntPoint> {
+    if segments.is_empty() {
+        return Vec::new();
+    }
+    let vertices: HashSet<IntPoint> = contours.iter().flatten().copied().collect();
+    let mut points = Vec::with_capacity(2 * segments.len());
+    for s in segments.iter() {
+        for p in s.iter() {
+            if !vertices.contains(p) {
+                points.push(*p);
+            }
+        }
+    }
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+/// Cuts segments where they cross the contour edges and inserts the cut points into the contours,
+/// so a segment leaving its shape keeps the part inside and ends on the boundary.
+///
+/// A cut point is rounded to an int point, so a slanted contour edge may bend by less than a unit.
+/// The bent edges and the pieces are cut again until nothing crosses anywhere but at shared points.
+/// The result is split at mutual crossings, see [`SplitSegments::split_at_crossings`].
+pub(crate) fn clip_segments(shapes: &mut [IntShape], segments: &[IntLine]) -> Vec<IntLine> {
+    let mut segments = segments.split_at_crossings();
+    if segments.is_empty() {
+        return segments;
+    }
+
+    loop {
+        let mut edges = Vec::new();
+        for contour in shapes.iter().flatten() {
+            let mut a = *contour.last().unwrap();
+            for &b in contour.iter() {
+                edges.push([a, b]);
+                a = b;
+            }
+        }
+
+        let n = segments.len();
+        let mut splits = vec![Vec::new(); n + edges.len()];
+        for (i, s) in segments.iter().enumerate() {
+            for (j, e) in edges.iter().enumerate() {
+                let e = if e[0] < e[1] { *e } else { [e[1], e[0]] };
+                if e[0].x <= s[1].x && s[0].x <= e[1].x {
+                    cross_segments(*s, e, i, n + j, &mut splits);
+                }
+            }
+        }
+
+        if splits.iter().all(|points| points.is_empty()) {
+            return segments;
+        }
+
+        // the cut points go into the contours in the edge order
+        let mut j = n;
+        for contour in shapes.iter_mut().flatten() {
+            let mut result = Vec::with_capacity(contour.len());
+            let mut a = *contour.last().unwrap();
+            for &b in contour.iter() {
+                let points = &mut splits[j];
+                points.retain(|&p| p != a && p != b);
+                points.sort_unstable();
+                points.dedup();
+                points.sort_unstable_by_key(|p| a.sqr_distance(*p));
+                result.extend_from_slice(points);
+                result.push(b);
+                a = b;
+                j += 1;
+            }
+            *contour = result;
+        }
+
+        splits.truncate(n);
+        segments = cut_segments(&segments, &mut splits).split_at_crossings();
+    }
+}
+
+impl RawIntTriangulation {
+    /// Forces every segment to become a mesh edge and marks it as constrained.
+    ///
+    /// Segment ends must be mesh points and segments must not cross each other or the mesh boundary,
+    /// see [`SplitSegments::split_at_crossings`] and [`clip_segments`]. Invalid segments are skipped.
+    pub(crate) fn insert_constraints(&mut self, segments: &[IntLine]) {
+        if segments.is_empty() || self.triangles.is_empty() {
+            return;
+        }
+
+        let mut map = HashMap::with_capacity(self.points.len());
+        for (i, p) in self.points.iter().enumerate() {
+            map.insert(*p, i);
+        }
+
+        let mut builder = ConstraintBuilder::new(&mut self.triangles, &self.points, &mut self.constraints);
+        for s in segments.iter() {
+            if let (Some(&a), Some(&b)) = (map.get(&s[0]), map.get(&s[1])) {
+                builder.insert(a, b);
+            }
+        }
+    }
+}
+
+enum Start {
+    Exist,
+    Vertex(usize),
+    Cross(usize, usize, usize),
+}
+
+struct ConstraintBuilder<'a> {
+    triangles: &'a mut [IntTriangle],
+    points: &'a [IntPoint],
+    constraints: &'a mut ConstrainedEdges,
+    vertex_triangle: Vec<usize>,
+    fan: Vec<usize>,
+}
+
+impl<'a> ConstraintBuilder<'a> {
+    fn new(
+        triangles: &'a mut [IntTriangle],
+        points: &'a [IntPoint],
+        constraints: &'a mut ConstrainedEdges,
+    ) -> Self {
+        let mut vertex_triangle = vec![usize::MAX; points.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            for v in t.vertices.iter() {
+                vertex_triangle[v.index] = i;
+            }
+        }
+
+        Self {
+            triangles,
+            points,
+            constraints,
+            vertex_triangle,
+            fan: Vec::with_capacity(16),
+        }
+    }
+
+    fn insert(&mut self, a: usize, b: usize) {
+        let mut stack = vec![(a, b)];
+        while let Some((u, v)) = stack.pop() {
+            if u == v {
+                continue;
+            }
+            match self.start(u, v) {
+                None => continue,
+                Some(Start::Exist) => self.constraints.insert(u, v),
+                Some(Start::Vertex(w)) => {
+                    self.constraints.insert(u, w);
+                    stack.push((w, v));
+                }
+                Some(Start::Cross(t, j, k)) => {
+                    let mut edges = VecDeque::with_capacity(8);
+                    let Some(w) = self.walk(u, v, t, j, k, &mut edges) else {
+                        continue;
+                    };
+
+                    if self.flip_edges(u, w, edges) {
+                        self.constraints.insert(u, w);
+                    }
+
+                    if w != v {
+                        stack.push((w, v));
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_fan(&mut self, u: usize) {
+        self.fan.clear();
+        let first = self.vertex_triangle[u];
+        if first.is_not_nil() {
+            // counter-clockwise
+            let mut t = first;
+            loop {
+                self.fan.push(t);
+                let triangle = &self.triangles[t];
+                let i = triangle.vertex_position(u);
+                t = triangle.neighbors[(i + 1) % 3];
+                if !t.is_not_nil() || t == first {
+                    break;
+                }
+            }
+            if t == first {
+                return;
+            }
+
+            // clockwise
+            let triangle = &self.triangles[first];
+            let i = triangle.vertex_position(u);
+            t = triangle.neighbors[(i + 2) % 3];
+            while t.is_not_nil() {
+                self.fan.push(t);
+                let triangle = &self.triangles[t];
+                let i = triangle.vertex_position(u);
+                t = triangle.neighbors[(i + 2) % 3];
+            }
+        }
+    }
+
+    fn start(&mut self, u: usize, v: usize) -> Option<Start> {
+        self.collect_fan(u);
+        let pu = self.points[u];
+        let pv = self.points[v];
+        let uv = pv.subtract(pu);
+        for &t in self.fan.iter() {
+            let triangle = &self.triangles[t];
+            let i = triangle.vertex_position(u);
+            let vj = triangle.vertices[(i + 1) % 3];
+            let vk = triangle.vertices[(i + 2) % 3];
+            if vj.index == v || vk.index == v {
+                return Some(Start::Exist);
+            }
+
+            let uj = vj.point.subtract(pu);
+            let uk = vk.point.subtract(pu);
+
+            let sj = uj.cross_product(uv);
+            if sj == 0 && uj.dot_product(uv) > 0 {
+                return if uj.sqr_length() < uv.sqr_length() {
+                    Some(Start::Vertex(vj.index))
+                } else {
+                    None
+                };
+            }
+
+            let sk = uv.cross_product(uk);
+            if sk == 0 && uk.dot_product(uv) > 0 {
+                return if uk.sqr_length() < uv.sqr_length() {
+                    Some(Start::Vertex(vk.index))
+                } else {
+                    None
+                };
+            }
+
+            if sj > 0 && sk > 0 {
+                return Some(Start::Cross(t, vj.index, vk.index));
+            }
+        }
+
+        None
+    }
+
+    // collect all edges crossed by segment uw, where w is v or the first vertex lying on uv
+    fn walk(
+        &self,
+        u: usize,
+        v: usize,
+        mut t: usize,
+        mut j: usize,
+        mut k: usize,
+        edges: &mut VecDeque<(usize, usize)>,
+    ) -> Option<usize> {
+        let pu = self.points[u];
+        let pv = self.points[v];
+        loop {
+            if self.constraints.contains(j, k) {
+                return None;
+            }
+            let triangle = &self.triangles[t];
+            let n = triangle.neighbors[triangle.other_vertex(j, k)];
+            if !n.is_not_nil() {
+                // leave the shape
+                return None;
+            }
+
+            edges.push_back((j, k));
+
+            let next = &self.triangles[n];
+            let w = next.vertices[next.other_vertex(j, k)];
+            if w.index == v {
+                return Some(v);
+            }
+
+            let side = Self::side(pu, pv, w.point);
+            if side == 0 {
+                return Some(w.index);
+            }
+
+            if side > 0 {
+                k = w.index;
+            } else {
+                j = w.index;
+            }
+            t = n;
+        }
+    }
+
+    fn flip_edges(&mut self, u: usize, v: usize, mut edges: VecDeque<(usize, usize)>) -> bool {
+        let pu = self.points[u];
+        let pv = self.points[v];
+
+        let n = edges.len() + 1;
+        let mut guard = 4 * n * n + 16;
+
+        while let Some((a, b)) = edges.pop_front() {
+            if guard == 0 {
+                return false;
+            }
+            guard -= 1;
+
+            let Some((abc_index, pcb_index)) = self.edge_triangles(a, b) else {
+                return false;
+            };
+
+            let abc = self.triangles[abc_index].abc_by_neighbor(pcb_index);
+            let pcb = self.triangles[pcb_index].abc_by_neighbor(abc_index);
+
+            let pa = abc.v0.vertex.point;
+            let pp = pcb.v0.vertex.point;
+
+            // the quad must be strictly convex
+            let sb = Self::side(pa, pp, abc.v1.vertex.point);
+            let sc = Self::side(pa, pp, abc.v2.vertex.point);
+            if sb == 0 || sc == 0 || (sb > 0) == (sc > 0) {
+                edges.push_back((a, b));
+                continue;
+            }
+
+            self.triangles.flip(abc_index, pcb_index, &abc, &pcb);
+            self.vertex_triangle[abc.v1.vertex.index] = abc_index;
+            self.vertex_triangle[abc.v2.vertex.index] = pcb_index;
+
+            let c = abc.v0.vertex.index;
+            let d = pcb.v0.vertex.index;
+
+            if c == u || c == v || d == u || d == v {
+                continue;
+            }
+
+            let s0 = Self::side(pu, pv, pa);
+            let s1 = Self::side(pu, pv, pp);
+            if s0 != 0 && s1 != 0 && (s0 > 0) != (s1 > 0) {
+                edges.push_back((c, d));
+            }
+        }
+
+        true
+    }
+
+    fn edge_triangles(&mut self, a: usize, b: usize) -> Option<(usize, usize)> {
+        self.collect_fan(a);
+        for &t in self.fan.iter() {
+            let triangle = &self.triangles[t];
+            if triangle.vertices.iter().any(|v| v.index == b) {
+                let n = triangle.neighbors[triangle.other_vertex(a, b)];
+                return if n.is_not_nil() { Some((t, n)) } else { None };
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn side(a: IntPoint, b: IntPoint, p: IntPoint) -> i64 {
+        b.subtract(a).cross_product(p.subtract(a)).signum()
+    }
+}
+
+impl IntTriangle {
+    #[inline]
+    pub(crate) fn vertex_position(&self, index: usize) -> usize {
+        if self.vertices[0].index == index {
+            0
+        } else if self.vertices[1].index == index {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::constraint::SplitSegments;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::int::triangulation::RawIntTriangulation;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use i_overlay::string::line::IntLine;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn line(a: [i32; 2], b: [i32; 2]) -> IntLine {
+        [IntPoint::new(a[0], a[1]), IntPoint::new(b[0], b[1])]
+    }
+
+    fn has_edge(raw: &RawIntTriangulation, a: IntPoint, b: IntPoint) -> bool {
+        raw.triangles.iter().any(|t| {
+            let mut has_a = false;
+            let mut has_b = false;
+            for v in t.vertices.iter() {
+                has_a |= v.point == a;
+                has_b |= v.point == b;
+            }
+            has_a && has_b
+        })
+    }
+
+    fn validate(raw: &RawIntTriangulation) {
+        for (i, t) in raw.triangles.iter().enumerate() {
+            let a = t.vertices[0].point;
+            let b = t.vertices[1].point;
+            let c = t.vertices[2].point;
+            assert!(Triangle::area_two_point(a, b, c) < 0);
+            for &n in t.neighbors.iter() {
+                if n < raw.triangles.len() {
+                    assert!(raw.triangles[n].neighbors.contains(&i));
+                }
+            }
+        }
+        for &(a, b) in raw.constraints.iter() {
+            assert!(has_edge(raw, raw.points[a], raw.points[b]));
+        }
+    }
+
+    // segments share more than an end
+    fn is_crossing(a: &IntLine, b: &IntLine) -> bool {
+        let side = |s: &IntLine, p: IntPoint| s[1].subtract(s[0]).cross_product(p.subtract(s[0])).signum();
+        let (a0, a1) = (side(a, b[0]), side(a, b[1]));
+        let (b0, b1) = (side(b, a[0]), side(b, a[1]));
+        if a0 == 0 && a1 == 0 {
+            // collinear, they overlap if the ends of one lie strictly inside the other
+            let inside = |s: &IntLine, p: IntPoint| s[0].min(s[1]) < p && p < s[0].max(s[1]);
+            return inside(a, b[0]) || inside(a, b[1]) || inside(b, a[0]) || inside(b, a[1]);
+        }
+        let is_shared = a[0] == b[0] || a[0] == b[1] || a[1] == b[0] || a[1] == b[1];
+        a0 * a1 <= 0 && b0 * b1 <= 0 && !is_shared
+    }
+
+    fn area(raw: &RawIntTriangulation) -> i64 {
+        raw.triangles.iter().fold(0, |s, t| {
+            s + Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point)
+        })
+    }
+
+    #[test]
+    fn test_0() {
+        let segments = [line([0, 0], [10, 10]), line([0, 10], [10, 0])];
+        let result = segments.split_at_crossings();
+        assert_eq!(result.len(), 4);
+    }
+
+    #[test]
+    fn test_1() {
+        let segments = [line([0, 0], [10, 0]), line([5, 0], [15, 0]), line([5, -5], [5, 0])];
+        let result = segments.split_at_crossings();
+        assert_eq!(
+            result,
+            vec![line([0, 0], [5, 0]), line([5, -5], [5, 0]), line([5, 0], [10, 0]), line([10, 0], [15, 0])]
+        );
+    }
+
+    #[test]
+    fn test_2() {
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let a = IntPoint::new(10, 0);
+        let b = IntPoint::new(0, 10);
+        let c = IntPoint::new(0, 0);
+        let d = IntPoint::new(10, 10);
+
+        let raw = contour.triangulate_with_constraints(&[[a, b]]);
+        validate(&raw);
+        assert!(has_edge(&raw, a, b));
+
+        let raw = contour.triangulate_with_constraints(&[[c, d]]);
+        validate(&raw);
+        assert!(has_edge(&raw, c, d));
+        assert_eq!(area(&raw), contour.area_two());
+    }
+
+    #[test]
+    fn test_3() {
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let segments = [line([-5, -5], [5, 5]), line([-5, 5], [5, -5])];
+
+        let raw = contour.triangulate_with_constraints(&segments);
+        validate(&raw);
+        assert_eq!(raw.points.len(), 9);
+        assert_eq!(raw.constraints.iter().count(), 4);
+        assert_eq!(area(&raw), contour.area_two());
+    }
+
+    #[test]
+    fn test_4() {
+        // segments leaving the shape are cut at the boundary
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let segments = [line([-5, 0], [15, 0]), line([-5, -5], [5, -5]), line([-15, 5], [15, 3])];
+
+        let raw = contour.triangulate_with_constraints(&segments);
+        validate(&raw);
+        assert_eq!(raw.constraints.iter().count(), 3);
+        assert!(has_edge(&raw, IntPoint::new(-5, 0), IntPoint::new(10, 0)));
+        assert!(has_edge(&raw, IntPoint::new(-10, 5), IntPoint::new(10, 3)));
+        assert!(raw.points.iter().all(|p| p.x.abs() <= 10));
+        assert_eq!(area(&raw), contour.area_two());
+    }
+
+    #[test]
+    fn test_5() {
+        // constrained edges survive delaunay and refinement
+        let contour = path(&[[0, 0], [100, 0], [100, 100], [0, 100]]);
+        let a = IntPoint::new(5, 50);
+        let b = IntPoint::new(95, 52);
+        let mut delaunay = contour
+            .triangulate_with_constraints(&[[a, b]])
+            .into_delaunay();
+
+        let is_constrained = |d: &crate::advanced::delaunay::IntDelaunay| {
+            d.constraints.iter().all(|&(i, j)| {
+                d.triangles.iter().any(|t| {
+                    t.vertices.iter().any(|v| v.index == i) && t.vertices.iter().any(|v| v.index == j)
+                })
+            })
+        };
+
+        assert!(is_constrained(&delaunay));
+
+        delaunay.refine_with_circumcenters_mut(20);
+        assert!(is_constrained(&delaunay));
+        assert!(delaunay.constraints.iter().count() > 1);
+    }
+
+    #[test]
+    fn test_6() {
+        // the crossing point (5, 1.5) is rounded, both segments bend to it
+        let contour = path(&[[-10, -10], [20, -10], [20, 10], [-10, 10]]);
+        let segments = [line([0, 0], [10, 3]), line([0, 3], [10, 0])];
+        let m = IntPoint::new(5, 2);
+
+        let raw = contour.triangulate_with_constraints(&segments);
+        validate(&raw);
+        assert_eq!(raw.constraints.iter().count(), 4);
+        for s in segments.iter() {
+            assert!(has_edge(&raw, s[0], m));
+            assert!(has_edge(&raw, m, s[1]));
+        }
+        assert_eq!(area(&raw), contour.area_two());
+    }
+
+    #[test]
+    fn test_7() {
+        // the rounded crossing points bend the pieces over each other
+        let segments = [line([1, 3], [8, 9]), line([2, 2], [6, 7]), line([3, 5], [10, 7])];
+        let result = segments.split_at_crossings();
+        for (i, a) in result.iter().enumerate() {
+            for b in result.iter().skip(i + 1) {
+                assert!(!is_crossing(a, b));
+            }
+        }
+
+        let contour = path(&[[-10, -10], [20, -10], [20, 10], [-10, 10]]);
+        let raw = contour.triangulate_with_constraints(&segments);
+        validate(&raw);
+        assert_eq!(raw.constraints.iter().count(), result.len());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let contour = path(&[[-50, -50], [50, -50], [50, 50], [-50, 50]]);
+        let shape_area = contour.area_two();
+        let mut rng = rand::rng();
+        for _ in 0..2_000 {
+            let n = rng.random_range(1..6);
+            let mut segments = Vec::with_capacity(n);
+            for _ in 0..n {
+                let a = IntPoint::new(rng.random_range(-40..=40), rng.random_range(-40..=40));
+                let b = IntPoint::new(rng.random_range(-40..=40), rng.random_range(-40..=40));
+                segments.push([a, b]);
+            }
+
+            let pieces = segments.split_at_crossings();
+            for (i, a) in pieces.iter().enumerate() {
+                for b in pieces.iter().skip(i + 1) {
+                    assert!(!is_crossing(a, b));
+                }
+            }
+
+            let raw = contour.triangulate_with_constraints(&segments);
+            validate(&raw);
+            assert_eq!(area(&raw), shape_area);
+            assert_eq!(raw.constraints.iter().count(), pieces.len());
+
+            let delaunay = raw.into_delaunay();
+            for &(a, b) in delaunay.constraints.iter() {
+                assert!(delaunay.triangles.iter().any(|t| {
+                    t.vertices.iter().any(|v| v.index == a) && t.vertices.iter().any(|v| v.index == b)
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_1() {
+        // segments leave an L shape, their inner parts are kept
+        let contour = path(&[[-50, -50], [50, -50], [50, 0], [0, 0], [0, 50], [-50, 50]]);
+        let shape_area = contour.area_two();
+        let mut rng = rand::rng();
+        for _ in 0..2_000 {
+            let n = rng.random_range(1..6);
+            let mut segments = Vec::with_capacity(n);
+            for _ in 0..n {
+                let a = IntPoint::new(rng.random_range(-80..=80), rng.random_range(-80..=80));
+                let b = IntPoint::new(rng.random_range(-80..=80), rng.random_range(-80..=80));
+                segments.push([a, b]);
+            }
+
+            let raw = contour.triangulate_with_constraints(&segments);
+            validate(&raw);
+            assert_eq!(area(&raw), shape_area);
+            assert!(raw.points.iter().all(|p| p.x.abs() <= 50 && p.y.abs() <= 50));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::int::monotone::chain_builder::fill_chain_vertices;
+use crate::int::monotone::chain_vertex::ChainVertex;
+use crate::int::monotone::mesh_builder::{SweepStore, TriangleMeshBuilder};
+use crate::int::error::TriangulationError;
+use crate::int::triangulation::{check_index_type, IndexType};
+use i_overlay::i_float::adapter::FloatPointAdapter;
+use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use i_overlay::i_float::float::number::FloatNumber;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::shape::{IntContour, IntShape};
+
+/// Scratch buffers reused between triangulation calls.
+///
+/// Keep one context per thread and pass it to the `*_into` methods of
+/// [`Triangulator`](crate::int::triangulator::Triangulator) (or the float `*_into` traits methods).
+/// Once the buffers have grown to fit the biggest shape, unchecked triangulation
+/// does not allocate anymore. The only exception is a shape with coincident vertices
+/// (e.g. a hole touching its outer contour), which needs a small temporary buffer.
+pub struct TriangulatorContext {
+    vertices: Vec<ChainVertex>,
+    builder: TriangleMeshBuilder,
+    sweep: SweepStore,
+    int_shape: IntShape,
+}
+
+impl TriangulatorContext {
+    /// Creates an empty context, its buffers grow on demand.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            builder: TriangleMeshBuilder::with_triangles_count(0),
+            sweep: SweepStore::default(),
+            int_shape: Vec::new(),
+        }
+    }
+
+    /// Triangulates valid paths with Steiner points and appends the result to `points` and `indices`.
+    ///
+    /// On failure nothing is appended.
+    pub(crate) fn append<P, I: IndexType, F: Fn(IntPoint) -> P>(
+        &mut self,
+        paths: &[IntContour],
+        steiner_points: &[IntPoint],
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+        map: F,
+    ) -> Result<(), TriangulationError> {
+        let count = self.sweep(paths, steiner_points);
+        if count == 0 {
+            return Ok(());
+        }
+
+        let offset = points.len();
+        check_index_type::<I>(offset + count)?;
+
+        indices.reserve(3 * self.builder.triangles.len());
+        for t in self.builder.triangles.iter() {
+            for v in t.vertices.iter() {
+                indices.push(I::try_from(offset + v.index).unwrap_or(I::ZERO));
+            }
+        }
+
+        points.reserve(count);
+        let mut index = usize::MAX;
+        for v in self.vertices.iter() {
+            if v.index != index {
+                index = v.index;
+                points.push(map(v.this));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads float contours into the reused int shape buffer.
+    ///
+    /// Returns the number of loaded contours, the buffer may keep extra contours from previous calls.
+    pub(crate) fn load_shape<'a, P: FloatPointCompatible<T> + 'a, T: FloatNumber>(
+        &mut self,
+        contours: impl Iterator<Item = &'a [P]>,
+        adapter: &FloatPointAdapter<P, T>,
+    ) -> usize {
+        let mut count = 0;
+        for contour in contours {
+            if count == self.int_shape.len() {
+                self.int_shape.push(Vec::with_capacity(contour.len()));
+            }
+            let int_contour = &mut self.int_shape[count];
+            int_contour.clear();
+            int_contour.extend(contour.iter().map(|p| adapter.float_to_int(p)));
+            count += 1;
+        }
+        count
+    }
+
+    /// Same as [`TriangulatorContext::append`] for the first `count` contours of the int shape buffer.
+    pub(crate) fn append_loaded<P, I: IndexType, F: Fn(IntPoint) -> P>(
+        &mut self,
+        count: usize,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+        map: F,
+    ) -> Result<(), TriangulationError> {
+        let shape = std::mem::take(&mut self.int_shape);
+        let result = self.append(&shape[..count], &[], points, indices, map);
+        self.int_shape = shape;
+        result
+    }
+
+    /// Returns the number of unique points, the triangles stay in the builder.
+    fn sweep(&mut self, paths: &[IntContour], points: &[IntPoint]) -> usize {
+        fill_chain_vertices(paths, points, &mut self.vertices);
+        let triangles_count = paths.iter().fold(0, |s, path| s + path.len().saturating_sub(2)) + 2 * points.len();
+        self.builder.reset(triangles_count);
+
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+
+        self.builder.build_reusing(&self.vertices, &mut self.sweep);
+
+        self.vertices.last().map_or(0, |v| v.index + 1)
+    }
+}
+
+impl Default for TriangulatorContext {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::context::TriangulatorContext;
+    use crate::int::error::TriangulationError;
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn star(n: usize, r0: f64, r1: f64) -> IntPath {
+        (0..2 * n)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::PI / n as f64;
+                let r = if i % 2 == 0 { r0 } else { r1 };
+                IntPoint::new((r * a.cos()) as i32, (r * a.sin()) as i32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let triangulator = Triangulator::default();
+        let mut context = TriangulatorContext::default();
+        let mut points = Vec::new();
+        let mut indices: Vec<u16> = Vec::new();
+
+        let square = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let hole = path(&[[3, 3], [3, 7], [7, 7], [7, 3]]);
+        let shape = vec![square.clone(), hole];
+
+        triangulator
+            .unchecked_triangulate_contour_into(&square, &mut context, &mut points, &mut indices)
+            .unwrap();
+        triangulator
+            .unchecked_triangulate_shape_into(&shape, &mut context, &mut points, &mut indices)
+            .unwrap();
+
+        let first = triangulator.unchecked_triangulate_contour(&square).into_triangulation::<u16>();
+        let second = triangulator.unchecked_triangulate_shape(&shape).into_triangulation::<u16>();
+
+        assert_eq!(points.len(), first.points.len() + second.points.len());
+        assert_eq!(&points[..4], first.points.as_slice());
+        assert_eq!(&points[4..], second.points.as_slice());
+        assert_eq!(&indices[..6], first.indices.as_slice());
+        let shifted: Vec<u16> = second.indices.iter().map(|&i| i + 4).collect();
+        assert_eq!(&indices[6..], shifted.as_slice());
+    }
+
+    #[test]
+    fn test_1() {
+        // index overflow leaves buffers unchanged
+        let triangulator = Triangulator::default();
+        let mut context = TriangulatorContext::default();
+        let mut points = Vec::new();
+        let mut indices: Vec<u8> = Vec::new();
+
+        let small = star(50, 100.0, 50.0);
+        let shapes = vec![vec![small.clone()], vec![star(100, 1000.0, 500.0)]];
+
+        triangulator
+            .unchecked_triangulate_contour_into(&small, &mut context, &mut points, &mut indices)
+            .unwrap();
+        let result = triangulator.triangulate_shapes_into(&shapes, &mut context, &mut points, &mut indices);
+
+        assert_eq!(result, Err(TriangulationError::IndexOverflow { index_type: "u8", points_count: 300 }));
+        assert_eq!(points.len(), 100);
+        assert_eq!(indices.len(), 3 * 98);
+    }
+
+    #[test]
+    fn test_2() {
+        // steady state does not reallocate
+        let triangulator = Triangulator::default();
+        let mut context = TriangulatorContext::default();
+        let mut points = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        let shapes: Vec<IntPath> = (3..40).map(|n| star(n, 100.0, 40.0)).collect();
+        for shape in shapes.iter() {
+            triangulator
+                .unchecked_triangulate_contour_into(shape, &mut context, &mut points, &mut indices)
+                .unwrap();
+        }
+
+        let vertices = context.vertices.as_ptr();
+        let triangles = context.builder.triangles.as_ptr();
+        let points_ptr = points.as_ptr();
+        let indices_ptr = indices.as_ptr();
+
+        for _ in 0..10 {
+            points.clear();
+            indices.clear();
+            for shape in shapes.iter() {
+                triangulator
+                    .unchecked_triangulate_contour_into(shape, &mut context, &mut points, &mut indices)
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(vertices, context.vertices.as_ptr());
+        assert_eq!(triangles, context.builder.triangles.as_ptr());
+        assert_eq!(points_ptr, points.as_ptr());
+        assert_eq!(indices_ptr, indices.as_ptr());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let triangulator = Triangulator::default();
+        let mut context = TriangulatorContext::default();
+        let mut rng = rand::rng();
+        for _ in 0..2000 {
+            let n = rng.random_range(3..64);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-20..=20), rng.random_range(-20..=20)))
+                .collect();
+
+            let mut points = Vec::new();
+            let mut indices: Vec<u32> = Vec::new();
+            triangulator
+                .triangulate_contour_into(&contour, &mut context, &mut points, &mut indices)
+                .unwrap();
+
+            let expected = triangulator.triangulate_contour(&contour).into_triangulation::<u32>();
+            assert_eq!(points, expected.points);
+            assert_eq!(indices, expected.indices);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+
+/// An error returned by the fallible `try_*` triangulation API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriangulationError {
+    /// The index type `index_type` cannot address `points_count` points.
+    IndexOverflow {
+        index_type: &'static str,
+        points_count: usize,
+    },
+    /// Nothing is left to triangulate (no shapes, or every shape was removed by validation).
+    EmptyInput,
+    /// `count` Steiner points lie outside of every shape or on a shape boundary.
+    UnassignableSteinerPoints { count: usize },
+    /// Input passed to a `try_unchecked_*` method violates its requirements.
+    InvalidInput(InvalidInput),
+}
+
+/// The reason why unchecked input was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidInput {
+    /// The contour `contour` of the shape `shape` has less than 3 points, a zero area or a zero edge.
+    DegenerateContour { shape: usize, contour: usize },
+    /// The outer contour is not counter-clockwise or a hole is not clockwise.
+    WrongOrientation { shape: usize, contour: usize },
+    /// Contours of the shape `shape` cross or touch each other anywhere but at shared points.
+    SelfIntersection { shape: usize },
+    /// The hole `contour` of the shape `shape` is not inside its outer contour or lies in another hole.
+    MisplacedHole { shape: usize, contour: usize },
+    /// The number of Steiner point groups differs from the number of shapes.
+    GroupsMismatch { shapes: usize, groups: usize },
+    /// The mesh has a point which is not an input point, so it has no input index.
+    UnknownPoint { x: i32, y: i32 },
+}
+
+impl fmt::Display for TriangulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriangulationError::IndexOverflow { index_type, points_count } => {
+                write!(f, "Index type `{}` cannot hold {} points", index_type, points_count)
+            }
+            TriangulationError::EmptyInput => write!(f, "Nothing to triangulate"),
+            TriangulationError::UnassignableSteinerPoints { count } => {
+                write!(f, "{} Steiner points are not strictly inside any shape", count)
+            }
+            TriangulationError::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+        }
+    }
+}
+
+impl fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidInput::DegenerateContour { shape, contour } => {
+                write!(f, "contour {} of shape {} is degenerate", contour, shape)
+            }
+            InvalidInput::WrongOrientation { shape, contour } => {
+                write!(f, "contour {} of shape {} has a wrong orientation", contour, shape)
+            }
+            InvalidInput::SelfIntersection { shape } => {
+                write!(f, "shape {} self-intersects", shape)
+            }
+            InvalidInput::MisplacedHole { shape, contour } => {
+                write!(f, "hole {} of shape {} is not inside the shape", contour, shape)
+            }
+            InvalidInput::GroupsMismatch { shapes, groups } => {
+                write!(f, "{} Steiner point groups for {} shapes", groups, shapes)
+            }
+            InvalidInput::UnknownPoint { x, y } => {
+                write!(f, "mesh point ({}, {}) is not an input point", x, y)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TriangulationError {}
+
+impl From<InvalidInput> for TriangulationError {
+    #[inline]
+    fn from(reason: InvalidInput) -> Self {
+        TriangulationError::InvalidInput(reason)
+    }
+}
@@ -1,7 +1,15 @@
 mod binder;
+mod check;
+pub mod context;
+pub(crate) mod constraint;
 pub mod custom;
+pub mod error;
+pub mod partition;
+pub mod provenance;
 pub mod triangulatable;
 pub mod triangulation;
 pub mod triangulator;
 pub mod unchecked;
-mod monotone;
\ No newline at end of file
+mod monotone;
+mod slit;
+mod trapezoid;
\ No newline at end of file
@@ -122,6 +122,16 @@ impl ToChainVertices for IntContour {
     }
 }
 
+/// Builds chain vertices of the given paths and Steiner points into the reused `vertices` buffer.
+pub(crate) fn fill_chain_vertices(paths: &[IntContour], points: &[IntPoint], vertices: &mut Vec<ChainVertex>) {
+    let mut builder = ChainVerticesDirectBuilder::with_buffer(std::mem::take(vertices));
+    for path in paths.iter() {
+        builder.add_path(path);
+    }
+    builder.add_steiner_points(points);
+    *vertices = builder.into_chain_vertices();
+}
+
 #[derive(Debug, PartialEq)]
 enum DirectionType {
     Next,
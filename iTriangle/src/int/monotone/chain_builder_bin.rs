@@ -103,7 +103,7 @@ impl ChainVerticesBinBuilder {
             let start = bin.offset;
             let end = bin.data;
             if start < end {
-                vertices[start..end].sort_by(|a, b| a.this.cmp(&b.this));
+                vertices[start..end].sort_by_key(|v| v.this);
             }
         }
 
@@ -14,6 +14,13 @@ impl ChainVerticesDirectBuilder {
         }
     }
 
+    /// Reuses the allocation of `vertices`, its content is dropped.
+    #[inline]
+    pub(super) fn with_buffer(mut vertices: Vec<ChainVertex>) -> Self {
+        vertices.clear();
+        Self { vertices }
+    }
+
     #[inline]
     pub(super) fn add_path(&mut self, path: &[IntPoint]) {
         let n = path.len();
@@ -40,7 +47,10 @@ impl ChainVerticesDirectBuilder {
 
     pub(super) fn into_chain_vertices(self) -> Vec<ChainVertex> {
         let mut vertices = self.vertices;
-        vertices.sort_unstable_by(|a, b| a.this.cmp(&b.this));
+        if vertices.is_empty() {
+            return vertices;
+        }
+        vertices.sort_unstable_by_key(|v| v.this);
 
         let mut index = vertices[0].index;
         let mut p = vertices[0].this;
@@ -16,8 +16,8 @@ pub(super) enum VertexType {
 
 #[derive(Debug, Clone)]
 pub(crate) struct ChainVertex {
-    pub(super) index: usize,
-    pub(super) this: IntPoint,
+    pub(crate) index: usize,
+    pub(crate) this: IntPoint,
     pub(super) next: IntPoint,
     pub(super) prev: IntPoint,
 }
@@ -47,6 +47,14 @@ impl PhantomEdgePool {
         self.unused.extend((n..n + length).rev());
     }
 
+    #[inline]
+    fn clear(&mut self) {
+        let n = self.buffer.len();
+        self.buffer.fill(Self::EMPTY);
+        self.unused.clear();
+        self.unused.extend((0..n).rev());
+    }
+
     #[inline]
     fn get(&self, index: usize) -> Option<PhantomHandler> {
         let item = self.buffer[index];
@@ -81,6 +89,16 @@ impl PhantomEdgePool {
 pub(crate) struct TriangleMeshBuilder {
     pub(crate) triangles: Vec<IntTriangle>,
     phantom_store: PhantomEdgePool,
+    cuts: Option<MonotoneCuts>,
+}
+
+/// The vertices where the sweep cuts the shape into x-monotone sections.
+#[derive(Default)]
+struct MonotoneCuts {
+    // a split vertex and the point on its left it is linked to
+    splits: Vec<[usize; 2]>,
+    // a merge vertex and the number of triangles built when the sweep passed it
+    merges: Vec<[usize; 2]>,
 }
 
 impl TriangleMeshBuilder {
@@ -90,35 +108,96 @@ impl TriangleMeshBuilder {
         Self {
             triangles: Vec::with_capacity(triangles_count),
             phantom_store: PhantomEdgePool::new(phantom_capacity),
+            cuts: None,
         }
     }
 
+    /// Clears the builder so it can be reused for another build without new allocations.
+    #[inline]
+    pub(crate) fn reset(&mut self, triangles_count: usize) {
+        self.triangles.clear();
+        self.triangles.reserve(triangles_count);
+        self.phantom_store.clear();
+    }
+
     #[inline]
     pub(crate) fn build(&mut self, vertices: &[ChainVertex]) {
         let n = vertices.len();
         let capacity = if n < 128 { 4 } else { n.ilog2() as usize };
         if capacity <= 12 {
-            self.build_with_store(SetList::new(capacity), vertices)
+            self.build_with_store(&mut SetList::new(capacity), vertices)
         } else {
-            self.build_with_store(SetTree::new(capacity), vertices)
+            self.build_with_store(&mut SetTree::new(capacity), vertices)
+        }
+    }
+
+    /// Same as [`TriangleMeshBuilder::build`], but also returns the diagonals between the x-monotone sections
+    /// of the sweep: the link of every split vertex to the left and of every merge vertex to the right.
+    pub(crate) fn build_with_diagonals(&mut self, vertices: &[ChainVertex]) -> Vec<[usize; 2]> {
+        self.cuts = Some(MonotoneCuts::default());
+        self.build(vertices);
+        let cuts = self.cuts.take().unwrap_or_default();
+
+        let mut diagonals = cuts.splits;
+        for [v, start] in cuts.merges {
+            // a merge vertex is linked by the first triangle that reaches it later
+            let next = self.triangles[start..]
+                .iter()
+                .find(|t| t.vertices[1].index == v || t.vertices[2].index == v);
+            if let Some(t) = next {
+                diagonals.push([v, t.vertices[0].index]);
+            }
         }
+
+        diagonals
     }
 
+    /// Same as [`TriangleMeshBuilder::build`], but takes the sweep line stores from `sweep`.
     #[inline]
-    pub(super) fn build_with_store<S: SetCollection<VSegment, Section>>(&mut self, mut store: S, vertices: &[ChainVertex]) {
+    pub(crate) fn build_reusing(&mut self, vertices: &[ChainVertex], sweep: &mut SweepStore) {
+        let n = vertices.len();
+        let capacity = if n < 128 { 4 } else { n.ilog2() as usize };
+        if capacity <= 12 {
+            sweep.list.clear();
+            self.build_with_store(&mut sweep.list, vertices)
+        } else {
+            let tree = sweep.tree.get_or_insert_with(|| SetTree::new(capacity));
+            tree.clear();
+            self.build_with_store(tree, vertices)
+        }
+    }
+
+    #[inline]
+    pub(super) fn build_with_store<S: SetCollection<VSegment, Section>>(&mut self, store: &mut S, vertices: &[ChainVertex]) {
         for v in vertices.iter() {
             match v.get_type() {
-                VertexType::Start => self.start(v, &mut store),
-                VertexType::End => self.end(v, &mut store),
-                VertexType::Merge => self.merge(v, &mut store),
-                VertexType::Split => self.split(v, &mut store),
-                VertexType::Join => self.join(v, &mut store),
-                VertexType::Steiner => self.steiner(v, &mut store),
+                VertexType::Start => self.start(v, store),
+                VertexType::End => self.end(v, store),
+                VertexType::Merge => self.merge(v, store),
+                VertexType::Split => self.split(v, store),
+                VertexType::Join => self.join(v, store),
+                VertexType::Steiner => self.steiner(v, store),
             }
         }
     }
 }
 
+/// Sweep line stores kept between [`TriangleMeshBuilder::build_reusing`] calls.
+pub(crate) struct SweepStore {
+    list: SetList<Section>,
+    tree: Option<SetTree<VSegment, Section>>,
+}
+
+impl Default for SweepStore {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            list: SetList::new(4),
+            tree: None,
+        }
+    }
+}
+
 impl TriangleMeshBuilder {
     #[inline]
     fn next_triangle_index(&self) -> usize {
@@ -130,6 +209,20 @@ impl TriangleMeshBuilder {
         self.phantom_store.alloc_phantom_index()
     }
 
+    #[inline]
+    fn add_split_cut(&mut self, vertex: usize, left: usize) {
+        if let Some(cuts) = &mut self.cuts {
+            cuts.splits.push([vertex, left]);
+        }
+    }
+
+    #[inline]
+    fn add_merge_cut(&mut self, vertex: usize) {
+        if let Some(cuts) = &mut self.cuts {
+            cuts.merges.push([vertex, self.triangles.len()]);
+        }
+    }
+
     #[inline]
     fn insert_triangle_with_neighbor_link(
         &mut self,
@@ -235,6 +328,7 @@ impl TriangleMeshBuilder {
         prev.sort = sort;
 
         tree.delete_by_index(next_index);
+        self.add_merge_cut(v.index_point().index);
     }
 
     fn steiner<S: SetCollection<VSegment, Section>>(&mut self, v: &ChainVertex, tree: &mut S) {
@@ -304,6 +398,7 @@ impl Section {
                     kind: EdgeType::Phantom(phantom_index),
                 };
 
+                net_builder.add_split_cut(vp.index, point.index);
                 self.content = Content::Edges(vec![top_edge]);
 
                 // bottom
@@ -356,6 +451,7 @@ impl Section {
                     kind: EdgeType::Phantom(phantom_index),
                 };
                 edges.push(top_edge);
+                net_builder.add_split_cut(vp.index, eb.index);
 
                 let bottom_edges = vec![TriangleEdge {
                     a: vp,
@@ -391,6 +487,7 @@ impl Section {
                 };
 
                 edges.push(top_edge);
+                net_builder.add_split_cut(vp.index, ea.index);
 
                 bottom_edges.insert(0, bottom_edge);
 
@@ -432,6 +529,9 @@ impl Section {
             content: Content::Edges(top_edges),
         };
 
+        // the fan point most to the right is the split diagonal
+        let e0 = &edges[0];
+        let mut left = if e0.a.point < e0.b.point { e0.b } else { e0.a };
         let mut next_index = index + 2;
         i = 1;
         while i < edges.len() {
@@ -444,10 +544,14 @@ impl Section {
             triangle.neighbors[2] = index;
             index = net_builder.insert_triangle_with_neighbor_link(ei, 0, triangle);
             next_index = index + 2;
+            if left.point < ei.b.point {
+                left = ei.b;
+            }
 
             i += 1;
         }
         net_builder.triangles[index].neighbors[1] = usize::MAX;
+        net_builder.add_split_cut(vp.index, left.index);
 
         let bottom_edge = TriangleEdge {
             a: vp,
@@ -1091,7 +1195,7 @@ mod tests {
 
         let shape_area = s.area_two();
 
-        let net = shape_to_builder(&s);
+        let net = shape_to_builder(s);
         assert_eq!(net.triangles.len(), 3);
         net.validate();
 
@@ -1331,7 +1435,7 @@ mod tests {
         let shape = vec![path(&[[1, 0], [-4, -2], [3, 0], [5, 1], [4, 1], [-4, -1]])];
         let points = vec![IntPoint::new(0, 3), IntPoint::new(4, 3)];
         let shape_area = shape.area_two();
-        let group = vec![shape.clone()].group_by_shapes(&points);
+        let group = [shape.clone()].group_by_shapes(&points);
 
         let net = shape_to_builder_with_points(&shape, &group[0]);
         assert_eq!(net.triangles.len(), 4);
@@ -1345,7 +1449,7 @@ mod tests {
         let shape = vec![path(&[[-1, 2], [-5, -2], [2, -2], [3, 4]])];
         let points = vec![IntPoint::new(1, 5)];
         let shape_area = shape.area_two();
-        let group = vec![shape.clone()].group_by_shapes(&points);
+        let group = [shape.clone()].group_by_shapes(&points);
 
         let net = shape_to_builder_with_points(&shape, &group[0]);
         assert_eq!(net.triangles.len(), 2);
@@ -1569,8 +1673,8 @@ mod tests {
             {
                 let shape_area = first.area_two();
 
-                let group = vec![first.clone()].group_by_shapes(&points);
-                let net = shape_to_builder_with_points(&first, &group[0]);
+                let group = [first.clone()].group_by_shapes(&points);
+                let net = shape_to_builder_with_points(first, &group[0]);
                 net.validate();
                 assert_eq!(net.area(), shape_area);
             };
@@ -1600,6 +1704,6 @@ mod tests {
             points.insert(IntPoint { x, y });
         }
 
-        points.iter().map(|p| p).copied().collect()
+        points.iter().copied().collect()
     }
 }
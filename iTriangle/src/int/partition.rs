@@ -0,0 +1,209 @@
+use crate::index::Index;
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::shape::IntContour;
+use i_overlay::string::line::IntLine;
+use std::collections::HashSet;
+
+/// The x-monotone pieces of a shape, see [`Triangulator::monotone_partition`](crate::int::triangulator::Triangulator::monotone_partition).
+#[derive(Debug, Clone, Default)]
+pub struct IntMonotonePartition {
+    /// Pieces wound like the outer contours, a vertical line crosses every piece at most once.
+    pub contours: Vec<IntContour>,
+    /// The diagonals the sweep added to cut the shape into pieces, one side of each is a split or a merge vertex.
+    pub diagonals: Vec<IntLine>,
+}
+
+impl RawIntTriangulation {
+    /// Groups the sweep triangles of a shape into its x-monotone sections.
+    ///
+    /// `diagonals` are the point pairs the sweep links at split and merge vertices,
+    /// see [`TriangleMeshBuilder::build_with_diagonals`](crate::int::monotone::mesh_builder::TriangleMeshBuilder::build_with_diagonals).
+    /// The pieces are the triangles between them.
+    pub(crate) fn monotone_partition(&self, diagonals: &[[usize; 2]]) -> IntMonotonePartition {
+        let mut cuts = HashSet::with_capacity(diagonals.len());
+        let mut lines = Vec::with_capacity(diagonals.len());
+        for &[a, b] in diagonals.iter() {
+            // a split vertex linked to a merge vertex cuts both
+            if cuts.insert((a.min(b), a.max(b))) {
+                lines.push([self.points[a], self.points[b]]);
+            }
+        }
+
+        IntMonotonePartition {
+            contours: self.pieces(&cuts),
+            diagonals: lines,
+        }
+    }
+
+    /// The boundary loops of the triangle groups separated by `cuts`.
+    fn pieces(&self, cuts: &HashSet<(usize, usize)>) -> Vec<IntContour> {
+        let is_side = |t: usize, k: usize| {
+            let triangle = &self.triangles[t];
+            if !triangle.neighbors[k].is_not_nil() {
+                return true;
+            }
+            let a = triangle.vertices[(k + 1) % 3].index;
+            let b = triangle.vertices[(k + 2) % 3].index;
+            cuts.contains(&(a.min(b), a.max(b)))
+        };
+
+        let mut visited = vec![[false; 3]; self.triangles.len()];
+        let mut contours = Vec::new();
+        for t0 in 0..self.triangles.len() {
+            for k0 in 0..3 {
+                if visited[t0][k0] || !is_side(t0, k0) {
+                    continue;
+                }
+
+                let mut contour: Vec<IntPoint> = Vec::new();
+                let (mut t, mut k) = (t0, k0);
+                while !visited[t][k] {
+                    visited[t][k] = true;
+                    let b = self.triangles[t].vertices[(k + 2) % 3].index;
+                    contour.push(self.points[b]);
+
+                    // turn around `b` inside the piece until the next side
+                    k = (k + 1) % 3;
+                    while !is_side(t, k) {
+                        let n = self.triangles[t].neighbors[k];
+                        let j = self.triangles[n].opposite(t);
+                        t = n;
+                        k = (j + 1) % 3;
+                    }
+                }
+                contours.push(contour);
+            }
+        }
+
+        contours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::partition::IntMonotonePartition;
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use i_overlay::i_shape::int::shape::{IntContour, IntShape};
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    // a piece is x-monotone if its points go right and then left once, in x then y order
+    fn is_monotone(contour: &IntContour) -> bool {
+        let n = contour.len();
+        let min = (0..n).min_by_key(|&i| contour[i]).unwrap();
+        let mut i = min;
+        while contour[(i + 1) % n] > contour[i] {
+            i = (i + 1) % n;
+        }
+        while contour[(i + 1) % n] < contour[i] {
+            i = (i + 1) % n;
+        }
+        i == min
+    }
+
+    fn check_partition(partition: &IntMonotonePartition, shape: &IntShape) {
+        let area: i64 = partition.contours.iter().map(|c| c.area_two()).sum();
+        assert_eq!(area, shape.area_two());
+        for contour in partition.contours.iter() {
+            assert!(is_monotone(contour));
+        }
+        assert_eq!(partition.contours.len(), partition.diagonals.len() + 2 - shape.len());
+    }
+
+    #[test]
+    fn test_0() {
+        // a convex contour is one piece
+        let shape = vec![path(&[[0, 0], [10, 0], [10, 10], [0, 10]])];
+        let partition = Triangulator::default().monotone_partition(&shape);
+        assert_eq!(partition.contours.len(), 1);
+        assert!(partition.diagonals.is_empty());
+        check_partition(&partition, &shape);
+    }
+
+    #[test]
+    fn test_1() {
+        // a split vertex at (12, 5) and a merge vertex at (8, 5)
+        let shape = vec![path(&[
+            [0, 0],
+            [20, 0],
+            [20, 4],
+            [12, 5],
+            [20, 6],
+            [20, 10],
+            [0, 10],
+            [0, 6],
+            [8, 5],
+            [0, 4],
+        ])];
+        let triangulator = Triangulator::default();
+        let partition = triangulator.monotone_partition(&shape);
+        // the split vertex is linked to the merge vertex, one diagonal serves both
+        assert_eq!(partition.diagonals, vec![[IntPoint::new(12, 5), IntPoint::new(8, 5)]]);
+        check_partition(&partition, &shape);
+
+        let partition = triangulator.unchecked_monotone_partition(&shape);
+        check_partition(&partition, &shape);
+    }
+
+    #[test]
+    fn test_2() {
+        // a hole has a split and a merge vertex
+        let shape = vec![
+            path(&[[0, 0], [30, 0], [30, 30], [0, 30]]),
+            path(&[[10, 10], [15, 20], [20, 10]]),
+        ];
+        let partition = Triangulator::default().monotone_partition(&shape);
+        assert_eq!(
+            partition.diagonals,
+            vec![
+                [IntPoint::new(10, 10), IntPoint::new(0, 30)],
+                [IntPoint::new(20, 10), IntPoint::new(30, 0)],
+            ]
+        );
+        check_partition(&partition, &shape);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        let triangulator = Triangulator::default();
+        for _ in 0..1000 {
+            let n = rng.random_range(3..=16);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-100..=100), rng.random_range(-100..=100)))
+                .collect();
+            let raw = triangulator.triangulate_contour(&contour);
+            let partition = triangulator.monotone_partition(&vec![contour]);
+            let area: i64 = partition.contours.iter().map(|c| c.area_two()).sum();
+            let mesh_area: i64 = raw.triangles.iter().map(|t| {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                Triangle::area_two_point(a, b, c)
+            }).sum();
+            assert_eq!(area, mesh_area);
+            for contour in partition.contours.iter() {
+                assert!(is_monotone(contour));
+            }
+
+            // the diagonals are edges of the sweep triangles
+            let edges: HashSet<_> = raw
+                .triangles
+                .iter()
+                .flat_map(|t| (0..3).map(move |k| (t.vertices[k].point, t.vertices[(k + 1) % 3].point)))
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect();
+            for &[a, b] in partition.diagonals.iter() {
+                assert!(edges.contains(&(a.min(b), a.max(b))));
+            }
+        }
+    }
+}
+
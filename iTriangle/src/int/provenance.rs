@@ -0,0 +1,153 @@
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::shape::IntShape;
+use std::collections::HashMap;
+
+/// The origin of an output point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexSource {
+    /// The point `index` of the contour `contour` of the input shape `shape`.
+    Original { shape: usize, contour: usize, index: usize },
+    /// The point `index` of the supplied Steiner points.
+    Steiner { index: usize },
+    /// A point created by validation (e.g. a crossing of two contours), a constraint or a slit.
+    Intersection,
+    /// A point inserted by refinement, e.g. [`IntDelaunay::refine_with_circumcenters`].
+    ///
+    /// [`IntDelaunay::refine_with_circumcenters`]: crate::advanced::delaunay::IntDelaunay::refine_with_circumcenters
+    Refinement,
+}
+
+impl RawIntTriangulation {
+    /// Attaches a [`VertexSource`] to every point of the mesh, see [`RawIntTriangulation::vertex_sources`].
+    ///
+    /// Pass the same shapes and Steiner points that were triangulated (a single contour is `&[vec![contour]]`).
+    /// Points are matched by position: validation may drop or reorder input points but never moves them.
+    /// If several input points share a position, the first one in input order wins.
+    ///
+    /// The table follows the mesh through Delaunay flips and refinement.
+    pub fn with_vertex_sources(mut self, shapes: &[IntShape], steiner_points: &[IntPoint]) -> Self {
+        self.vertex_sources = collect_vertex_sources(&self.points, shapes, steiner_points);
+        self
+    }
+
+    /// Returns the origin of every point, `None` unless [`RawIntTriangulation::with_vertex_sources`] was called.
+    #[inline]
+    pub fn vertex_sources(&self) -> Option<&[VertexSource]> {
+        if self.vertex_sources.is_empty() {
+            None
+        } else {
+            Some(&self.vertex_sources)
+        }
+    }
+}
+
+fn collect_vertex_sources(
+    points: &[IntPoint],
+    shapes: &[IntShape],
+    steiner_points: &[IntPoint],
+) -> Vec<VertexSource> {
+    let count = shapes.iter().flatten().map(Vec::len).sum::<usize>() + steiner_points.len();
+    let mut map = HashMap::with_capacity(count);
+    for (shape, contours) in shapes.iter().enumerate() {
+        for (contour, path) in contours.iter().enumerate() {
+            for (index, &p) in path.iter().enumerate() {
+                map.entry(p).or_insert(VertexSource::Original { shape, contour, index });
+            }
+        }
+    }
+    for (index, &p) in steiner_points.iter().enumerate() {
+        map.entry(p).or_insert(VertexSource::Steiner { index });
+    }
+
+    points
+        .iter()
+        .map(|p| map.get(p).copied().unwrap_or(VertexSource::Intersection))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::provenance::VertexSource;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let shape = vec![
+            path(&[[0, 0], [100, 0], [100, 100], [0, 100]]),
+            path(&[[40, 40], [40, 60], [60, 60], [60, 40]]),
+        ];
+        let steiner = vec![IntPoint::new(20, 20), IntPoint::new(80, 80)];
+
+        let raw = shape
+            .triangulate_with_steiner_points(&steiner)
+            .with_vertex_sources(std::slice::from_ref(&shape), &steiner);
+        let sources = raw.vertex_sources().unwrap();
+        assert_eq!(sources.len(), raw.points.len());
+
+        for (p, source) in raw.points.iter().zip(sources.iter()) {
+            match *source {
+                VertexSource::Original { shape: s, contour, index } => {
+                    assert_eq!(s, 0);
+                    assert_eq!(shape[contour][index], *p);
+                }
+                VertexSource::Steiner { index } => assert_eq!(steiner[index], *p),
+                _ => panic!("unexpected source"),
+            }
+        }
+
+        let refined = raw.into_delaunay().refine_with_circumcenters(32);
+        let sources = refined.vertex_sources().unwrap();
+        assert_eq!(sources.len(), refined.points.len());
+        assert!(sources[10..].iter().all(|&s| s == VertexSource::Refinement));
+        assert!(sources[..10].iter().all(|&s| s != VertexSource::Refinement));
+    }
+
+    #[test]
+    fn test_1() {
+        // self-intersecting contour, the crossing point is created by validation
+        let contour = path(&[[0, 0], [10, 0], [0, 10], [10, 10]]);
+
+        let raw = Triangulator::default()
+            .triangulate_contour(&contour)
+            .with_vertex_sources(&[vec![contour.clone()]], &[]);
+        let sources = raw.vertex_sources().unwrap();
+
+        let crossing = IntPoint::new(5, 5);
+        assert!(raw.points.contains(&crossing));
+        for (p, &source) in raw.points.iter().zip(sources.iter()) {
+            assert_eq!(*p == crossing, source == VertexSource::Intersection);
+        }
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let n = rng.random_range(3..16);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-20..=20), rng.random_range(-20..=20)))
+                .collect();
+
+            let raw = contour.triangulate().with_vertex_sources(&[vec![contour.clone()]], &[]);
+            if raw.is_empty() {
+                continue;
+            }
+            for (p, source) in raw.points.iter().zip(raw.vertex_sources().unwrap().iter()) {
+                match *source {
+                    VertexSource::Original { index, .. } => assert_eq!(contour[index], *p),
+                    VertexSource::Intersection => assert!(!contour.contains(p)),
+                    _ => panic!("unexpected source"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use crate::index::{Index, NIL_INDEX};
+use crate::int::triangulation::RawIntTriangulation;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::path::IntPath;
+use i_overlay::i_shape::int::shape::IntContour;
+use i_overlay::string::line::IntLine;
+use std::collections::HashMap;
+
+/// Converts open polylines into a flat list of their segments.
+pub(crate) fn slit_segments(slits: &[IntPath]) -> Vec<IntLine> {
+    let count = slits.iter().map(|s| s.len().saturating_sub(1)).sum();
+    let mut segments = Vec::with_capacity(count);
+    for slit in slits.iter() {
+        for w in slit.windows(2) {
+            segments.push([w[0], w[1]]);
+        }
+    }
+    segments
+}
+
+/// Inserts every slit vertex lying strictly inside a contour edge into that contour,
+/// so a slit may start or end on the shape boundary.
+pub(crate) fn insert_slit_ends(contours: &mut [IntContour], slits: &[IntPath]) {
+    let mut points: Vec<IntPoint> = slits.iter().flatten().copied().collect();
+    if points.is_empty() {
+        return;
+    }
+    points.sort_unstable();
+    points.dedup();
+
+    let mut buffer = Vec::new();
+    for contour in contours.iter_mut() {
+        let mut modified = false;
+        let mut result = Vec::with_capacity(contour.len());
+        let mut a = *contour.last().unwrap();
+        for &b in contour.iter() {
+            buffer.clear();
+            buffer.extend(points.iter().copied().filter(|&p| is_inside_edge(a, b, p)));
+            if !buffer.is_empty() {
+                buffer.sort_unstable_by_key(|p| a.sqr_distance(*p));
+                result.extend_from_slice(&buffer);
+                modified = true;
+            }
+            result.push(b);
+            a = b;
+        }
+        if modified {
+            *contour = result;
+        }
+    }
+}
+
+#[inline]
+fn is_inside_edge(a: IntPoint, b: IntPoint, p: IntPoint) -> bool {
+    if p == a || p == b {
+        return false;
+    }
+    let ab = b.subtract(a);
+    let ap = p.subtract(a);
+    if ab.cross_product(ap) != 0 {
+        return false;
+    }
+    let dot = ab.dot_product(ap);
+    0 < dot && dot < ab.sqr_length()
+}
+
+impl RawIntTriangulation {
+    /// Disconnects triangles across every constrained edge and duplicates the vertices
+    /// along them, so each side of a cut references its own points.
+    ///
+    /// Constraints are consumed by the cut: the cut edges become boundary edges.
+    pub(crate) fn cut_along_constraints(&mut self) {
+        if self.constraints.is_empty() {
+            return;
+        }
+
+        let mut touched = Vec::new();
+        for t in self.triangles.iter_mut() {
+            for j in 0..3 {
+                if !t.neighbors[j].is_not_nil() {
+                    continue;
+                }
+                let a = t.vertices[(j + 1) % 3].index;
+                let b = t.vertices[(j + 2) % 3].index;
+                if self.constraints.contains(a, b) {
+                    t.neighbors[j] = NIL_INDEX;
+                    touched.push(a);
+                    touched.push(b);
+                }
+            }
+        }
+        self.constraints = Default::default();
+
+        if touched.is_empty() {
+            return;
+        }
+        touched.sort_unstable();
+        touched.dedup();
+
+        let mut fans: HashMap<usize, Vec<usize>> =
+            touched.iter().map(|&v| (v, Vec::new())).collect();
+        for (i, t) in self.triangles.iter().enumerate() {
+            for v in t.vertices.iter() {
+                if let Some(fan) = fans.get_mut(&v.index) {
+                    fan.push(i);
+                }
+            }
+        }
+
+        let mut group = Vec::new();
+        let mut visited = Vec::new();
+        for v in touched {
+            let fan = &fans[&v];
+            visited.clear();
+            visited.resize(fan.len(), false);
+
+            let mut is_first = true;
+            for start in 0..fan.len() {
+                if visited[start] {
+                    continue;
+                }
+                visited[start] = true;
+                group.clear();
+                group.push(fan[start]);
+
+                let mut k = 0;
+                while k < group.len() {
+                    let t = &self.triangles[group[k]];
+                    let i = t.vertex_position(v);
+                    for n in [t.neighbors[(i + 1) % 3], t.neighbors[(i + 2) % 3]] {
+                        if !n.is_not_nil() {
+                            continue;
+                        }
+                        if let Some(pos) = fan.iter().position(|&f| f == n) {
+                            if !visited[pos] {
+                                visited[pos] = true;
+                                group.push(n);
+                            }
+                        }
+                    }
+                    k += 1;
+                }
+
+                if is_first {
+                    is_first = false;
+                    continue;
+                }
+
+                let new_index = self.points.len();
+                self.points.push(self.points[v]);
+                for &ti in group.iter() {
+                    let t = &mut self.triangles[ti];
+                    let i = t.vertex_position(v);
+                    t.vertices[i].index = new_index;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geom::triangle::IntTriangle;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::int::triangulation::RawIntTriangulation;
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn validate(raw: &RawIntTriangulation) {
+        for (i, t) in raw.triangles.iter().enumerate() {
+            let a = t.vertices[0].point;
+            let b = t.vertices[1].point;
+            let c = t.vertices[2].point;
+            assert!(Triangle::area_two_point(a, b, c) < 0);
+            for v in t.vertices.iter() {
+                assert_eq!(raw.points[v.index], v.point);
+            }
+            for (j, &n) in t.neighbors.iter().enumerate() {
+                if n < raw.triangles.len() {
+                    let other = &raw.triangles[n];
+                    let k = other.neighbors.iter().position(|&m| m == i).unwrap();
+                    // shared edge must use the same point indices
+                    let e0 = edge(t, j);
+                    let e1 = edge(other, k);
+                    assert_eq!(e0, e1);
+                }
+            }
+        }
+    }
+
+    fn edge(t: &IntTriangle, j: usize) -> (usize, usize) {
+        let a = t.vertices[(j + 1) % 3].index;
+        let b = t.vertices[(j + 2) % 3].index;
+        if a < b { (a, b) } else { (b, a) }
+    }
+
+    fn area(raw: &RawIntTriangulation) -> i64 {
+        raw.triangles.iter().fold(0, |s, t| {
+            s + Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point)
+        })
+    }
+
+    fn components(raw: &RawIntTriangulation) -> usize {
+        let mut visited = vec![false; raw.triangles.len()];
+        let mut count = 0;
+        for start in 0..raw.triangles.len() {
+            if visited[start] {
+                continue;
+            }
+            count += 1;
+            visited[start] = true;
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                for &n in raw.triangles[i].neighbors.iter() {
+                    if n < raw.triangles.len() && !visited[n] {
+                        visited[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_0() {
+        // inner crack, its tips stay shared
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let slit = path(&[[-5, 0], [0, 1], [5, 0]]);
+
+        let raw = contour.triangulate_with_slits(&[slit]);
+        validate(&raw);
+        assert_eq!(area(&raw), contour.area_two());
+        assert_eq!(raw.points.len(), 8);
+        assert_eq!(components(&raw), 1);
+    }
+
+    #[test]
+    fn test_1() {
+        // cut from boundary to boundary splits the shape in two
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let slit = path(&[[-10, 0], [0, 2], [10, 0]]);
+
+        let raw = contour.triangulate_with_slits(&[slit]);
+        validate(&raw);
+        assert_eq!(area(&raw), contour.area_two());
+        assert_eq!(raw.points.len(), 10);
+        assert_eq!(components(&raw), 2);
+    }
+
+    #[test]
+    fn test_2() {
+        // crack from a shape corner
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let slit = path(&[[-10, -10], [0, 0]]);
+
+        let raw = contour.triangulate_with_slits(&[slit]);
+        validate(&raw);
+        assert_eq!(area(&raw), contour.area_two());
+        assert_eq!(raw.points.len(), 6);
+        assert_eq!(components(&raw), 1);
+    }
+
+    #[test]
+    fn test_3() {
+        // the segment leaving the shape is ignored, the rest is an inner crack
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let slit = path(&[[-5, -5], [5, -5], [15, 5]]);
+
+        let raw = Triangulator::default().triangulate_contour_with_slits(&contour, &[slit]);
+        validate(&raw);
+        assert_eq!(area(&raw), contour.area_two());
+        assert_eq!(raw.points.len(), 6);
+    }
+
+    #[test]
+    fn test_4() {
+        // crossing slits
+        let contour = path(&[[-10, -10], [10, -10], [10, 10], [-10, 10]]);
+        let slits = [path(&[[-5, -5], [5, 5]]), path(&[[-5, 5], [5, -5]])];
+
+        let raw = contour.triangulate_with_slits(&slits);
+        validate(&raw);
+        assert_eq!(area(&raw), contour.area_two());
+        // the crossing point is split into 4 copies
+        assert_eq!(raw.points.len(), 12);
+        assert_eq!(components(&raw), 1);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let contour = path(&[[-50, -50], [50, -50], [50, 50], [-50, 50]]);
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let n = rng.random_range(1..4);
+            let slits: Vec<IntPath> = (0..n)
+                .map(|_| {
+                    let m = rng.random_range(2..5);
+                    (0..m)
+                        .map(|_| IntPoint::new(rng.random_range(-50..=50), rng.random_range(-50..=50)))
+                        .collect()
+                })
+                .collect();
+
+            let raw = contour.triangulate_with_slits(&slits);
+            validate(&raw);
+            assert_eq!(area(&raw), contour.area_two());
+        }
+    }
+}
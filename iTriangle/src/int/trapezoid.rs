@@ -0,0 +1,227 @@
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_shape::int::shape::{IntContour, IntShapes};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// A not vertical contour edge going from left to right.
+#[derive(Debug, Clone, Copy)]
+struct SlabEdge {
+    a: IntPoint,
+    b: IntPoint,
+}
+
+impl SlabEdge {
+    /// Compares the edges inside the slab `x0..x1` both of them cross, by their middle.
+    #[inline]
+    fn order(&self, other: &Self, x0: i32, x1: i32) -> Ordering {
+        let xm = x0 as i128 + x1 as i128;
+        // y * 2 * dx at the doubled middle
+        let y = |e: &SlabEdge| {
+            let dx = (e.b.x as i128) - (e.a.x as i128);
+            let dy = (e.b.y as i128) - (e.a.y as i128);
+            2 * e.a.y as i128 * dx + dy * (xm - 2 * e.a.x as i128)
+        };
+        let dx0 = (self.b.x as i128) - (self.a.x as i128);
+        let dx1 = (other.b.x as i128) - (other.a.x as i128);
+        (y(self) * dx1).cmp(&(y(other) * dx0))
+    }
+
+    /// The point of the edge at `x`, rounded to the grid.
+    #[inline]
+    fn point_at(&self, x: i32) -> IntPoint {
+        let dx = self.b.x as i64 - self.a.x as i64;
+        let dy = self.b.y as i64 - self.a.y as i64;
+        let num = dy * (x as i64 - self.a.x as i64);
+        let y = self.a.y as i64 + (2 * num + dx).div_euclid(2 * dx);
+        IntPoint::new(x, y as i32)
+    }
+}
+
+/// Cuts valid shapes into trapezoids with vertical left and right sides.
+///
+/// Every contour vertex cuts the trapezoids above and below it by a vertical line,
+/// a trapezoid goes on while its bottom and top edges stay the same.
+/// Corners are rounded to the grid, a trapezoid with a collapsed side is a triangle.
+pub(crate) fn trapezoids(shapes: &IntShapes) -> Vec<IntContour> {
+    let mut edges = Vec::new();
+    let mut xs = Vec::new();
+    for contour in shapes.iter().flatten() {
+        for (i, &p) in contour.iter().enumerate() {
+            let q = contour[(i + 1) % contour.len()];
+            xs.push(p.x);
+            match p.x.cmp(&q.x) {
+                Ordering::Less => edges.push(SlabEdge { a: p, b: q }),
+                Ordering::Greater => edges.push(SlabEdge { a: q, b: p }),
+                Ordering::Equal => {}
+            }
+        }
+    }
+    xs.sort_unstable();
+    xs.dedup();
+    edges.sort_unstable_by_key(|e| e.a.x);
+
+    let mut result = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    // open trapezoids: bottom edge, top edge, left x
+    let mut open: Vec<(usize, usize, i32)> = Vec::new();
+    let mut next_open = Vec::new();
+    let mut pairs = HashSet::new();
+    let mut next = 0;
+
+    for slab in xs.windows(2) {
+        let (x0, x1) = (slab[0], slab[1]);
+        active.retain(|&e| edges[e].b.x > x0);
+        while next < edges.len() && edges[next].a.x == x0 {
+            let edge = &edges[next];
+            let position = active.partition_point(|&e| edges[e].order(edge, x0, x1) == Ordering::Less);
+            active.insert(position, next);
+            next += 1;
+        }
+
+        // the inside lies between the even and the odd edges
+        pairs.clear();
+        pairs.extend(active.chunks_exact(2).map(|p| (p[0], p[1])));
+        next_open.clear();
+        for &(bottom, top, x) in open.iter() {
+            if pairs.remove(&(bottom, top)) {
+                next_open.push((bottom, top, x));
+            } else {
+                push_trapezoid(&mut result, &edges[bottom], &edges[top], x, x0);
+            }
+        }
+        for p in active.chunks_exact(2) {
+            if pairs.contains(&(p[0], p[1])) {
+                next_open.push((p[0], p[1], x0));
+            }
+        }
+        std::mem::swap(&mut open, &mut next_open);
+    }
+
+    if let Some(&x) = xs.last() {
+        for &(bottom, top, x0) in open.iter() {
+            push_trapezoid(&mut result, &edges[bottom], &edges[top], x0, x);
+        }
+    }
+
+    result
+}
+
+fn push_trapezoid(result: &mut Vec<IntContour>, bottom: &SlabEdge, top: &SlabEdge, x0: i32, x1: i32) {
+    let mut contour = Vec::with_capacity(4);
+    for p in [bottom.point_at(x0), bottom.point_at(x1), top.point_at(x1), top.point_at(x0)] {
+        if contour.last() != Some(&p) {
+            contour.push(p);
+        }
+    }
+    if contour.len() > 1 && contour.first() == contour.last() {
+        contour.pop();
+    }
+    if contour.len() >= 3 {
+        result.push(contour);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use i_overlay::i_shape::int::shape::IntContour;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    // left and right sides are vertical
+    fn check_trapezoid(contour: &IntContour) {
+        assert!((3..=4).contains(&contour.len()));
+        assert!(contour.area_two() < 0);
+        let min_x = contour.iter().map(|p| p.x).min().unwrap();
+        let max_x = contour.iter().map(|p| p.x).max().unwrap();
+        assert!(contour.iter().all(|p| p.x == min_x || p.x == max_x));
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let trapezoids = Triangulator::default().trapezoidate_contour(&contour);
+        assert_eq!(trapezoids, vec![contour]);
+    }
+
+    #[test]
+    fn test_1() {
+        // the top and the bottom meet on the right
+        let contour = path(&[[0, 0], [10, 0], [0, 10]]);
+        let trapezoids = Triangulator::default().trapezoidate_contour(&contour);
+        assert_eq!(trapezoids.len(), 1);
+        assert_eq!(trapezoids[0].len(), 3);
+        check_trapezoid(&trapezoids[0]);
+    }
+
+    #[test]
+    fn test_2() {
+        // a hole cuts the square into the left, the bottom, the top and the right parts
+        let shape = vec![
+            path(&[[0, 0], [30, 0], [30, 30], [0, 30]]),
+            path(&[[10, 10], [10, 20], [20, 20], [20, 10]]),
+        ];
+        let trapezoids = Triangulator::default().trapezoidate_shape(&shape);
+        assert_eq!(trapezoids.len(), 4);
+        let area: i64 = trapezoids.iter().map(|t| t.area_two()).sum();
+        assert_eq!(area, shape.area_two());
+        for t in trapezoids.iter() {
+            check_trapezoid(t);
+        }
+
+        let unchecked = Triangulator::default().unchecked_trapezoidate_shapes(&vec![shape]);
+        assert_eq!(unchecked, trapezoids);
+    }
+
+    #[test]
+    fn test_3() {
+        // a diamond is cut by its middle vertices
+        let contour = path(&[[0, 0], [10, -10], [20, 0], [10, 10]]);
+        let trapezoids = Triangulator::default().trapezoidate_contour(&contour);
+        assert_eq!(trapezoids.len(), 2);
+        for t in trapezoids.iter() {
+            assert_eq!(t.len(), 3);
+            check_trapezoid(t);
+        }
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        let triangulator = Triangulator::default();
+        for _ in 0..1000 {
+            let n = rng.random_range(3..=16);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-100..=100), rng.random_range(-100..=100)))
+                .collect();
+
+            let raw = triangulator.triangulate_contour(&contour);
+            let mesh_area: i64 = raw
+                .triangles
+                .iter()
+                .map(|t| {
+                    let [a, b, c] = t.vertices.map(|v| v.point);
+                    Triangle::area_two_point(a, b, c)
+                })
+                .sum();
+
+            let trapezoids = triangulator.trapezoidate_contour(&contour);
+            let mut area = 0;
+            for t in trapezoids.iter() {
+                check_trapezoid(t);
+                area += t.area_two();
+            }
+
+            // a rounded edge moves by half a unit at most
+            let tolerance: i64 = (0..n).map(|i| (contour[(i + 1) % n].x - contour[i].x).abs() as i64).sum();
+            assert!((area - mesh_area).abs() <= tolerance);
+        }
+    }
+}
@@ -2,6 +2,8 @@ use crate::int::triangulation::RawIntTriangulation;
 use crate::int::triangulator::Triangulator;
 use i_overlay::i_float::int::point::IntPoint;
 use i_overlay::i_shape::int::shape::{IntContour, IntShape, IntShapes};
+use i_overlay::i_shape::int::path::IntPath;
+use i_overlay::string::line::IntLine;
 
 /// A trait for performing triangulation with default validation settings.
 ///
@@ -21,6 +23,15 @@ use i_overlay::i_shape::int::shape::{IntContour, IntShape, IntShapes};
 ///
 /// # Steiner Points
 /// Use [`triangulate_with_steiner_points`] to inject additional internal points during triangulation.
+///
+/// # Constraints
+/// Use [`triangulate_with_constraints`](IntTriangulatable::triangulate_with_constraints) to force segments (feature lines) into the mesh.
+///
+/// # Slits
+/// Use [`triangulate_with_slits`](IntTriangulatable::triangulate_with_slits) to cut the mesh along open polylines (cracks).
+///
+/// # Shape Ids
+/// Use [`triangulate_with_shape_ids`](IntTriangulatable::triangulate_with_shape_ids) to know which input shape produced each triangle.
 pub trait IntTriangulatable {
     /// Triangulates the shape(s) with automatic validation and cleanup.
     ///
@@ -31,6 +42,22 @@ pub trait IntTriangulatable {
     ///
     /// Points must lie within the shape's valid interior area (not on edges).
     fn triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation;
+
+    /// Triangulates the shape(s) so that every segment becomes a chain of triangle edges.
+    ///
+    /// Segments are split at crossings and cut at the shape boundary.
+    fn triangulate_with_constraints(&self, segments: &[IntLine]) -> RawIntTriangulation;
+
+    /// Triangulates the shape(s) and cuts the mesh along open polylines.
+    ///
+    /// Triangles on opposite sides of a slit are disconnected and its vertices are duplicated.
+    fn triangulate_with_slits(&self, slits: &[IntPath]) -> RawIntTriangulation;
+
+    /// Triangulates the shape(s) and records the index of the source shape for every triangle.
+    ///
+    /// Each shape is simplified separately, see [`Triangulator::triangulate_shapes_with_shape_ids`].
+    /// A single contour or shape gets the id `0`.
+    fn triangulate_with_shape_ids(&self) -> RawIntTriangulation;
 }
 
 impl IntTriangulatable for IntContour {
@@ -43,6 +70,21 @@ impl IntTriangulatable for IntContour {
     fn triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation {
         Triangulator::default().triangulate_contour_with_steiner_points(self, points)
     }
+
+    #[inline]
+    fn triangulate_with_constraints(&self, segments: &[IntLine]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_contour_with_constraints(self, segments)
+    }
+
+    #[inline]
+    fn triangulate_with_slits(&self, slits: &[IntPath]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_contour_with_slits(self, slits)
+    }
+
+    #[inline]
+    fn triangulate_with_shape_ids(&self) -> RawIntTriangulation {
+        Triangulator::default().triangulate_contour(self).with_shape_id(0)
+    }
 }
 
 impl IntTriangulatable for IntShape {
@@ -55,6 +97,21 @@ impl IntTriangulatable for IntShape {
     fn triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation {
         Triangulator::default().triangulate_shape_with_steiner_points(self, points)
     }
+
+    #[inline]
+    fn triangulate_with_constraints(&self, segments: &[IntLine]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shape_with_constraints(self, segments)
+    }
+
+    #[inline]
+    fn triangulate_with_slits(&self, slits: &[IntPath]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shape_with_slits(self, slits)
+    }
+
+    #[inline]
+    fn triangulate_with_shape_ids(&self) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shape(self).with_shape_id(0)
+    }
 }
 
 impl IntTriangulatable for IntShapes {
@@ -67,4 +124,19 @@ impl IntTriangulatable for IntShapes {
     fn triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation {
         Triangulator::default().triangulate_shapes_with_steiner_points(self, points)
     }
+
+    #[inline]
+    fn triangulate_with_constraints(&self, segments: &[IntLine]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shapes_with_constraints(self, segments)
+    }
+
+    #[inline]
+    fn triangulate_with_slits(&self, slits: &[IntPath]) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shapes_with_slits(self, slits)
+    }
+
+    #[inline]
+    fn triangulate_with_shape_ids(&self) -> RawIntTriangulation {
+        Triangulator::default().triangulate_shapes_with_shape_ids(self)
+    }
 }
\ No newline at end of file
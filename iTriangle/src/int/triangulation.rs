@@ -1,5 +1,10 @@
-use crate::geom::triangle::IntTriangle;
+use crate::geom::triangle::{IntTriangle, TriangleNet};
+use crate::int::constraint::ConstrainedEdges;
+use crate::int::error::{InvalidInput, TriangulationError};
+use crate::int::provenance::VertexSource;
 use i_overlay::i_float::int::point::IntPoint;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 pub trait IndexType: Copy + Clone + TryFrom<usize> {
     const MAX: usize;
@@ -42,6 +47,21 @@ impl IndexType for usize {
 pub struct IntTriangulation<I> {
     pub points: Vec<IntPoint>,
     pub indices: Vec<I>,
+    /// The source shape index of every triangle, empty unless shape ids were requested.
+    pub(crate) shape_ids: Vec<usize>,
+}
+
+impl<I> IntTriangulation<I> {
+    /// Returns the index of the source shape for every triangle, `None` unless shape ids were requested,
+    /// see [`RawIntTriangulation::shape_ids`].
+    #[inline]
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        if self.shape_ids.is_empty() {
+            None
+        } else {
+            Some(&self.shape_ids)
+        }
+    }
 }
 
 /// A int triangle mesh produced by the triangulation process.
@@ -56,6 +76,9 @@ pub struct IntTriangulation<I> {
 pub struct RawIntTriangulation {
     pub(crate) triangles: Vec<IntTriangle>,
     pub(crate) points: Vec<IntPoint>,
+    pub(crate) constraints: ConstrainedEdges,
+    pub(crate) shape_ids: Vec<usize>,
+    pub(crate) vertex_sources: Vec<VertexSource>,
 }
 
 impl RawIntTriangulation {
@@ -63,12 +86,21 @@ impl RawIntTriangulation {
         Self {
             triangles: vec![],
             points: vec![],
+            constraints: Default::default(),
+            shape_ids: vec![],
+            vertex_sources: vec![],
         }
     }
 
     #[inline]
     pub(super) fn new(triangles: Vec<IntTriangle>, points: Vec<IntPoint>) -> Self {
-        Self { triangles, points }
+        Self {
+            triangles,
+            points,
+            constraints: Default::default(),
+            shape_ids: vec![],
+            vertex_sources: vec![],
+        }
     }
 
     /// Returns true if the triangulation contains no triangles.
@@ -85,31 +117,38 @@ impl RawIntTriangulation {
         &self.points
     }
 
+    /// Returns the index of the source shape for every triangle.
+    ///
+    /// Available only for meshes built by [`Triangulator::triangulate_shapes_with_shape_ids`]
+    /// (or the `triangulate_with_shape_ids` trait methods), `None` otherwise.
+    ///
+    /// [`Triangulator::triangulate_shapes_with_shape_ids`]: crate::int::triangulator::Triangulator::triangulate_shapes_with_shape_ids
+    #[inline]
+    pub fn shape_ids(&self) -> Option<&[usize]> {
+        if self.shape_ids.is_empty() {
+            None
+        } else {
+            Some(&self.shape_ids)
+        }
+    }
+
     /// Returns a flat list of triangle vertex indices (ABC ordering).
     ///
     /// Each triangle contributes 3 indices into the `points` buffer.
     ///
+    /// # Panics
+    /// If the index type `I` cannot address all points, see [`RawIntTriangulation::try_triangle_indices`].
     #[inline]
     pub fn triangle_indices<I: IndexType>(&self) -> Vec<I> {
-        let points_count = self.points.len();
-        if points_count > I::MAX {
-            panic!(
-                "Index type `{}` cannot hold {} points",
-                std::any::type_name::<I>(),
-                points_count
-            );
-        }
-
-        let mut result = Vec::with_capacity(3 * self.triangles.len());
-        for t in &self.triangles {
-            let v = &t.vertices;
-            let i0 = I::try_from(v[0].index).unwrap_or(I::ZERO);
-            let i1 = I::try_from(v[1].index).unwrap_or(I::ZERO);
-            let i2 = I::try_from(v[2].index).unwrap_or(I::ZERO);
+        self.try_triangle_indices().unwrap_or_else(|e| panic!("{}", e))
+    }
 
-            result.extend_from_slice(&[i0, i1, i2]);
-        }
-        result
+    /// Returns a flat list of triangle vertex indices (ABC ordering).
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_triangle_indices<I: IndexType>(&self) -> Result<Vec<I>, TriangulationError> {
+        collect_indices(&self.triangles, self.points.len())
     }
 
     /// Converts the int triangulation into a simpler index-based mesh.
@@ -120,18 +159,180 @@ impl RawIntTriangulation {
         IntTriangulation {
             indices: self.triangle_indices(),
             points: self.points,
+            shape_ids: self.shape_ids,
+        }
+    }
+
+    /// Converts the int triangulation into a simpler index-based mesh.
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if the index type `I` cannot address all points.
+    #[inline]
+    pub fn try_into_triangulation<I: IndexType>(self) -> Result<IntTriangulation<I>, TriangulationError> {
+        Ok(IntTriangulation {
+            indices: self.try_triangle_indices()?,
+            points: self.points,
+            shape_ids: self.shape_ids,
+        })
+    }
+
+    /// Returns a flat list of triangle vertex indices into the `input` points instead of `points`.
+    ///
+    /// Mesh points are matched to the `input` points by position, a repeated input point maps to its first position.
+    /// Fails with [`InvalidInput::UnknownPoint`] if a mesh point is not one of the `input` points.
+    pub(crate) fn try_input_indices<I: IndexType>(
+        &self,
+        input: impl Iterator<Item = IntPoint>,
+    ) -> Result<Vec<I>, TriangulationError> {
+        let mut sorted: Vec<(IntPoint, usize)> = input.enumerate().map(|(i, p)| (p, i)).collect();
+        check_index_type::<I>(sorted.len())?;
+
+        // sorted by position within equal points, so dedup keeps the first one
+        sorted.sort_unstable();
+        sorted.dedup_by_key(|e| e.0);
+
+        let mut map = Vec::with_capacity(self.points.len());
+        for p in self.points.iter() {
+            let k = sorted
+                .binary_search_by(|e| e.0.cmp(p))
+                .map_err(|_| InvalidInput::UnknownPoint { x: p.x, y: p.y })?;
+            map.push(sorted[k].1);
+        }
+
+        let mut result = Vec::with_capacity(3 * self.triangles.len());
+        for t in self.triangles.iter() {
+            for v in t.vertices.iter() {
+                result.push(I::try_from(map[v.index]).unwrap_or(I::ZERO));
+            }
         }
+        Ok(result)
     }
 
     #[inline]
     pub(crate) fn shift(&mut self, points_offset: usize, triangle_offset: usize) {
-        for t in self.triangles.iter_mut() {
-            t.vertices[0].index += points_offset;
-            t.vertices[1].index += points_offset;
-            t.vertices[2].index += points_offset;
-            t.neighbors[0] = t.neighbors[0].saturating_add(triangle_offset);
-            t.neighbors[1] = t.neighbors[1].saturating_add(triangle_offset);
-            t.neighbors[2] = t.neighbors[2].saturating_add(triangle_offset);
+        self.triangles.shift(points_offset, triangle_offset);
+        self.constraints.shift(points_offset);
+    }
+
+    /// Concatenates independent triangulations in the given order.
+    ///
+    /// Point and triangle offsets of every part are computed by prefix sums,
+    /// so parts can be shifted in parallel and the result stays deterministic.
+    pub(crate) fn merge(mut parts: Vec<RawIntTriangulation>) -> RawIntTriangulation {
+        let mut offsets = Vec::with_capacity(parts.len());
+        let mut points_count = 0;
+        let mut triangles_count = 0;
+        for part in parts.iter() {
+            offsets.push((points_count, triangles_count));
+            points_count += part.points.len();
+            triangles_count += part.triangles.len();
+        }
+
+        #[cfg(feature = "rayon")]
+        parts
+            .par_iter_mut()
+            .zip(offsets.par_iter())
+            .for_each(|(part, &(points_offset, triangle_offset))| part.shift(points_offset, triangle_offset));
+
+        #[cfg(not(feature = "rayon"))]
+        for (part, &(points_offset, triangle_offset)) in parts.iter_mut().zip(offsets.iter()) {
+            part.shift(points_offset, triangle_offset);
+        }
+
+        let has_shape_ids = parts.iter().all(Self::keeps_shape_ids);
+        let has_vertex_sources = parts.iter().all(Self::keeps_vertex_sources);
+
+        let mut result = Self::new(
+            Vec::with_capacity(triangles_count),
+            Vec::with_capacity(points_count),
+        );
+        if has_shape_ids {
+            result.shape_ids.reserve(triangles_count);
+        }
+        if has_vertex_sources {
+            result.vertex_sources.reserve(points_count);
+        }
+        for mut part in parts {
+            result.triangles.append(&mut part.triangles);
+            result.points.append(&mut part.points);
+            result.constraints.append(&mut part.constraints);
+            if has_shape_ids {
+                result.shape_ids.append(&mut part.shape_ids);
+            }
+            if has_vertex_sources {
+                result.vertex_sources.append(&mut part.vertex_sources);
+            }
+        }
+
+        result
+    }
+
+    #[inline]
+    pub(crate) fn append(&mut self, mut other: RawIntTriangulation) {
+        let has_shape_ids = self.keeps_shape_ids() && other.keeps_shape_ids();
+        let has_vertex_sources = self.keeps_vertex_sources() && other.keeps_vertex_sources();
+        other.shift(self.points.len(), self.triangles.len());
+        self.triangles.append(&mut other.triangles);
+        self.points.append(&mut other.points);
+        self.constraints.append(&mut other.constraints);
+        if has_shape_ids {
+            self.shape_ids.append(&mut other.shape_ids);
+        } else {
+            self.shape_ids.clear();
         }
+        if has_vertex_sources {
+            self.vertex_sources.append(&mut other.vertex_sources);
+        } else {
+            self.vertex_sources.clear();
+        }
+    }
+
+    /// Marks every triangle as produced by the shape `shape_id`.
+    #[inline]
+    pub(crate) fn with_shape_id(mut self, shape_id: usize) -> Self {
+        self.shape_ids.clear();
+        self.shape_ids.resize(self.triangles.len(), shape_id);
+        self
+    }
+
+    /// An empty mesh does not break shape ids of the mesh it is merged with.
+    #[inline]
+    fn keeps_shape_ids(&self) -> bool {
+        self.triangles.is_empty() || !self.shape_ids.is_empty()
+    }
+
+    #[inline]
+    fn keeps_vertex_sources(&self) -> bool {
+        self.points.is_empty() || !self.vertex_sources.is_empty()
+    }
+}
+
+/// Checks that the index type `I` can address `points_count` points.
+#[inline]
+pub(crate) fn check_index_type<I: IndexType>(points_count: usize) -> Result<(), TriangulationError> {
+    if points_count > I::MAX {
+        Err(TriangulationError::IndexOverflow {
+            index_type: std::any::type_name::<I>(),
+            points_count,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn collect_indices<I: IndexType>(
+    triangles: &[IntTriangle],
+    points_count: usize,
+) -> Result<Vec<I>, TriangulationError> {
+    check_index_type::<I>(points_count)?;
+
+    let mut result = Vec::with_capacity(3 * triangles.len());
+    for t in triangles {
+        let v = &t.vertices;
+        let i0 = I::try_from(v[0].index).unwrap_or(I::ZERO);
+        let i1 = I::try_from(v[1].index).unwrap_or(I::ZERO);
+        let i2 = I::try_from(v[2].index).unwrap_or(I::ZERO);
+
+        result.extend_from_slice(&[i0, i1, i2]);
     }
+    Ok(result)
 }
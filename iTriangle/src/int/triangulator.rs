@@ -6,9 +6,20 @@ use i_overlay::core::fill_rule::FillRule;
 use i_overlay::core::overlay::IntOverlayOptions;
 use i_overlay::core::simplify::Simplify;
 use i_overlay::i_float::int::point::IntPoint;
-use i_overlay::i_shape::int::count::PointsCount;
 use i_overlay::i_shape::int::shape::{IntContour, IntShape, IntShapes};
 use crate::int::monotone::chain_builder::ToChainVertices;
+use crate::int::constraint::{clip_segments, segment_inner_points, SplitSegments};
+use i_overlay::string::line::IntLine;
+use i_overlay::i_shape::int::path::IntPath;
+use crate::int::slit::{insert_slit_ends, slit_segments};
+use crate::int::error::{InvalidInput, TriangulationError};
+use crate::int::check::ShapeCheck;
+use crate::int::context::TriangulatorContext;
+use crate::int::triangulation::IndexType;
+use crate::int::partition::IntMonotonePartition;
+use crate::int::trapezoid::trapezoids;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Validation {
@@ -86,34 +97,13 @@ impl Triangulator {
             };
         }
 
-        let mut triangles_count = 0;
-        let mut points_count = 0;
-        for shape in shapes.iter() {
-            triangles_count += shape.iter().fold(0, |s, path| s + path.len() - 2);
-            points_count += shape.points_count();
-        }
-
-        let mut triangles = Vec::with_capacity(triangles_count);
-        let mut points = Vec::with_capacity(points_count);
+        #[cfg(feature = "rayon")]
+        let parts = shapes.par_iter().map(|shape| self.triangulate_shape(shape)).collect();
 
-        let mut iter = shapes.iter();
-        if let Some(first) = iter.next() {
-            let mut first_raw = self.triangulate_shape(first);
-            triangles.append(&mut first_raw.triangles);
-            points.append(&mut first_raw.points);
+        #[cfg(not(feature = "rayon"))]
+        let parts = shapes.iter().map(|shape| self.triangulate_shape(shape)).collect();
 
-            for shape in iter {
-                let points_offset = points.len();
-                let triangle_offset = triangles.len();
-                let mut raw = self.triangulate_shape(shape);
-                raw.shift(points_offset, triangle_offset);
-
-                triangles.append(&mut raw.triangles);
-                points.append(&mut raw.points);
-            }
-        }
-
-        RawIntTriangulation::new(triangles, points)
+        RawIntTriangulation::merge(parts)
     }
 
     /// Triangulates a list of shapes, inserting user-provided Steiner points before processing.
@@ -147,40 +137,131 @@ impl Triangulator {
             };
         }
 
-        let mut triangles_count = 0;
-        let mut points_count = 0;
-        for (i, shape) in shapes.iter().enumerate() {
-            let steiner_points_count = groups[i].len();
-            triangles_count +=
-                shape.iter().fold(0, |s, path| s + path.len() - 2) + 2 * steiner_points_count;
-            points_count += shape.points_count() + steiner_points_count;
-        }
+        #[cfg(feature = "rayon")]
+        let parts = shapes
+            .par_iter()
+            .zip(groups.par_iter())
+            .map(|(shape, steiner_points)| self.unchecked_triangulate_shape_with_steiner_points(shape, steiner_points))
+            .collect();
+
+        #[cfg(not(feature = "rayon"))]
+        let parts = shapes
+            .iter()
+            .zip(groups.iter())
+            .map(|(shape, steiner_points)| self.unchecked_triangulate_shape_with_steiner_points(shape, steiner_points))
+            .collect();
+
+        RawIntTriangulation::merge(parts)
+    }
+}
+
+impl Triangulator {
+    /// Triangulates a contour without any validation and returns indices into the contour itself.
+    ///
+    /// Unlike [`Triangulator::unchecked_triangulate_contour`], the indices point into the contour itself,
+    /// so the result is an index buffer for a vertex buffer that already holds the contour.
+    /// The mesh is built on a copy, its points are mapped back to the contour by position.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_contour`].
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all contour points or the mesh has a point
+    /// which is not a contour point, see [`Triangulator::try_unchecked_triangulate_contour_indices`].
+    #[inline]
+    pub fn unchecked_triangulate_contour_indices<I: IndexType>(&self, contour: &IntContour) -> Vec<I> {
+        self.unchecked_triangulate_contour(contour)
+            .try_input_indices(contour.iter().copied())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Triangulates a shape without any validation and returns indices into its contours
+    /// flattened in order (outer contour first, then holes).
+    ///
+    /// A point shared by several contours (e.g. a hole touching the outer contour)
+    /// is referenced by its first position.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_shape`].
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all shape points or the mesh has a point
+    /// which is not a shape point, see [`Triangulator::try_unchecked_triangulate_shape_indices`].
+    #[inline]
+    pub fn unchecked_triangulate_shape_indices<I: IndexType>(&self, shape: &IntShape) -> Vec<I> {
+        self.unchecked_triangulate_shape(shape)
+            .try_input_indices(shape.iter().flatten().copied())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let mut triangles = Vec::with_capacity(triangles_count);
-        let mut points = Vec::with_capacity(points_count);
+    /// Fallible version of [`Triangulator::unchecked_triangulate_contour_indices`].
+    ///
+    /// The contour is checked the same way as in [`Triangulator::try_unchecked_triangulate_contour`].
+    pub fn try_unchecked_triangulate_contour_indices<I: IndexType>(
+        &self,
+        contour: &IntContour,
+    ) -> Result<Vec<I>, TriangulationError> {
+        self.try_unchecked_triangulate_contour(contour)?
+            .try_input_indices(contour.iter().copied())
+    }
 
-        let mut first_raw =
-            self.unchecked_triangulate_shape_with_steiner_points(&shapes[0], &groups[0]);
-        triangles.append(&mut first_raw.triangles);
-        points.append(&mut first_raw.points);
+    /// Fallible version of [`Triangulator::unchecked_triangulate_shape_indices`].
+    ///
+    /// The shape is checked the same way as in [`Triangulator::try_unchecked_triangulate_shape`].
+    pub fn try_unchecked_triangulate_shape_indices<I: IndexType>(
+        &self,
+        shape: &IntShape,
+    ) -> Result<Vec<I>, TriangulationError> {
+        self.try_unchecked_triangulate_shape(shape)?
+            .try_input_indices(shape.iter().flatten().copied())
+    }
+}
 
-        let mut i = 1;
-        while i < shapes.len() {
-            let shape = &shapes[i];
-            let steiner_points = &groups[i];
-            i += 1;
+impl Triangulator {
+    /// Triangulates a list of shapes and records the index of the source shape for every triangle,
+    /// see [`RawIntTriangulation::shape_ids`].
+    ///
+    /// Every shape is validated and simplified on its own, so the ids survive simplification.
+    /// As a consequence overlapping shapes are not merged and their triangles overlap.
+    pub fn triangulate_shapes_with_shape_ids(&self, shapes: &IntShapes) -> RawIntTriangulation {
+        #[cfg(feature = "rayon")]
+        let parts = shapes
+            .par_iter()
+            .enumerate()
+            .map(|(shape_id, shape)| self.triangulate_shape(shape).with_shape_id(shape_id))
+            .collect();
 
-            let points_offset = points.len();
-            let triangle_offset = triangles.len();
-            let mut raw =
-                self.unchecked_triangulate_shape_with_steiner_points(shape, steiner_points);
-            raw.shift(points_offset, triangle_offset);
+        #[cfg(not(feature = "rayon"))]
+        let parts = shapes
+            .iter()
+            .enumerate()
+            .map(|(shape_id, shape)| self.triangulate_shape(shape).with_shape_id(shape_id))
+            .collect();
 
-            triangles.append(&mut raw.triangles);
-            points.append(&mut raw.points);
-        }
+        RawIntTriangulation::merge(parts)
+    }
+
+    /// Triangulates a list of shapes without any validation and records the index
+    /// of the source shape for every triangle, see [`RawIntTriangulation::shape_ids`].
+    ///
+    /// # Safety
+    /// Same rules apply as [`unchecked_triangulate_shapes`](Triangulator::unchecked_triangulate_shapes).
+    pub fn unchecked_triangulate_shapes_with_shape_ids(&self, shapes: &IntShapes) -> RawIntTriangulation {
+        #[cfg(feature = "rayon")]
+        let parts = shapes
+            .par_iter()
+            .enumerate()
+            .map(|(shape_id, shape)| self.unchecked_triangulate_shape(shape).with_shape_id(shape_id))
+            .collect();
+
+        #[cfg(not(feature = "rayon"))]
+        let parts = shapes
+            .iter()
+            .enumerate()
+            .map(|(shape_id, shape)| self.unchecked_triangulate_shape(shape).with_shape_id(shape_id))
+            .collect();
 
-        RawIntTriangulation::new(triangles, points)
+        RawIntTriangulation::merge(parts)
     }
 }
 
@@ -292,3 +373,782 @@ impl Triangulator {
         RawIntTriangulation::new(net_builder.triangles, chain_vertices.into_points())
     }
 }
+
+impl Triangulator {
+    /// Triangulates a list of shapes after validation, forcing the given segments to become triangle edges.
+    ///
+    /// Segments are split at mutual crossings and assigned to the shape containing them,
+    /// pieces lying outside of every shape or in a hole are dropped.
+    /// A segment leaving its shape is cut at the boundary, the cut point becomes a contour vertex
+    /// (rounded to an int point) and only the part inside the shape is kept.
+    ///
+    /// Resulting constrained edges are kept by [`RawIntTriangulation::into_delaunay`] and by
+    /// circumcenter refinement (which may only split them).
+    pub fn triangulate_shapes_with_constraints(
+        &self,
+        shapes: &IntShapes,
+        segments: &[IntLine],
+    ) -> RawIntTriangulation {
+        let mut shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        let segments = clip_segments(&mut shapes, segments);
+        let groups = shapes.group_segments_by_shapes(&segments);
+        self.unchecked_triangulate_shapes_with_constraints(&shapes, &groups)
+    }
+
+    /// Performs triangulation on shapes and associated constraint segments without any validation.
+    ///
+    /// # Safety
+    /// Same rules apply as [`unchecked_triangulate_shapes`](Triangulator::unchecked_triangulate_shapes), with additional constraints:
+    /// - Every segment in a group must lie inside its assigned shape.
+    /// - Segment ends must be **strictly inside** the shape or coincide with a shape vertex.
+    pub fn unchecked_triangulate_shapes_with_constraints(
+        &self,
+        shapes: &IntShapes,
+        groups: &[Vec<IntLine>],
+    ) -> RawIntTriangulation {
+        let mut iter = shapes.iter().zip(groups.iter());
+        let mut result = if let Some((shape, segments)) = iter.next() {
+            self.unchecked_triangulate_shape_with_constraints(shape, segments)
+        } else {
+            return RawIntTriangulation::empty();
+        };
+
+        for (shape, segments) in iter {
+            result.append(self.unchecked_triangulate_shape_with_constraints(shape, segments));
+        }
+
+        result
+    }
+
+    /// Triangulates a single shape with constraint segments after simplifying and validating it.
+    pub fn triangulate_shape_with_constraints(
+        &self,
+        shape: &IntShape,
+        segments: &[IntLine],
+    ) -> RawIntTriangulation {
+        let mut shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        let segments = clip_segments(&mut shapes, segments);
+        let groups = shapes.group_segments_by_shapes(&segments);
+        self.unchecked_triangulate_shapes_with_constraints(&shapes, &groups)
+    }
+
+    /// Triangulates a single shape with constraint segments, assuming everything is valid.
+    ///
+    /// Segments are still split at mutual crossings.
+    pub fn unchecked_triangulate_shape_with_constraints(
+        &self,
+        shape: &IntShape,
+        segments: &[IntLine],
+    ) -> RawIntTriangulation {
+        let segments = segments.split_at_crossings();
+        let points = segment_inner_points(shape, &segments);
+        let mut raw = self.unchecked_triangulate_shape_with_steiner_points(shape, &points);
+        raw.insert_constraints(&segments);
+        raw
+    }
+
+    /// Triangulates a closed contour with constraint segments after validation.
+    pub fn triangulate_contour_with_constraints(
+        &self,
+        contour: &IntContour,
+        segments: &[IntLine],
+    ) -> RawIntTriangulation {
+        let mut shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        let segments = clip_segments(&mut shapes, segments);
+        let groups = shapes.group_segments_by_shapes(&segments);
+        self.unchecked_triangulate_shapes_with_constraints(&shapes, &groups)
+    }
+
+    /// Triangulates a closed contour with constraint segments, assuming all inputs are valid.
+    pub fn unchecked_triangulate_contour_with_constraints(
+        &self,
+        contour: &IntContour,
+        segments: &[IntLine],
+    ) -> RawIntTriangulation {
+        let segments = segments.split_at_crossings();
+        let points = segment_inner_points(std::slice::from_ref(contour), &segments);
+        let mut raw = self.unchecked_triangulate_contour_with_steiner_points(contour, &points);
+        raw.insert_constraints(&segments);
+        raw
+    }
+}
+
+impl Triangulator {
+    /// Triangulates a list of shapes after validation and cuts the mesh along open polylines (slits).
+    ///
+    /// Every slit becomes a chain of triangle edges, triangles on opposite sides of it are not
+    /// neighbors and the vertices along it are duplicated, one copy per side. The tips of a slit
+    /// lying inside a shape stay shared.
+    ///
+    /// A slit vertex must lie inside its shape, on one of its edges or coincide with one of its
+    /// vertices. Slit segments leaving their shape are ignored.
+    pub fn triangulate_shapes_with_slits(
+        &self,
+        shapes: &IntShapes,
+        slits: &[IntPath],
+    ) -> RawIntTriangulation {
+        let shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        self.triangulate_simplified_with_slits(shapes, slits)
+    }
+
+    /// Triangulates a single shape after validation and cuts the mesh along the given slits.
+    ///
+    /// See [`Triangulator::triangulate_shapes_with_slits`].
+    pub fn triangulate_shape_with_slits(&self, shape: &IntShape, slits: &[IntPath]) -> RawIntTriangulation {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        self.triangulate_simplified_with_slits(shapes, slits)
+    }
+
+    /// Triangulates a closed contour after validation and cuts the mesh along the given slits.
+    ///
+    /// See [`Triangulator::triangulate_shapes_with_slits`].
+    pub fn triangulate_contour_with_slits(
+        &self,
+        contour: &IntContour,
+        slits: &[IntPath],
+    ) -> RawIntTriangulation {
+        let shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        self.triangulate_simplified_with_slits(shapes, slits)
+    }
+
+    /// Triangulates a single valid shape and cuts the mesh along the given slits.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_shape_with_constraints`]
+    /// for every slit segment.
+    pub fn unchecked_triangulate_shape_with_slits(
+        &self,
+        shape: &IntShape,
+        slits: &[IntPath],
+    ) -> RawIntTriangulation {
+        let segments = slit_segments(slits);
+        let mut raw = self.unchecked_triangulate_shape_with_constraints(shape, &segments);
+        raw.cut_along_constraints();
+        raw
+    }
+
+    /// Triangulates a valid closed contour and cuts the mesh along the given slits.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_shape_with_constraints`]
+    /// for every slit segment.
+    pub fn unchecked_triangulate_contour_with_slits(
+        &self,
+        contour: &IntContour,
+        slits: &[IntPath],
+    ) -> RawIntTriangulation {
+        let segments = slit_segments(slits);
+        let mut raw = self.unchecked_triangulate_contour_with_constraints(contour, &segments);
+        raw.cut_along_constraints();
+        raw
+    }
+
+    fn triangulate_simplified_with_slits(&self, mut shapes: IntShapes, slits: &[IntPath]) -> RawIntTriangulation {
+        // simplification removes collinear points, so slit ends are put on the boundary afterwards
+        for shape in shapes.iter_mut() {
+            insert_slit_ends(shape, slits);
+        }
+        let groups = shapes.group_segments_by_shapes(&slit_segments(slits));
+        let mut raw = self.unchecked_triangulate_shapes_with_constraints(&shapes, &groups);
+        raw.cut_along_constraints();
+        raw
+    }
+}
+
+impl Triangulator {
+    /// Cuts a shape into x-monotone pieces after validation and simplification.
+    ///
+    /// The pieces are the sections of the triangulation sweep, cut apart by the diagonals it adds
+    /// at split and merge vertices. See [`IntMonotonePartition`].
+    pub fn monotone_partition(&self, shape: &IntShape) -> IntMonotonePartition {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        let mut partition = IntMonotonePartition::default();
+        for shape in shapes.iter() {
+            let part = self.unchecked_monotone_partition(shape);
+            partition.contours.extend(part.contours);
+            partition.diagonals.extend(part.diagonals);
+        }
+        partition
+    }
+
+    /// Cuts a single valid shape into x-monotone pieces without simplification or validation.
+    pub fn unchecked_monotone_partition(&self, shape: &IntShape) -> IntMonotonePartition {
+        let triangles_count = shape.iter().fold(0, |s, path| s + path.len() - 2);
+
+        let chain_vertices = shape.to_chain_vertices();
+        let mut net_builder = TriangleMeshBuilder::with_triangles_count(triangles_count);
+        let diagonals = net_builder.build_with_diagonals(&chain_vertices);
+
+        RawIntTriangulation::new(net_builder.triangles, chain_vertices.into_points()).monotone_partition(&diagonals)
+    }
+}
+
+impl Triangulator {
+    /// Cuts shapes into trapezoids with vertical left and right sides after validation and simplification.
+    ///
+    /// A vertical line through every vertex cuts the trapezoids above and below it.
+    /// Corners are rounded to int points and a trapezoid with a collapsed side becomes a triangle.
+    /// Contours are wound like the outer contours.
+    pub fn trapezoidate_shapes(&self, shapes: &IntShapes) -> Vec<IntContour> {
+        let shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        self.unchecked_trapezoidate_shapes(&shapes)
+    }
+
+    /// Cuts a single shape into trapezoids after validation, see [`Triangulator::trapezoidate_shapes`].
+    pub fn trapezoidate_shape(&self, shape: &IntShape) -> Vec<IntContour> {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        self.unchecked_trapezoidate_shapes(&shapes)
+    }
+
+    /// Cuts a single contour into trapezoids after validation, see [`Triangulator::trapezoidate_shapes`].
+    pub fn trapezoidate_contour(&self, contour: &IntContour) -> Vec<IntContour> {
+        let shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        self.unchecked_trapezoidate_shapes(&shapes)
+    }
+
+    /// Cuts valid shapes into trapezoids without any validation.
+    ///
+    /// Shapes must follow the rules of [`Triangulator::unchecked_triangulate_shapes`] and must not overlap.
+    pub fn unchecked_trapezoidate_shapes(&self, shapes: &IntShapes) -> Vec<IntContour> {
+        trapezoids(shapes)
+    }
+}
+
+impl Triangulator {
+    /// Fallible version of [`Triangulator::triangulate_shapes`].
+    ///
+    /// Fails with [`TriangulationError::EmptyInput`] if nothing is left after validation.
+    pub fn try_triangulate_shapes(&self, shapes: &IntShapes) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        if shapes.is_empty() {
+            return Err(TriangulationError::EmptyInput);
+        }
+        Ok(self.unchecked_triangulate_shapes(&shapes))
+    }
+
+    /// Fallible version of [`Triangulator::triangulate_shape`].
+    pub fn try_triangulate_shape(&self, shape: &IntShape) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        if shapes.is_empty() {
+            return Err(TriangulationError::EmptyInput);
+        }
+        Ok(self.unchecked_triangulate_shapes(&shapes))
+    }
+
+    /// Fallible version of [`Triangulator::triangulate_contour`].
+    pub fn try_triangulate_contour(&self, contour: &IntContour) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        if shapes.is_empty() {
+            return Err(TriangulationError::EmptyInput);
+        }
+        Ok(self.unchecked_triangulate_shapes(&shapes))
+    }
+
+    /// Fallible version of [`Triangulator::triangulate_shapes_with_steiner_points`].
+    ///
+    /// Unlike the infallible version, which silently drops them, fails with
+    /// [`TriangulationError::UnassignableSteinerPoints`] if some points are not strictly inside any shape.
+    pub fn try_triangulate_shapes_with_steiner_points(
+        &self,
+        shapes: &IntShapes,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        self.try_triangulate_simplified_with_steiner_points(&shapes, points)
+    }
+
+    /// Fallible version of [`Triangulator::triangulate_shape_with_steiner_points`].
+    pub fn try_triangulate_shape_with_steiner_points(
+        &self,
+        shape: &IntShape,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        self.try_triangulate_simplified_with_steiner_points(&shapes, points)
+    }
+
+    /// Fallible version of [`Triangulator::triangulate_contour_with_steiner_points`].
+    pub fn try_triangulate_contour_with_steiner_points(
+        &self,
+        contour: &IntContour,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        let shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        self.try_triangulate_simplified_with_steiner_points(&shapes, points)
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_shapes`].
+    ///
+    /// Contour sizes and orientations, self-intersections and hole placement are checked
+    /// before triangulation.
+    pub fn try_unchecked_triangulate_shapes(&self, shapes: &IntShapes) -> Result<RawIntTriangulation, TriangulationError> {
+        let mut iter = shapes.iter().enumerate();
+        let mut result = if let Some((index, shape)) = iter.next() {
+            self.try_unchecked_shape(index, shape, &[])?
+        } else {
+            return Err(TriangulationError::EmptyInput);
+        };
+
+        for (index, shape) in iter {
+            result.append(self.try_unchecked_shape(index, shape, &[])?);
+        }
+
+        Ok(result)
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_shape`].
+    pub fn try_unchecked_triangulate_shape(&self, shape: &IntShape) -> Result<RawIntTriangulation, TriangulationError> {
+        self.try_unchecked_shape(0, shape, &[])
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_contour`].
+    pub fn try_unchecked_triangulate_contour(&self, contour: &IntContour) -> Result<RawIntTriangulation, TriangulationError> {
+        self.try_unchecked_shape(0, &vec![contour.clone()], &[])
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_shapes_with_steiner_points`].
+    ///
+    /// In addition to [`Triangulator::try_unchecked_triangulate_shapes`] checks, every Steiner point
+    /// must be strictly inside its assigned shape.
+    pub fn try_unchecked_triangulate_shapes_with_steiner_points(
+        &self,
+        shapes: &IntShapes,
+        groups: &[Vec<IntPoint>],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        if shapes.len() != groups.len() {
+            return Err(InvalidInput::GroupsMismatch {
+                shapes: shapes.len(),
+                groups: groups.len(),
+            }
+            .into());
+        }
+
+        let mut iter = shapes.iter().zip(groups.iter()).enumerate();
+        let mut result = if let Some((index, (shape, points))) = iter.next() {
+            self.try_unchecked_shape(index, shape, points)?
+        } else {
+            return Err(TriangulationError::EmptyInput);
+        };
+
+        for (index, (shape, points)) in iter {
+            result.append(self.try_unchecked_shape(index, shape, points)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_shape_with_steiner_points`].
+    pub fn try_unchecked_triangulate_shape_with_steiner_points(
+        &self,
+        shape: &IntShape,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        self.try_unchecked_shape(0, shape, points)
+    }
+
+    /// Fallible version of [`Triangulator::unchecked_triangulate_contour_with_steiner_points`].
+    pub fn try_unchecked_triangulate_contour_with_steiner_points(
+        &self,
+        contour: &IntContour,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        self.try_unchecked_shape(0, &vec![contour.clone()], points)
+    }
+
+    fn try_triangulate_simplified_with_steiner_points(
+        &self,
+        shapes: &IntShapes,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        if shapes.is_empty() {
+            return Err(TriangulationError::EmptyInput);
+        }
+        let groups = shapes.group_by_shapes(points);
+        let unassigned = unique_count(points) - groups.iter().map(Vec::len).sum::<usize>();
+        if unassigned > 0 {
+            return Err(TriangulationError::UnassignableSteinerPoints { count: unassigned });
+        }
+        Ok(self.unchecked_triangulate_shapes_with_steiner_points(shapes, &groups))
+    }
+
+    fn try_unchecked_shape(
+        &self,
+        index: usize,
+        shape: &IntShape,
+        points: &[IntPoint],
+    ) -> Result<RawIntTriangulation, TriangulationError> {
+        if shape.is_empty() {
+            return Err(TriangulationError::EmptyInput);
+        }
+        shape.check(index)?;
+
+        if !points.is_empty() {
+            let assigned = std::slice::from_ref(shape).group_by_shapes(points)[0].len();
+            let unassigned = unique_count(points) - assigned;
+            if unassigned > 0 {
+                return Err(TriangulationError::UnassignableSteinerPoints { count: unassigned });
+            }
+        }
+
+        Ok(self.unchecked_triangulate_shape_with_steiner_points(shape, points))
+    }
+}
+
+
+impl Triangulator {
+    /// Triangulates a list of shapes after validation and appends the result to caller-owned buffers.
+    ///
+    /// New triangles index into `points`, so buffers may already contain other meshes.
+    /// Scratch memory is taken from `context`, see [`TriangulatorContext`].
+    ///
+    /// Fails with [`TriangulationError::IndexOverflow`] if `I` cannot address all points,
+    /// in this case the buffers are left unchanged.
+    pub fn triangulate_shapes_into<I: IndexType>(
+        &self,
+        shapes: &IntShapes,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        let shapes = shapes.simplify(self.validation.fill_rule, self.validation.options);
+        Self::shapes_into(&shapes, context, points, indices, |p| p)
+    }
+
+    /// Triangulates a single shape after validation and appends the result to caller-owned buffers.
+    ///
+    /// See [`Triangulator::triangulate_shapes_into`].
+    pub fn triangulate_shape_into<I: IndexType>(
+        &self,
+        shape: &IntShape,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        let shapes = shape.simplify(self.validation.fill_rule, self.validation.options);
+        Self::shapes_into(&shapes, context, points, indices, |p| p)
+    }
+
+    /// Triangulates a closed contour after validation and appends the result to caller-owned buffers.
+    ///
+    /// See [`Triangulator::triangulate_shapes_into`].
+    pub fn triangulate_contour_into<I: IndexType>(
+        &self,
+        contour: &IntContour,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        let shapes = contour.simplify(self.validation.fill_rule, self.validation.options);
+        Self::shapes_into(&shapes, context, points, indices, |p| p)
+    }
+
+    /// Triangulates a list of valid shapes and appends the result to caller-owned buffers.
+    ///
+    /// Once `context` and the buffers have grown enough, this method does not allocate.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_shapes`].
+    pub fn unchecked_triangulate_shapes_into<I: IndexType>(
+        &self,
+        shapes: &IntShapes,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        Self::shapes_into(shapes, context, points, indices, |p| p)
+    }
+
+    /// Triangulates a single valid shape and appends the result to caller-owned buffers.
+    ///
+    /// See [`Triangulator::unchecked_triangulate_shapes_into`].
+    pub fn unchecked_triangulate_shape_into<I: IndexType>(
+        &self,
+        shape: &IntShape,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        context.append(shape, &[], points, indices, |p| p)
+    }
+
+    /// Triangulates a valid closed contour and appends the result to caller-owned buffers.
+    ///
+    /// See [`Triangulator::unchecked_triangulate_shapes_into`].
+    pub fn unchecked_triangulate_contour_into<I: IndexType>(
+        &self,
+        contour: &IntContour,
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        context.append(std::slice::from_ref(contour), &[], points, indices, |p| p)
+    }
+
+    /// Triangulates a single valid shape with Steiner points and appends the result to caller-owned buffers.
+    ///
+    /// # Safety
+    /// Same rules apply as [`Triangulator::unchecked_triangulate_shape_with_steiner_points`].
+    pub fn unchecked_triangulate_shape_with_steiner_points_into<I: IndexType>(
+        &self,
+        shape: &IntShape,
+        steiner_points: &[IntPoint],
+        context: &mut TriangulatorContext,
+        points: &mut Vec<IntPoint>,
+        indices: &mut Vec<I>,
+    ) -> Result<(), TriangulationError> {
+        context.append(shape, steiner_points, points, indices, |p| p)
+    }
+
+    pub(crate) fn shapes_into<P, I: IndexType, F: Fn(IntPoint) -> P + Copy>(
+        shapes: &[IntShape],
+        context: &mut TriangulatorContext,
+        points: &mut Vec<P>,
+        indices: &mut Vec<I>,
+        map: F,
+    ) -> Result<(), TriangulationError> {
+        let points_len = points.len();
+        let indices_len = indices.len();
+        for shape in shapes.iter() {
+            if let Err(error) = context.append(shape, &[], points, indices, map) {
+                points.truncate(points_len);
+                indices.truncate(indices_len);
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unique_count(points: &[IntPoint]) -> usize {
+    let mut points = points.to_vec();
+    points.sort_unstable();
+    points.dedup();
+    points.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::error::{InvalidInput, TriangulationError};
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use i_overlay::i_shape::int::shape::IntShape;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let triangulator = Triangulator::default();
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+
+        let raw = triangulator.try_triangulate_contour(&contour).unwrap();
+        assert_eq!(raw.triangles.len(), 2);
+
+        let empty = path(&[[0, 0], [10, 0], [20, 0]]);
+        assert_eq!(triangulator.try_triangulate_contour(&empty).err(), Some(TriangulationError::EmptyInput));
+        assert_eq!(triangulator.try_triangulate_shapes(&vec![]).err(), Some(TriangulationError::EmptyInput));
+    }
+
+    #[test]
+    fn test_1() {
+        let triangulator = Triangulator::default();
+        let contour = path(&[[0, 0], [10, 0], [10, 10], [0, 10]]);
+        let points = [IntPoint::new(5, 5), IntPoint::new(10, 5), IntPoint::new(20, 20)];
+
+        let result = triangulator.try_triangulate_contour_with_steiner_points(&contour, &points);
+        assert_eq!(result.err(), Some(TriangulationError::UnassignableSteinerPoints { count: 2 }));
+
+        let result = triangulator.try_unchecked_triangulate_contour_with_steiner_points(&contour, &points);
+        assert_eq!(result.err(), Some(TriangulationError::UnassignableSteinerPoints { count: 2 }));
+
+        let raw = triangulator
+            .try_triangulate_contour_with_steiner_points(&contour, &points[..1])
+            .unwrap();
+        assert_eq!(raw.triangles.len(), 4);
+    }
+
+    #[test]
+    fn test_2() {
+        let triangulator = Triangulator::default();
+
+        let cw = path(&[[0, 0], [0, 10], [10, 10], [10, 0]]);
+        let result = triangulator.try_unchecked_triangulate_contour(&cw);
+        assert_eq!(
+            result.err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::WrongOrientation { shape: 0, contour: 0 }))
+        );
+
+        let line = path(&[[0, 0], [10, 0]]);
+        let result = triangulator.try_unchecked_triangulate_shapes(&vec![vec![path(&[[0, 0], [10, 0], [0, 10]])], vec![line]]);
+        assert_eq!(
+            result.err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::DegenerateContour { shape: 1, contour: 0 }))
+        );
+
+        let result = triangulator.try_unchecked_triangulate_shapes_with_steiner_points(&vec![vec![cw]], &[]);
+        assert_eq!(
+            result.err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::GroupsMismatch { shapes: 1, groups: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_3() {
+        let triangulator = Triangulator::default();
+
+        // bow-tie, its signed area is zero
+        let bow_tie = path(&[[0, 0], [10, 10], [10, 0], [0, 10]]);
+        assert_eq!(
+            triangulator.try_unchecked_triangulate_contour(&bow_tie).err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::DegenerateContour { shape: 0, contour: 0 }))
+        );
+
+        // self-intersecting but with a correct orientation
+        let contour = path(&[[0, 0], [20, 0], [20, 10], [5, 10], [5, -5], [0, -5]]);
+        assert_eq!(
+            triangulator.try_unchecked_triangulate_contour(&contour).err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::SelfIntersection { shape: 0 }))
+        );
+
+        // a hole touching the outer contour by an edge
+        let contour = path(&[[0, 0], [20, 0], [20, 10], [0, 10]]);
+        let hole = path(&[[5, 5], [5, 10], [8, 10], [8, 5]]);
+        assert_eq!(
+            triangulator.try_unchecked_triangulate_shape(&vec![contour.clone(), hole]).err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::SelfIntersection { shape: 0 }))
+        );
+
+        let outside = path(&[[25, 5], [25, 8], [28, 8], [28, 5]]);
+        assert_eq!(
+            triangulator.try_unchecked_triangulate_shape(&vec![contour.clone(), outside]).err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::MisplacedHole { shape: 0, contour: 1 }))
+        );
+
+        // holes touching at a point are valid
+        let hole_0 = path(&[[5, 5], [5, 8], [8, 8], [8, 5]]);
+        let hole_1 = path(&[[8, 8], [8, 9], [11, 9], [11, 8]]);
+        let raw = triangulator
+            .try_unchecked_triangulate_shape(&vec![contour, hole_0, hole_1])
+            .unwrap();
+        assert_eq!(raw.points.len(), 11);
+    }
+
+    #[test]
+    fn test_4() {
+        let contour: IntPath = (0..300)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::TAU / 300.0;
+                IntPoint::new((1000.0 * a.cos()) as i32, (1000.0 * a.sin()) as i32)
+            })
+            .collect();
+
+        let raw = Triangulator::default().try_triangulate_contour(&contour).unwrap();
+        assert_eq!(
+            raw.try_triangle_indices::<u8>().err(),
+            Some(TriangulationError::IndexOverflow { index_type: "u8", points_count: 300 })
+        );
+        assert!(raw.try_into_triangulation::<u16>().is_ok());
+    }
+
+    #[test]
+    fn test_5() {
+        // overlapping shapes keep their own triangles and ids
+        let shapes = vec![
+            vec![path(&[[0, 0], [100, 0], [100, 100], [0, 100]])],
+            vec![path(&[[50, 50], [150, 50], [150, 150], [50, 150]]), path(&[[90, 90], [90, 110], [110, 110], [110, 90]])],
+            vec![path(&[[0, 0], [10, 0], [20, 0]])],
+        ];
+
+        let triangulator = Triangulator::default();
+        assert!(triangulator.triangulate_shapes(&shapes).shape_ids().is_none());
+
+        let raw = triangulator.triangulate_shapes_with_shape_ids(&shapes);
+        assert_eq!(raw.shape_ids().unwrap().len(), raw.triangles.len());
+
+        let refined = raw.into_delaunay().refine_with_circumcenters(16);
+        let ids = refined.shape_ids().unwrap();
+        assert_eq!(ids.len(), refined.triangles.len());
+
+        let mut areas = [0; 3];
+        for (t, &id) in refined.triangles.iter().zip(ids.iter()) {
+            areas[id] += Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point);
+        }
+        assert_eq!(areas[0], shapes[0].area_two());
+        assert_eq!(areas[1], shapes[1].area_two());
+        assert_eq!(areas[2], 0);
+
+        let triangulation = refined.into_triangulation::<u32>();
+        assert_eq!(3 * triangulation.shape_ids().unwrap().len(), triangulation.indices.len());
+    }
+
+    #[test]
+    fn test_6() {
+        let triangulator = Triangulator::default();
+        let contour = path(&[[0, 0], [10, 0], [20, 5], [10, 10], [0, 10]]);
+
+        let indices = triangulator.unchecked_triangulate_contour_indices::<u16>(&contour);
+        let raw = triangulator.unchecked_triangulate_contour(&contour);
+        let expected: Vec<IntPoint> = raw.triangle_indices::<usize>().iter().map(|&i| raw.points[i]).collect();
+        let actual: Vec<IntPoint> = indices.iter().map(|&i| contour[i as usize]).collect();
+        assert_eq!(actual, expected);
+
+        // the hole touches the outer contour, the shared point keeps the outer index
+        let shape = vec![
+            path(&[[0, 0], [10, 0], [10, 10], [0, 10]]),
+            path(&[[0, 0], [5, 8], [8, 5]]),
+        ];
+        let indices = triangulator.unchecked_triangulate_shape_indices::<u32>(&shape);
+        let flat: Vec<IntPoint> = shape.iter().flatten().copied().collect();
+        assert_eq!(indices.len(), 3 * 5);
+        assert!(!indices.contains(&4));
+        let area = indices.chunks(3).fold(0, |s, t| {
+            s + Triangle::area_two_point(flat[t[0] as usize], flat[t[1] as usize], flat[t[2] as usize])
+        });
+        assert_eq!(area, shape.area_two());
+
+        let circle: IntPath = (0..300)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::TAU / 300.0;
+                IntPoint::new((1000.0 * a.cos()) as i32, (1000.0 * a.sin()) as i32)
+            })
+            .collect();
+        assert_eq!(
+            triangulator.try_unchecked_triangulate_contour_indices::<u8>(&circle).err(),
+            Some(TriangulationError::IndexOverflow { index_type: "u8", points_count: 300 })
+        );
+
+        // a mesh point missing from the input has no index
+        assert_eq!(
+            raw.try_input_indices::<u16>(contour[1..].iter().copied()).err(),
+            Some(TriangulationError::InvalidInput(InvalidInput::UnknownPoint { x: 0, y: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_random_0() {
+        let triangulator = Triangulator::default();
+        let mut rng = rand::rng();
+        for _ in 0..20_000 {
+            let shape: IntShape = (0..rng.random_range(1..3))
+                .map(|_| {
+                    (0..rng.random_range(3..7))
+                        .map(|_| IntPoint::new(rng.random_range(-8..=8), rng.random_range(-8..=8)))
+                        .collect()
+                })
+                .collect();
+
+            // must never abort, a successful result must cover the shape exactly
+            if let Ok(raw) = triangulator.try_unchecked_triangulate_shape(&shape) {
+                let area = raw.triangles.iter().fold(0, |s, t| {
+                    s + Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point)
+                });
+                assert_eq!(area, shape.area_two());
+            }
+        }
+    }
+}
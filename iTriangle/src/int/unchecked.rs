@@ -1,7 +1,7 @@
 use i_overlay::i_float::int::point::IntPoint;
 use i_overlay::i_shape::int::shape::{IntContour, IntShape, IntShapes};
 use crate::int::binder::SteinerInference;
-use crate::int::triangulation::RawIntTriangulation;
+use crate::int::triangulation::{IndexType, RawIntTriangulation};
 use crate::int::triangulator::Triangulator;
 
 /// A trait for performing triangulation on already validated geometry.
@@ -27,6 +27,15 @@ pub trait IntUncheckedTriangulatable {
     ///
     /// Points are grouped and applied based on their target shape.
     fn unchecked_triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation;
+
+    /// Performs triangulation without validation and returns indices into the input points,
+    /// with contours (and shapes) flattened in order.
+    ///
+    /// Mesh points are mapped back to the input points by position, see [`Triangulator::unchecked_triangulate_shape_indices`].
+    ///
+    /// # Panics
+    /// If the index type `I` cannot address all input points or the mesh has a point which is not an input point.
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I>;
 }
 
 impl IntUncheckedTriangulatable for IntContour {
@@ -39,6 +48,11 @@ impl IntUncheckedTriangulatable for IntContour {
     fn unchecked_triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation {
         Triangulator::default().unchecked_triangulate_contour_with_steiner_points(self, points)
     }
+
+    #[inline]
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        Triangulator::default().unchecked_triangulate_contour_indices(self)
+    }
 }
 
 impl IntUncheckedTriangulatable for IntShape {
@@ -51,6 +65,11 @@ impl IntUncheckedTriangulatable for IntShape {
     fn unchecked_triangulate_with_steiner_points(&self, points: &[IntPoint]) -> RawIntTriangulation {
         Triangulator::default().unchecked_triangulate_shape_with_steiner_points(self, points)
     }
+
+    #[inline]
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        Triangulator::default().unchecked_triangulate_shape_indices(self)
+    }
 }
 
 impl IntUncheckedTriangulatable for IntShapes {
@@ -64,4 +83,12 @@ impl IntUncheckedTriangulatable for IntShapes {
         let group = self.group_by_shapes(points);
         Triangulator::default().unchecked_triangulate_shapes_with_steiner_points(self, &group)
     }
+
+    #[inline]
+    fn unchecked_triangulate_indices<I: IndexType>(&self) -> Vec<I> {
+        Triangulator::default()
+            .unchecked_triangulate_shapes(self)
+            .try_input_indices(self.iter().flatten().flatten().copied())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 }
\ No newline at end of file
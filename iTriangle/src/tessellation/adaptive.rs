@@ -0,0 +1,343 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::triangle::IntTriangle;
+use crate::tessellation::quality::{abc_by_edge, grid_point_on_segment};
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use std::collections::HashSet;
+
+/// How [`IntDelaunay::refine_triangles`] splits a triangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefinementStrategy {
+    /// Splits the longest edge by its middle, rounded to a grid point of the edge.
+    /// A neighbor whose longest edge is another one is bisected first, so angles do not degrade.
+    LongestEdge,
+    /// Splits the edge opposite the newest vertex (the one with the biggest point index) by its middle,
+    /// rounded to a grid point of the edge.
+    /// A neighbor with another such edge is bisected first.
+    NewestVertex,
+    /// Inserts the circumcenter like [`IntDelaunay::refine_with_circumcenters`]
+    /// and flips edges to keep the mesh Delaunay.
+    Circumcenter,
+}
+
+impl IntDelaunay {
+    /// Splits the given triangles, e.g. the ones with a big error after a solve.
+    ///
+    /// An edge is always split together with the triangle on its other side, so the mesh stays
+    /// conforming without hanging points.
+    /// Bisections do not flip edges and keep every child inside its parent, so the mesh is not
+    /// Delaunay in general. [`RefinementStrategy::Circumcenter`] keeps it Delaunay.
+    /// Bisections split an edge only at a grid point on it, a triangle whose edge has no such point
+    /// (e.g. a small one with odd coordinates) is left as it is.
+    /// A triangle already changed by an earlier split of the same call is not split again.
+    ///
+    /// Returns the children of every triangle of the mesh before the call: the new triangles
+    /// overlapping it, in increasing order. A triangle that was not changed keeps its own index.
+    pub fn refine_triangles(&mut self, triangles: &[usize], strategy: RefinementStrategy) -> Vec<Vec<usize>> {
+        let old: Vec<[IntPoint; 3]> = self.triangles.iter().map(|t| t.vertices.map(|v| v.point)).collect();
+        let mut changed = vec![false; old.len()];
+
+        let mut unchecked = HashSet::with_capacity(16);
+        let mut buffer = Vec::with_capacity(16);
+        let mut touched = Vec::with_capacity(16);
+        let mut stack = Vec::with_capacity(16);
+
+        for &abc_index in triangles {
+            if abc_index >= old.len() || changed[abc_index] {
+                continue;
+            }
+
+            if strategy == RefinementStrategy::Circumcenter {
+                let t = &self.triangles[abc_index];
+                let abc = abc_by_edge(t, longest_edge(t));
+                if self.split_triangle(abc_index, abc, &mut buffer) {
+                    self.fix_triangles_and_collect(&mut buffer, &mut unchecked, &mut touched);
+                }
+            } else {
+                self.bisect(abc_index, strategy, &mut stack, &mut touched);
+            }
+
+            changed.resize(self.triangles.len(), false);
+            for t in touched.drain(..) {
+                changed[t] = true;
+            }
+        }
+
+        self.children_map(&old, &changed)
+    }
+
+    /// Bisects `abc_index`, first bisecting the neighbors whose refinement edge is another one.
+    fn bisect(
+        &mut self,
+        abc_index: usize,
+        strategy: RefinementStrategy,
+        stack: &mut Vec<usize>,
+        touched: &mut Vec<usize>,
+    ) {
+        stack.clear();
+        stack.push(abc_index);
+
+        while let Some(&t) = stack.last() {
+            let k = self.refinement_edge(t, strategy);
+            let n = self.triangles[t].neighbors[k];
+            if n < self.triangles.len() {
+                let nk = self.refinement_edge(n, strategy);
+                // a cycle of equal edges is broken by splitting the edge anyway
+                if self.triangles[n].neighbors[nk] != t && !stack.contains(&n) {
+                    stack.push(n);
+                    continue;
+                }
+            }
+
+            if !self.bisect_edge(t, k, touched) {
+                return;
+            }
+            stack.pop();
+        }
+    }
+
+    #[inline]
+    fn refinement_edge(&self, abc_index: usize, strategy: RefinementStrategy) -> usize {
+        let t = &self.triangles[abc_index];
+        match strategy {
+            RefinementStrategy::NewestVertex => {
+                let mut k = 0;
+                for i in 1..3 {
+                    if t.vertices[i].index > t.vertices[k].index {
+                        k = i;
+                    }
+                }
+                k
+            }
+            _ => longest_edge(t),
+        }
+    }
+
+    /// Splits the edge `k` of `abc_index` by the grid point of the edge nearest its middle.
+    /// Returns false if the edge has no grid point inside, such an edge is never split.
+    fn bisect_edge(&mut self, abc_index: usize, k: usize, touched: &mut Vec<usize>) -> bool {
+        let t = &self.triangles[abc_index];
+        let b = t.vertices[(k + 1) % 3].point;
+        let c = t.vertices[(k + 2) % 3].point;
+        let m = if let Some(m) = grid_point_on_segment(b, c, 0.5) {
+            m
+        } else {
+            return false;
+        };
+
+        let abc = abc_by_edge(t, k);
+        if !self.is_valid_split(&abc, m) {
+            return false;
+        }
+        self.split_edge(abc_index, abc, m, touched);
+        true
+    }
+
+    fn children_map(&self, old: &[[IntPoint; 3]], changed: &[bool]) -> Vec<Vec<usize>> {
+        let mut children: Vec<Vec<usize>> = (0..old.len())
+            .map(|i| if changed[i] { Vec::new() } else { vec![i] })
+            .collect();
+
+        // changed parents sorted by their left side
+        let mut parents: Vec<usize> = (0..old.len()).filter(|&i| changed[i]).collect();
+        parents.sort_unstable_by_key(|&i| x_range(&old[i]).0);
+        let max_width = parents
+            .iter()
+            .map(|&i| {
+                let (min_x, max_x) = x_range(&old[i]);
+                max_x - min_x
+            })
+            .max()
+            .unwrap_or(0);
+
+        for child in (0..self.triangles.len()).filter(|&i| changed[i]) {
+            let points = self.triangles[child].vertices.map(|v| v.point);
+            let (min_x, max_x) = x_range(&points);
+            let start = parents.partition_point(|&i| x_range(&old[i]).0 < min_x - max_width);
+            for &parent in parents[start..].iter().take_while(|&&i| x_range(&old[i]).0 <= max_x) {
+                if is_overlap(&old[parent], &points) {
+                    children[parent].push(child);
+                }
+            }
+        }
+
+        children
+    }
+}
+
+#[inline]
+fn longest_edge(t: &IntTriangle) -> usize {
+    let [a, b, c] = t.vertices.map(|v| v.point);
+    let sqr = [b.sqr_distance(c), c.sqr_distance(a), a.sqr_distance(b)];
+    let mut k = 0;
+    for i in 1..3 {
+        if sqr[i] > sqr[k] {
+            k = i;
+        }
+    }
+    k
+}
+
+#[inline]
+fn x_range(points: &[IntPoint; 3]) -> (i64, i64) {
+    let min_x = points[0].x.min(points[1].x).min(points[2].x);
+    let max_x = points[0].x.max(points[1].x).max(points[2].x);
+    (min_x as i64, max_x as i64)
+}
+
+/// True if the interiors of two valid triangles overlap, no edge of them separates the other one.
+fn is_overlap(abc: &[IntPoint; 3], def: &[IntPoint; 3]) -> bool {
+    !is_separated_by(abc, def) && !is_separated_by(def, abc)
+}
+
+#[inline]
+fn is_separated_by(abc: &[IntPoint; 3], def: &[IntPoint; 3]) -> bool {
+    (0..3).any(|i| {
+        let p = abc[i];
+        let q = abc[(i + 1) % 3];
+        // the inside of a valid triangle is on the negative side of its edges
+        def.iter().all(|&x| Triangle::area_two_point(p, q, x) >= 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::tessellation::adaptive::RefinementStrategy;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn areas(delaunay: &IntDelaunay) -> Vec<i64> {
+        delaunay
+            .triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                Triangle::area_two_point(a, b, c)
+            })
+            .collect()
+    }
+
+    // children cover their parents exactly and every triangle has one parent
+    fn check_nested(before: &[i64], after: &IntDelaunay, children: &[Vec<usize>]) {
+        let after_areas = areas(after);
+        assert_eq!(children.len(), before.len());
+        for (parent, list) in children.iter().enumerate() {
+            let sum: i64 = list.iter().map(|&c| after_areas[c]).sum();
+            assert_eq!(sum, before[parent]);
+        }
+        let mut all: Vec<usize> = children.iter().flatten().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..after.triangles.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [1024, 0], [1024, 1024], [0, 1024]]);
+        let mut delaunay = contour.triangulate().into_delaunay();
+        let before = areas(&delaunay);
+        let children = delaunay.refine_triangles(&[0], RefinementStrategy::LongestEdge);
+        delaunay.validate();
+
+        // the diagonal is split on both sides
+        assert_eq!(delaunay.triangles.len(), 4);
+        assert_eq!(children, vec![vec![0, 2], vec![1, 3]]);
+        check_nested(&before, &delaunay, &children);
+    }
+
+    #[test]
+    fn test_1() {
+        // a marked triangle next to a bigger one bisects it first
+        let contour = path(&[[0, 0], [1024, 0], [1024, 1024], [0, 1024]]);
+        for strategy in [RefinementStrategy::LongestEdge, RefinementStrategy::NewestVertex] {
+            let mut delaunay = contour.triangulate().into_delaunay();
+            delaunay.refine_triangles(&[0], strategy);
+            delaunay.refine_triangles(&[0], strategy);
+
+            let before = areas(&delaunay);
+            let children = delaunay.refine_triangles(&[0], strategy);
+            delaunay.validate();
+            assert!(delaunay.triangles.len() > before.len());
+            check_nested(&before, &delaunay, &children);
+            assert_eq!(delaunay.area(), contour.area_two());
+        }
+    }
+
+    #[test]
+    fn test_2() {
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let mut delaunay = contour.triangulate().into_delaunay();
+        delaunay.refine_with_circumcenters_mut(20_000);
+        let count = delaunay.triangles.len();
+        let marked: Vec<usize> = (0..count).step_by(3).collect();
+        let children = delaunay.refine_triangles(&marked, RefinementStrategy::Circumcenter);
+        delaunay.validate();
+        assert!(delaunay.triangles.len() > count);
+        assert_eq!(delaunay.area(), contour.area_two());
+
+        // every new triangle has a parent, every parent has a child
+        assert_eq!(children.len(), count);
+        assert!(children.iter().all(|list| !list.is_empty()));
+        for i in 0..delaunay.triangles.len() {
+            assert!(children.iter().any(|list| list.contains(&i)));
+        }
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let contour = path(&[[0, 0], [4096, 0], [4096, 4096], [0, 4096]]);
+            let mut delaunay = contour.triangulate().into_delaunay();
+            let strategy = if rng.random_bool(0.5) {
+                RefinementStrategy::LongestEdge
+            } else {
+                RefinementStrategy::NewestVertex
+            };
+
+            // a few rounds of mark and refine
+            for _ in 0..6 {
+                let n = delaunay.triangles.len();
+                let marked: Vec<usize> = (0..rng.random_range(1..=n)).map(|_| rng.random_range(0..n)).collect();
+                let before = areas(&delaunay);
+                let children = delaunay.refine_triangles(&marked, strategy);
+                delaunay.validate();
+                check_nested(&before, &delaunay, &children);
+            }
+            assert_eq!(delaunay.area(), contour.area_two());
+        }
+    }
+
+    #[test]
+    fn test_random_1() {
+        // slanted edges with odd coordinates run out of grid points
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let contour = path(&[[0, 0], [101, 7], [97, 103], [3, 89]]);
+            let mut delaunay = contour.triangulate().into_delaunay();
+            let strategy = if rng.random_bool(0.5) {
+                RefinementStrategy::LongestEdge
+            } else {
+                RefinementStrategy::NewestVertex
+            };
+
+            for _ in 0..12 {
+                let n = delaunay.triangles.len();
+                let marked: Vec<usize> = (0..rng.random_range(1..=n)).map(|_| rng.random_range(0..n)).collect();
+                let before = areas(&delaunay);
+                let children = delaunay.refine_triangles(&marked, strategy);
+                delaunay.validate();
+                check_nested(&before, &delaunay, &children);
+            }
+            assert_eq!(delaunay.area(), contour.area_two());
+        }
+    }
+}
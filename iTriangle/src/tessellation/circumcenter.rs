@@ -2,11 +2,115 @@ use std::collections::HashSet;
 use crate::advanced::delaunay::IntDelaunay;
 use crate::geom::point::IndexPoint;
 use crate::geom::triangle::{Abc, IntTriangle};
+use crate::tessellation::pass::Passes;
 use i_overlay::i_float::int::point::IntPoint;
 use i_overlay::i_float::triangle::Triangle;
 
+/// Controls [`IntDelaunay::refine_with_options`].
+///
+/// Areas and lengths are in the units of the mesh: `u64` int units for [`IntDelaunay`],
+/// float units for the float [`Delaunay`](crate::float::delaunay::Delaunay).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefinementOptions<T = u64> {
+    /// The maximum number of passes over the mesh.
+    pub max_iterations: usize,
+    /// Refinement stops after a pass that split fewer than this part of all triangles.
+    pub min_split_ratio: f64,
+    /// Refinement stops once the mesh has this many triangles, the last split may add one more.
+    pub target_triangle_count: Option<usize>,
+    /// Refinement stops once the mesh has this many points.
+    pub max_point_count: Option<usize>,
+    /// Triangles with a bigger area are refined.
+    pub min_area: T,
+    /// Triangles with a longer edge are refined, whatever their area.
+    pub max_edge_length: Option<T>,
+}
+
+impl<T: Default> Default for RefinementOptions<T> {
+    /// 8 passes, stopping early after a pass which split less than 25% of triangles.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_iterations: 8,
+            min_split_ratio: 0.25,
+            target_triangle_count: None,
+            max_point_count: None,
+            min_area: T::default(),
+            max_edge_length: None,
+        }
+    }
+}
+
+/// [`RefinementOptions`] prepared for the int checks.
+pub(crate) struct Limits {
+    pub(crate) two_area: u64,
+    pub(crate) sqr_edge: u64,
+    pub(crate) max_triangles: usize,
+    pub(crate) max_points: usize,
+}
+
+impl RefinementOptions {
+    #[inline]
+    pub(crate) fn limits(&self) -> Limits {
+        Limits {
+            two_area: self.min_area << 1,
+            sqr_edge: self.max_edge_length.map_or(u64::MAX, |l| l.saturating_mul(l)),
+            max_triangles: self.target_triangle_count.unwrap_or(usize::MAX),
+            max_points: self.max_point_count.unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// Chooses the edge to split in a triangle picked for refinement.
+///
+/// `triangle` is the index of the triangle in the mesh, triangles created by refinement
+/// are pushed after the existing ones. Edge `i` is the edge opposite the point `i`.
+/// Triangles made by a split are checked in the same pass, so a selector must pick edges that get
+/// shorter (e.g. the longest one) or the pass may never end.
+/// Implemented for any `Fn(usize, [IntPoint; 3]) -> Option<usize>`.
+pub trait EdgeSelector: Sync {
+    /// Returns the edge to split, `None` keeps the triangle as it is.
+    fn select(&self, triangle: usize, points: [IntPoint; 3]) -> Option<usize>;
+
+    /// True if [`EdgeSelector::select`] or [`EdgeSelector::priority`] read `triangle`, true by default.
+    ///
+    /// With the `rayon` feature, independent parts of the mesh are refined with their own indices,
+    /// so a mesh is refined by parts only for a selector returning false here.
+    #[inline]
+    fn uses_index(&self) -> bool {
+        true
+    }
+
+    /// The order of [`IntDelaunay::refine_worst_first`], a triangle with a bigger priority is split first.
+    /// The doubled area by default.
+    #[inline]
+    fn priority(&self, _triangle: usize, points: [IntPoint; 3]) -> f64 {
+        let [a, b, c] = points;
+        Triangle::area_two_point(a, b, c).unsigned_abs() as f64
+    }
+}
+
+impl<F: Fn(usize, [IntPoint; 3]) -> Option<usize> + Sync> EdgeSelector for F {
+    #[inline]
+    fn select(&self, triangle: usize, points: [IntPoint; 3]) -> Option<usize> {
+        self(triangle, points)
+    }
+}
+
+/// Selects the longest edge, used by [`IntDelaunay::refine_with_circumcenters`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelectBiggerAngle;
+
+/// Selects the edge opposite an obtuse angle, used by [`IntDelaunay::refine_with_circumcenters_by_obtuse_angle`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelectObtuseAngle;
+
 impl IntDelaunay {
 
+    /// Splits triangles bigger than `min_area` by inserting their circumcenters.
+    ///
+    /// With the `rayon` feature, independent parts of the mesh (e.g. separate shapes)
+    /// are refined in parallel, the result is the same as without the feature.
     #[inline]
     pub fn refine_with_circumcenters(mut self, min_area: u64) -> Self {
         self.refine_with_circumcenters_mut(min_area);
@@ -15,7 +119,11 @@ impl IntDelaunay {
 
     #[inline]
     pub fn refine_with_circumcenters_mut(&mut self, min_area: u64) {
-        self.refine_with_circumcenters_and_selector::<SelectBiggerAngle>(min_area);
+        let options = RefinementOptions {
+            min_area,
+            ..Default::default()
+        };
+        self.refine_with_options_mut(&options, SelectBiggerAngle);
     }
 
     #[inline]
@@ -26,72 +134,136 @@ impl IntDelaunay {
 
     #[inline]
     pub fn refine_with_circumcenters_by_obtuse_angle_mut(&mut self, min_area: u64) {
-        self.refine_with_circumcenters_and_selector::<SelectObtuseAngle>(min_area)
-    }
-    
-    fn refine_with_circumcenters_and_selector<S: EdgeSelector>(&mut self, min_area: u64) {
-        let two_area = min_area << 1;
-        let mut unchecked = HashSet::with_capacity(self.triangles.len());
-        let mut buffer = Vec::with_capacity(16);
-
-        let mut iter_counter = 0;
-        let mut split_counter = self.triangles.len();
-
-        // do 8 times or leave if last time we found only 25%
-        while iter_counter < 8 && 4 * split_counter > self.triangles.len() {
-            let mut abc_index = 0;
-            split_counter = 0;
-            while abc_index < self.triangles.len() {
-                let abc = &self.triangles[abc_index];
-                if let Some(t) = self.select_edge_for_refinement::<S>(two_area, abc) {
-                    self.split_triangle(abc_index, t, &mut buffer);
-                    self.fix_triangles(&mut buffer, &mut unchecked);
-                    debug_assert!(buffer.is_empty());
-                    split_counter += 1;
-                }
-                abc_index += 1;
-            }
+        let options = RefinementOptions {
+            min_area,
+            ..Default::default()
+        };
+        self.refine_with_options_mut(&options, SelectObtuseAngle);
+    }
 
-            iter_counter += 1;
-        }
+    /// Splits the edges chosen by `selector` in triangles bigger than `options.min_area`
+    /// (or with an edge longer than `options.max_edge_length`) by inserting their circumcenters.
+    ///
+    /// With the `rayon` feature, independent parts of the mesh are refined in parallel
+    /// unless the options limit the triangle or point count of the whole mesh
+    /// or the selector reads triangle indices (see [`EdgeSelector::uses_index`]).
+    /// The passes stay in step over all parts, so the result is the same as without the feature.
+    #[inline]
+    pub fn refine_with_options<S: EdgeSelector>(mut self, options: &RefinementOptions, selector: S) -> Self {
+        self.refine_with_options_mut(options, selector);
+        self
+    }
+
+    pub fn refine_with_options_mut<S: EdgeSelector>(&mut self, options: &RefinementOptions, selector: S) {
+        self.run_passes(&CircumcenterPasses {
+            options,
+            limits: options.limits(),
+            selector: &selector,
+        });
     }
 
     #[inline]
-    fn select_edge_for_refinement<S: EdgeSelector>(&self, min_area: u64, abc: &IntTriangle) -> Option<Abc> {
+    pub(crate) fn select_edge_for_refinement<S: EdgeSelector>(
+        two_area: u64,
+        sqr_edge: u64,
+        selector: &S,
+        abc_index: usize,
+        abc: &IntTriangle,
+    ) -> Option<Abc> {
         let a = abc.vertices[0].point;
         let b = abc.vertices[1].point;
         let c = abc.vertices[2].point;
 
         let area = Triangle::area_two_point(a, b, c).unsigned_abs();
-        if area <= min_area {
-            return None;
+        if area <= two_area {
+            let max = a.sqr_distance(b).max(b.sqr_distance(c)).max(c.sqr_distance(a)) as u64;
+            if max <= sqr_edge {
+                return None;
+            }
+        }
+
+        match selector.select(abc_index, [a, b, c])? {
+            0 => Some(abc.abc_by_a()),
+            1 => Some(abc.abc_by_b()),
+            _ => Some(abc.abc_by_c()),
+        }
+    }
+
+    /// Splits the edge `bc` of `abc` by the circumcenter if it falls inside the neighbor, by the middle otherwise.
+    ///
+    /// Returns false if the rounded middle of a short edge would make an invalid triangle.
+    #[inline]
+    pub(crate) fn split_triangle(&mut self, abc_index: usize, abc: Abc, buffer: &mut Vec<usize>) -> bool {
+        let pcb_index = abc.v0.neighbor;
+        let m = if pcb_index < self.triangles.len() {
+            let p = abc.circumscribed_center();
+            let pcb = self.triangles[pcb_index].abc_by_neighbor(abc_index);
+
+            // a constrained edge can only be split by its middle
+            let is_constrained = self.constraints.contains(abc.v1.vertex.index, abc.v2.vertex.index);
+            if !is_constrained && pcb.is_contain(p) {
+                p
+            } else {
+                abc.edge_mid_point()
+            }
+        } else {
+            abc.edge_mid_point()
+        };
+
+        if !self.is_valid_split(&abc, m) {
+            return false;
         }
 
-        S::select(abc)
+        self.split_edge(abc_index, abc, m, buffer);
+        true
     }
 
+    /// True if all triangles made by splitting the edge `bc` of `abc` by `m` keep their winding.
     #[inline]
-    fn split_triangle(&mut self, abc_index: usize, abc: Abc, buffer: &mut Vec<usize>) {
+    pub(crate) fn is_valid_split(&self, abc: &Abc, m: IntPoint) -> bool {
+        let a = abc.v0.vertex.point;
+        let b = abc.v1.vertex.point;
+        let c = abc.v2.vertex.point;
+        if Triangle::area_two_point(a, b, m) >= 0 || Triangle::area_two_point(a, m, c) >= 0 {
+            return false;
+        }
+
+        let pcb_index = abc.v0.neighbor;
+        if pcb_index >= self.triangles.len() {
+            return true;
+        }
+        let pcb = &self.triangles[pcb_index];
+        let p = pcb.vertices[pcb.other_vertex(abc.v1.vertex.index, abc.v2.vertex.index)].point;
+        Triangle::area_two_point(p, c, m) < 0 && Triangle::area_two_point(p, m, b) < 0
+    }
+
+    /// Splits the edge `bc` of `abc` (and the triangle on the other side) by the point `m`.
+    ///
+    /// `m` must lie inside the union of both triangles, on the edge itself if it has no neighbor.
+    /// The indices of all new and changed triangles are appended to `buffer`.
+    #[inline]
+    pub(crate) fn split_edge(&mut self, abc_index: usize, abc: Abc, m: IntPoint, buffer: &mut Vec<usize>) {
         let pcb_index = abc.v0.neighbor;
         if pcb_index < self.triangles.len() {
-            buffer.extend_from_slice(&self.split_triangle_with_neighbor(abc_index, abc, pcb_index));
+            buffer.extend_from_slice(&self.split_triangle_with_neighbor(abc_index, abc, pcb_index, m));
         } else {
-            buffer.extend_from_slice(&self.split_alone_triangle(abc_index, abc));
+            buffer.extend_from_slice(&self.split_alone_triangle(abc_index, abc, m));
         }
     }
 
-    fn split_triangle_with_neighbor(&mut self, abc_index: usize, abc: Abc, pcb_index: usize) -> [usize; 4] {
-        let p = abc.circumscribed_center();
+    fn split_triangle_with_neighbor(&mut self, abc_index: usize, abc: Abc, pcb_index: usize, m: IntPoint) -> [usize; 4] {
         let pcb = &self.triangles[pcb_index].abc_by_neighbor(abc_index);
 
-        let m = if pcb.is_contain(p) {
-            p
-        } else {
-            abc.edge_mid_point()
-        };
+        let b_index = abc.v1.vertex.index;
+        let c_index = abc.v2.vertex.index;
+        let is_constrained = self.constraints.contains(b_index, c_index);
 
         let m_index = self.points.len();
         self.points.push(m);
+        self.add_refinement_source();
+        if is_constrained {
+            self.constraints.split(b_index, c_index, m_index);
+        }
         let vm = IndexPoint {
             index: m_index,
             point: m,
@@ -135,15 +307,18 @@ impl IntDelaunay {
         self.triangles[pcm_index] = pcm;
         self.triangles.push(amc);
         self.triangles.push(pmb);
+        self.inherit_shape_id(abm_index);
+        self.inherit_shape_id(pcm_index);
 
 
         [abm_index, pcm_index, amc_index, pmb_index]
     }
 
-    fn split_alone_triangle(&mut self, abc_index: usize, abc: Abc) -> [usize; 2] {
-        let m = abc.edge_mid_point();
+    fn split_alone_triangle(&mut self, abc_index: usize, abc: Abc, m: IntPoint) -> [usize; 2] {
         let m_index = self.points.len();
         self.points.push(m);
+        self.add_refinement_source();
+        self.constraints.split(abc.v1.vertex.index, abc.v2.vertex.index, m_index);
         let vm = IndexPoint {
             index: m_index,
             point: m,
@@ -167,6 +342,7 @@ impl IntDelaunay {
 
         self.triangles[abm_index] = abm;
         self.triangles.push(amc);
+        self.inherit_shape_id(abm_index);
 
         [abm_index, amc_index]
     }
@@ -175,29 +351,7 @@ impl IntDelaunay {
 impl Abc {
     #[inline]
     fn circumscribed_center(&self) -> IntPoint {
-        let a = self.v0.vertex.point;
-        let b = self.v1.vertex.point;
-        let c = self.v2.vertex.point;
-        let ax = a.x as f64;
-        let ay = a.y as f64;
-        let bx = b.x as f64;
-        let by = b.y as f64;
-        let cx = c.x as f64;
-        let cy = c.y as f64;
-
-        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
-        let aa = ax * ax + ay * ay;
-        let bb = bx * bx + by * by;
-        let cc = cx * cx + cy * cy;
-        let id = 1.0 / d;
-
-        let fx = (aa * (by - cy) + bb * (cy - ay) + cc * (ay - by)) * id;
-        let fy = (aa * (cx - bx) + bb * (ax - cx) + cc * (bx - ax)) * id;
-
-        let x = fx.round() as i32;
-        let y = fy.round() as i32;
-
-        IntPoint::new(x, y)
+        circumscribed_center(self.v0.vertex.point, self.v1.vertex.point, self.v2.vertex.point)
     }
 
     #[inline]
@@ -221,54 +375,228 @@ impl Abc {
     }
 }
 
-trait EdgeSelector {
-    fn select(abc: &IntTriangle) -> Option<Abc>;
+/// The center of the circle passing through `a`, `b` and `c`, rounded to the nearest int point.
+#[inline]
+pub(crate) fn circumscribed_center(a: IntPoint, b: IntPoint, c: IntPoint) -> IntPoint {
+    let [fx, fy] = circumscribed_center_f64(a, b, c);
+
+    let x = fx.round() as i32;
+    let y = fy.round() as i32;
+
+    IntPoint::new(x, y)
+}
+
+/// The center of the circle passing through `a`, `b` and `c`, far away for a nearly flat triangle.
+#[inline]
+pub(crate) fn circumscribed_center_f64(a: IntPoint, b: IntPoint, c: IntPoint) -> [f64; 2] {
+    let ax = a.x as f64;
+    let ay = a.y as f64;
+    let bx = b.x as f64;
+    let by = b.y as f64;
+    let cx = c.x as f64;
+    let cy = c.y as f64;
+
+    let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    let aa = ax * ax + ay * ay;
+    let bb = bx * bx + by * by;
+    let cc = cx * cx + cy * cy;
+    let id = 1.0 / d;
+
+    let fx = (aa * (by - cy) + bb * (cy - ay) + cc * (ay - by)) * id;
+    let fy = (aa * (cx - bx) + bb * (ax - cx) + cc * (bx - ax)) * id;
+
+    [fx, fy]
 }
 
-struct SelectBiggerAngle {}
-struct SelectObtuseAngle {}
+/// The passes of [`IntDelaunay::refine_with_options`].
+pub(crate) struct CircumcenterPasses<'a, S> {
+    pub(crate) options: &'a RefinementOptions,
+    pub(crate) limits: Limits,
+    pub(crate) selector: &'a S,
+}
 
-impl EdgeSelector for SelectObtuseAngle {
+impl<S: EdgeSelector> Passes for CircumcenterPasses<'_, S> {
+    type State = (HashSet<usize>, Vec<usize>);
 
     #[inline]
-    fn select(abc: &IntTriangle) -> Option<Abc> {
-        let a = abc.vertices[0].point;
-        let b = abc.vertices[1].point;
-        let c = abc.vertices[2].point;
+    fn state(&self, delaunay: &IntDelaunay) -> Self::State {
+        (HashSet::with_capacity(delaunay.triangles.len()), Vec::with_capacity(16))
+    }
+
+    #[inline]
+    fn visit(&self, delaunay: &mut IntDelaunay, state: &mut Self::State, _sweep: usize, abc_index: usize) -> bool {
+        let (unchecked, buffer) = state;
+        let abc = &delaunay.triangles[abc_index];
+        let Limits { two_area, sqr_edge, .. } = self.limits;
+        if let Some(t) = IntDelaunay::select_edge_for_refinement(two_area, sqr_edge, self.selector, abc_index, abc) {
+            if delaunay.split_triangle(abc_index, t, buffer) {
+                delaunay.fix_triangles(buffer, unchecked);
+                debug_assert!(buffer.is_empty());
+                return true;
+            }
+        }
+        false
+    }
+
+    // leave if the last pass split too few triangles
+    #[inline]
+    fn is_next(&self, pass: usize, changes: usize, triangles: usize) -> bool {
+        pass < self.options.max_iterations && changes as f64 > self.options.min_split_ratio * triangles as f64
+    }
+
+    #[inline]
+    fn is_full(&self, delaunay: &IntDelaunay) -> bool {
+        delaunay.triangles.len() >= self.limits.max_triangles || delaunay.points.len() >= self.limits.max_points
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn is_parallel(&self) -> bool {
+        self.limits.max_triangles == usize::MAX
+            && self.limits.max_points == usize::MAX
+            && !self.selector.uses_index()
+    }
+}
+
+impl EdgeSelector for SelectObtuseAngle {
+    #[inline]
+    fn select(&self, _triangle: usize, points: [IntPoint; 3]) -> Option<usize> {
+        let [a, b, c] = points;
 
         let sqr_c = a.sqr_distance(b);
         let sqr_a = b.sqr_distance(c);
         let sqr_b = c.sqr_distance(a);
 
         if sqr_c > sqr_a + sqr_b {
-            Some(abc.abc_by_c())
+            Some(2)
         } else if sqr_b > sqr_a + sqr_c {
-            Some(abc.abc_by_b())
+            Some(1)
         } else if sqr_a > sqr_b + sqr_c {
-            Some(abc.abc_by_a())
+            Some(0)
         } else {
             None
         }
     }
+
+    #[inline]
+    fn uses_index(&self) -> bool {
+        false
+    }
 }
 
 impl EdgeSelector for SelectBiggerAngle {
     #[inline]
-    fn select(abc: &IntTriangle) -> Option<Abc> {
-        let a = abc.vertices[0].point;
-        let b = abc.vertices[1].point;
-        let c = abc.vertices[2].point;
+    fn select(&self, _triangle: usize, points: [IntPoint; 3]) -> Option<usize> {
+        let [a, b, c] = points;
 
         let sqr_c = a.sqr_distance(b);
         let sqr_a = b.sqr_distance(c);
         let sqr_b = c.sqr_distance(a);
 
         if sqr_c >= sqr_a && sqr_c >= sqr_b {
-            Some(abc.abc_by_c())
+            Some(2)
         } else if sqr_b >= sqr_a && sqr_b >= sqr_c {
-            Some(abc.abc_by_b())
+            Some(1)
         } else {
-            Some(abc.abc_by_a())
+            Some(0)
+        }
+    }
+
+    #[inline]
+    fn uses_index(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::tessellation::circumcenter::{EdgeSelector, RefinementOptions, SelectBiggerAngle};
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let options = RefinementOptions {
+            max_iterations: 100,
+            min_split_ratio: 0.0,
+            target_triangle_count: Some(50),
+            ..Default::default()
+        };
+        let refined = contour.triangulate().into_delaunay().refine_with_options(&options, SelectBiggerAngle);
+        refined.validate();
+        // the last split may add two triangles
+        assert!((50..=51).contains(&refined.triangles.len()));
+        assert_eq!(refined.area(), contour.area_two());
+
+        let options = RefinementOptions {
+            max_point_count: Some(20),
+            ..options
+        };
+        let refined = contour.triangulate().into_delaunay().refine_with_options(&options, SelectBiggerAngle);
+        assert_eq!(refined.points.len(), 20);
+    }
+
+    #[test]
+    fn test_1() {
+        // only the left half is refined
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let options = RefinementOptions {
+            max_iterations: 16,
+            max_edge_length: Some(100),
+            min_area: u64::MAX,
+            ..Default::default()
+        };
+        let left_half = |_: usize, points: [IntPoint; 3]| {
+            if points.iter().all(|p| p.x <= 500) {
+                SelectBiggerAngle.select(0, points)
+            } else {
+                None
+            }
+        };
+        let refined = contour.triangulate().into_delaunay().refine_with_options(&options, left_half);
+        refined.validate();
+        assert_eq!(refined.area(), contour.area_two());
+
+        for t in refined.triangles.iter() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            let max = a.sqr_distance(b).max(b.sqr_distance(c)).max(c.sqr_distance(a));
+            if a.x.max(b.x).max(c.x) <= 500 {
+                assert!(max <= 100 * 100);
+            }
         }
+        assert!(refined.points.iter().all(|p| p.x <= 500 || p.x == 1000));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(3..12);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000)))
+                .collect();
+
+            let count = rng.random_range(1..200);
+            let options = RefinementOptions {
+                max_iterations: 32,
+                min_split_ratio: 0.0,
+                target_triangle_count: Some(count),
+                ..Default::default()
+            };
+            let delaunay = contour.triangulate().into_delaunay();
+            let (triangles, area) = (delaunay.triangles.len(), delaunay.area());
+            let refined = delaunay.refine_with_options(&options, SelectBiggerAngle);
+            refined.validate();
+            assert!(refined.triangles.len() <= count.max(triangles) + 1);
+            assert!((refined.area() - area).abs() <= refined.points.len() as i64 * 2000);
+        }
+    }
+}
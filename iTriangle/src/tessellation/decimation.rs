@@ -0,0 +1,458 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::point::IndexPoint;
+use crate::index::NIL_INDEX;
+use crate::tessellation::quality::collect_vertex_fan;
+use i_overlay::i_float::triangle::Triangle;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Controls [`IntDelaunay::decimate`].
+///
+/// Lengths are in the units of the mesh: `u64` int units for [`IntDelaunay`],
+/// float units for the float [`Delaunay`](crate::float::delaunay::Delaunay).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DecimationOptions<T = u64> {
+    /// Stops when the mesh has no more triangles than this.
+    /// A collapse removes two triangles, so the mesh may end one triangle below.
+    pub target_triangle_count: Option<usize>,
+    /// The longest edge that may be collapsed, i.e. how far a removed point may move.
+    /// `None` means no limit.
+    pub max_error: Option<T>,
+}
+
+impl IntDelaunay {
+    /// Reduces the triangle count by collapsing the shortest edges into one of their ends.
+    ///
+    /// Only inner points are removed, the points of contours and constrained edges are never
+    /// moved or removed, so the mesh covers exactly the same area.
+    /// A collapse that would invert a triangle or join two unrelated edges is skipped.
+    /// The points left keep their positions, a flip pass makes the mesh Delaunay again at the end.
+    #[inline]
+    pub fn decimate(mut self, options: &DecimationOptions) -> Self {
+        self.decimate_mut(options);
+        self
+    }
+
+    pub fn decimate_mut(&mut self, options: &DecimationOptions) {
+        let max_sqr_edge = options.max_error.map_or(u64::MAX, |e| e.saturating_mul(e));
+        let target = options.target_triangle_count.unwrap_or(0);
+
+        let mut decimation = Decimation::new(self);
+        decimation.fill_queue(self);
+
+        let mut count = self.triangles.len();
+        while let Some(Reverse((sqr_edge, v, u))) = decimation.queue.pop() {
+            if count <= target || sqr_edge > max_sqr_edge {
+                break;
+            }
+            if decimation.removed_points[v] || decimation.removed_points[u] {
+                continue;
+            }
+            if decimation.collapse(self, v, u) {
+                count -= 2;
+                decimation.push_around(self, u);
+            }
+        }
+
+        if count < self.triangles.len() {
+            decimation.compact(self);
+            self.build();
+        }
+    }
+}
+
+struct Decimation {
+    fixed: Vec<bool>,
+    removed_points: Vec<bool>,
+    removed_triangles: Vec<bool>,
+    vertex_triangle: Vec<usize>,
+    queue: BinaryHeap<Reverse<(u64, usize, usize)>>,
+    fan: Vec<usize>,
+    ring: Vec<usize>,
+}
+
+impl Decimation {
+    fn new(delaunay: &IntDelaunay) -> Self {
+        let points_count = delaunay.points.len();
+        let mut fixed = vec![false; points_count];
+        let mut vertex_triangle = vec![NIL_INDEX; points_count];
+
+        for (i, t) in delaunay.triangles.iter().enumerate() {
+            for k in 0..3 {
+                vertex_triangle[t.vertices[k].index] = i;
+
+                let a = t.vertices[(k + 1) % 3].index;
+                let b = t.vertices[(k + 2) % 3].index;
+                if t.neighbors[k] >= delaunay.triangles.len() || delaunay.constraints.contains(a, b) {
+                    fixed[a] = true;
+                    fixed[b] = true;
+                }
+            }
+        }
+
+        // a point without triangles is left as it is
+        for (is_fixed, &t) in fixed.iter_mut().zip(vertex_triangle.iter()) {
+            *is_fixed |= t == NIL_INDEX;
+        }
+
+        Self {
+            fixed,
+            removed_points: vec![false; points_count],
+            removed_triangles: vec![false; delaunay.triangles.len()],
+            vertex_triangle,
+            queue: BinaryHeap::with_capacity(delaunay.triangles.len()),
+            fan: Vec::with_capacity(16),
+            ring: Vec::with_capacity(16),
+        }
+    }
+
+    fn fill_queue(&mut self, delaunay: &IntDelaunay) {
+        for (i, t) in delaunay.triangles.iter().enumerate() {
+            for k in 0..3 {
+                // every inner edge is taken once, from the triangle with the smaller index
+                let n = t.neighbors[k];
+                if n < delaunay.triangles.len() && n < i {
+                    continue;
+                }
+                let a = t.vertices[(k + 1) % 3].index;
+                let b = t.vertices[(k + 2) % 3].index;
+                self.push_edge(delaunay, a, b);
+                self.push_edge(delaunay, b, a);
+            }
+        }
+    }
+
+    /// Queues the collapses of `u` and its neighbors, their fans have changed.
+    fn push_around(&mut self, delaunay: &IntDelaunay, u: usize) {
+        let mut ring = Vec::with_capacity(16);
+        self.collect_ring(delaunay, u, &mut ring);
+        for &w in ring.iter() {
+            self.push_edge(delaunay, u, w);
+        }
+
+        let mut next_ring = Vec::with_capacity(16);
+        for &w in ring.iter() {
+            if self.fixed[w] {
+                continue;
+            }
+            self.collect_ring(delaunay, w, &mut next_ring);
+            for &z in next_ring.iter() {
+                self.push_edge(delaunay, w, z);
+            }
+        }
+    }
+
+    /// Collects the points connected to `v` by an edge.
+    fn collect_ring(&mut self, delaunay: &IntDelaunay, v: usize, ring: &mut Vec<usize>) {
+        collect_vertex_fan(delaunay, self.vertex_triangle[v], v, &mut self.fan);
+        ring.clear();
+        for &t in self.fan.iter() {
+            for p in delaunay.triangles[t].vertices.iter() {
+                if p.index != v && !ring.contains(&p.index) {
+                    ring.push(p.index);
+                }
+            }
+        }
+    }
+
+    /// Queues the collapse of `v` into `u`.
+    #[inline]
+    fn push_edge(&mut self, delaunay: &IntDelaunay, v: usize, u: usize) {
+        if self.fixed[v] {
+            return;
+        }
+        let sqr_edge = delaunay.points[v].sqr_distance(delaunay.points[u]) as u64;
+        self.queue.push(Reverse((sqr_edge, v, u)));
+    }
+
+    /// Moves the point `v` into `u` and removes both triangles of the edge `vu`.
+    fn collapse(&mut self, delaunay: &mut IntDelaunay, v: usize, u: usize) -> bool {
+        collect_vertex_fan(delaunay, self.vertex_triangle[v], v, &mut self.fan);
+
+        let mut pair = [NIL_INDEX; 2];
+        let mut pair_count = 0;
+        for &t in self.fan.iter() {
+            if delaunay.triangles[t].vertices.iter().any(|p| p.index == u) {
+                if pair_count == 2 {
+                    return false;
+                }
+                pair[pair_count] = t;
+                pair_count += 1;
+            }
+        }
+        if pair_count != 2 {
+            // the edge is gone
+            return false;
+        }
+
+        let pu = delaunay.points[u];
+        for &t in self.fan.iter() {
+            if pair.contains(&t) {
+                continue;
+            }
+            let [a, b, c] = delaunay.triangles[t].vertices.map(|p| if p.index == v { pu } else { p.point });
+            if Triangle::area_two_point(a, b, c) >= 0 {
+                return false;
+            }
+        }
+
+        if !self.is_link_valid(delaunay, v, u, pair) {
+            return false;
+        }
+
+        for &t in pair.iter() {
+            let triangle = &delaunay.triangles[t];
+            let kv = triangle.vertices.iter().position(|p| p.index == v).unwrap();
+            let ku = triangle.vertices.iter().position(|p| p.index == u).unwrap();
+            let x = triangle.vertices[3 - kv - ku].index;
+
+            // the triangles on the edges `ux` and `vx` become neighbors
+            let ux_neighbor = triangle.neighbors[kv];
+            let vx_neighbor = triangle.neighbors[ku];
+            delaunay.update_neighbor(ux_neighbor, t, vx_neighbor);
+            delaunay.update_neighbor(vx_neighbor, t, ux_neighbor);
+
+            self.removed_triangles[t] = true;
+            self.vertex_triangle[x] = vx_neighbor;
+            self.vertex_triangle[u] = vx_neighbor;
+        }
+
+        let vu = IndexPoint { index: u, point: pu };
+        for &t in self.fan.iter() {
+            if pair.contains(&t) {
+                continue;
+            }
+            for p in delaunay.triangles[t].vertices.iter_mut() {
+                if p.index == v {
+                    *p = vu;
+                }
+            }
+        }
+        self.removed_points[v] = true;
+
+        true
+    }
+
+    /// True if `v` and `u` have no common neighbors except the apexes of their two triangles.
+    fn is_link_valid(&mut self, delaunay: &IntDelaunay, v: usize, u: usize, pair: [usize; 2]) -> bool {
+        // the fan of `v` is already collected
+        self.ring.clear();
+        for &t in self.fan.iter() {
+            for p in delaunay.triangles[t].vertices.iter() {
+                if p.index != v && p.index != u && !self.ring.contains(&p.index) {
+                    self.ring.push(p.index);
+                }
+            }
+        }
+
+        let apexes = pair.map(|t| {
+            let vertices = &delaunay.triangles[t].vertices;
+            vertices.iter().find(|p| p.index != v && p.index != u).unwrap().index
+        });
+
+        let mut fan_u = Vec::with_capacity(16);
+        collect_vertex_fan(delaunay, pair[0], u, &mut fan_u);
+        for &t in fan_u.iter() {
+            for p in delaunay.triangles[t].vertices.iter() {
+                if p.index != u && !apexes.contains(&p.index) && self.ring.contains(&p.index) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Drops the removed points and triangles.
+    fn compact(&self, delaunay: &mut IntDelaunay) {
+        let mut point_map = vec![NIL_INDEX; delaunay.points.len()];
+        let mut points_count = 0;
+        for (i, &is_removed) in self.removed_points.iter().enumerate() {
+            if !is_removed {
+                point_map[i] = points_count;
+                delaunay.points[points_count] = delaunay.points[i];
+                if !delaunay.vertex_sources.is_empty() {
+                    delaunay.vertex_sources[points_count] = delaunay.vertex_sources[i];
+                }
+                points_count += 1;
+            }
+        }
+        delaunay.points.truncate(points_count);
+        delaunay.vertex_sources.truncate(points_count);
+
+        let mut triangle_map = vec![NIL_INDEX; delaunay.triangles.len()];
+        let mut triangles_count = 0;
+        for (i, &is_removed) in self.removed_triangles.iter().enumerate() {
+            if !is_removed {
+                triangle_map[i] = triangles_count;
+                delaunay.triangles.swap(triangles_count, i);
+                if !delaunay.shape_ids.is_empty() {
+                    delaunay.shape_ids[triangles_count] = delaunay.shape_ids[i];
+                }
+                triangles_count += 1;
+            }
+        }
+        delaunay.triangles.truncate(triangles_count);
+        delaunay.shape_ids.truncate(triangles_count);
+
+        for t in delaunay.triangles.iter_mut() {
+            for p in t.vertices.iter_mut() {
+                p.index = point_map[p.index];
+            }
+            for n in t.neighbors.iter_mut() {
+                if *n < triangle_map.len() {
+                    *n = triangle_map[*n];
+                }
+            }
+        }
+
+        delaunay.constraints.remap(&point_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::provenance::VertexSource;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::tessellation::decimation::DecimationOptions;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let refined = contour.triangulate().into_delaunay().refine_with_circumcenters(1_000);
+        let count = refined.triangles.len();
+        let boundary: Vec<IntPoint> = refined
+            .points
+            .iter()
+            .filter(|p| p.x == 0 || p.y == 0 || p.x == 1000 || p.y == 1000)
+            .copied()
+            .collect();
+
+        let options = DecimationOptions {
+            target_triangle_count: Some(count / 2),
+            ..Default::default()
+        };
+        let decimated = refined.decimate(&options);
+        decimated.validate();
+        assert!((count / 2 - 1..=count / 2).contains(&decimated.triangles.len()));
+        assert_eq!(decimated.area(), contour.area_two());
+        for t in decimated.triangles.iter() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            assert!(Triangle::area_two_point(a, b, c) < 0);
+            for v in t.vertices.iter() {
+                assert_eq!(decimated.points[v.index], v.point);
+            }
+        }
+
+        // the boundary points are kept
+        assert!(boundary.iter().all(|p| decimated.points.contains(p)));
+    }
+
+    #[test]
+    fn test_1() {
+        // without a target almost all inner points are removed
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let refined = contour.triangulate().into_delaunay().refine_with_circumcenters(1_000);
+        let decimated = refined.decimate(&DecimationOptions::default());
+        decimated.validate();
+        assert_eq!(decimated.area(), contour.area_two());
+        // the last inner point is linked to every side, moving it to any of them flattens a triangle
+        let inner = decimated
+            .points
+            .iter()
+            .filter(|p| p.x != 0 && p.y != 0 && p.x != 1000 && p.y != 1000)
+            .count();
+        assert!(inner <= 1);
+
+        // a zero error keeps the mesh
+        let refined = contour.triangulate().into_delaunay().refine_with_circumcenters(1_000);
+        let count = refined.triangles.len();
+        let options = DecimationOptions {
+            max_error: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(refined.decimate(&options).triangles.len(), count);
+    }
+
+    #[test]
+    fn test_2() {
+        // a hole and a constrained edge are kept
+        let shape = vec![
+            path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]),
+            path(&[[400, 400], [400, 600], [600, 600], [600, 400]]),
+        ];
+        let refined = shape.triangulate().into_delaunay().refine_with_circumcenters(500);
+        let hole_points = refined
+            .points
+            .iter()
+            .filter(|p| (400..=600).contains(&p.x) && (400..=600).contains(&p.y))
+            .count();
+
+        let decimated = refined.decimate(&DecimationOptions::default());
+        decimated.validate();
+        assert_eq!(decimated.area(), shape.area_two());
+        let after = decimated
+            .points
+            .iter()
+            .filter(|p| (400..=600).contains(&p.x) && (400..=600).contains(&p.y))
+            .count();
+        assert_eq!(after, hole_points);
+    }
+
+    #[test]
+    fn test_3() {
+        // vertex sources follow the removed points
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let refined = contour
+            .triangulate()
+            .with_vertex_sources(&[vec![contour.clone()]], &[])
+            .into_delaunay()
+            .refine_with_circumcenters(2_000);
+        let decimated = refined.decimate(&DecimationOptions::default());
+        let sources = decimated.vertex_sources().unwrap();
+        assert_eq!(sources.len(), decimated.points.len());
+        for (p, source) in decimated.points.iter().zip(sources.iter()) {
+            if let VertexSource::Original { index, .. } = *source {
+                assert_eq!(*p, contour[index]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(3..=8);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000)))
+                .collect();
+
+            let refined = contour.triangulate().into_delaunay().refine_with_circumcenters(2_000);
+            let area = refined.area();
+            let count = refined.triangles.len();
+            let options = DecimationOptions {
+                target_triangle_count: Some(rng.random_range(0..=count)),
+                max_error: Some(rng.random_range(10..1000)),
+            };
+            let decimated = refined.decimate(&options);
+            decimated.validate();
+            assert_eq!(decimated.area(), area);
+            for t in decimated.triangles.iter() {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                assert!(Triangle::area_two_point(a, b, c) < 0);
+                for v in t.vertices.iter() {
+                    assert_eq!(decimated.points[v.index], v.point);
+                }
+            }
+        }
+    }
+}
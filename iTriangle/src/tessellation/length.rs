@@ -0,0 +1,164 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::tessellation::circumcenter::{EdgeSelector, RefinementOptions};
+use i_overlay::i_float::int::point::IntPoint;
+
+impl IntDelaunay {
+    /// Splits edges until no edge of the mesh is longer than `max_edge_length`.
+    ///
+    /// Boundary and constrained edges are split by their middle, like [`SliceContour`](crate::tessellation::split::SliceContour)
+    /// does for contours, inner edges by the circumcenters of their triangles.
+    /// The longest edge is always split first and the mesh stays Delaunay after every split.
+    /// If the longest edge of a triangle can not be split, its other long edges are tried.
+    ///
+    /// Middles are rounded to int points, so a slanted boundary may move by a unit.
+    /// A long edge of a sliver thinner than a unit, whose split would invert a triangle, is left as it is.
+    /// A zero length can not be met and leaves the mesh as it is.
+    #[inline]
+    pub fn refine_by_max_edge_length(mut self, max_edge_length: u64) -> Self {
+        self.refine_by_max_edge_length_mut(max_edge_length);
+        self
+    }
+
+    #[inline]
+    pub fn refine_by_max_edge_length_mut(&mut self, max_edge_length: u64) {
+        if max_edge_length == 0 {
+            return;
+        }
+        let options = RefinementOptions {
+            min_area: u64::MAX >> 1,
+            max_edge_length: Some(max_edge_length),
+            ..Default::default()
+        };
+        let selector = LongestEdge {
+            sqr_edge: max_edge_length.saturating_mul(max_edge_length),
+        };
+        self.refine_queue(&options, selector, true);
+    }
+}
+
+/// Selects the longest edge if it is longer than the limit, the longest edges go first.
+struct LongestEdge {
+    sqr_edge: u64,
+}
+
+impl LongestEdge {
+    #[inline]
+    fn longest(points: [IntPoint; 3]) -> (usize, u64) {
+        let [a, b, c] = points;
+        let sqr = [b.sqr_distance(c), c.sqr_distance(a), a.sqr_distance(b)].map(|s| s as u64);
+        let mut k = 0;
+        for i in 1..3 {
+            if sqr[i] > sqr[k] {
+                k = i;
+            }
+        }
+        (k, sqr[k])
+    }
+}
+
+impl EdgeSelector for LongestEdge {
+    #[inline]
+    fn select(&self, _triangle: usize, points: [IntPoint; 3]) -> Option<usize> {
+        let (k, sqr) = Self::longest(points);
+        if sqr > self.sqr_edge { Some(k) } else { None }
+    }
+
+    #[inline]
+    fn priority(&self, _triangle: usize, points: [IntPoint; 3]) -> f64 {
+        Self::longest(points).1 as f64
+    }
+
+    #[inline]
+    fn uses_index(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulatable::IntTriangulatable;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn max_sqr_edge(delaunay: &IntDelaunay) -> i64 {
+        delaunay
+            .triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                a.sqr_distance(b).max(b.sqr_distance(c)).max(c.sqr_distance(a))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [1024, 0], [1024, 1024], [0, 1024]]);
+        let refined = contour.triangulate().into_delaunay().refine_by_max_edge_length(100);
+        refined.validate();
+        assert!(max_sqr_edge(&refined) <= 100 * 100);
+        assert_eq!(refined.area(), contour.area_two());
+
+        // the boundary is sliced too
+        let on_bottom = refined.points.iter().filter(|p| p.y == 0).count();
+        assert!(on_bottom >= 11);
+    }
+
+    #[test]
+    fn test_1() {
+        // a square with a hole
+        let shape = vec![
+            path(&[[0, 0], [2048, 0], [2048, 2048], [0, 2048]]),
+            path(&[[512, 512], [512, 1536], [1536, 1536], [1536, 512]]),
+        ];
+        let refined = shape.triangulate().into_delaunay().refine_by_max_edge_length(256);
+        refined.validate();
+        assert!(max_sqr_edge(&refined) <= 256 * 256);
+        assert_eq!(refined.area(), shape.area_two());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(3..=8);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000)))
+                .collect();
+
+            let length = rng.random_range(50..400);
+            let refined = contour.triangulate().into_delaunay().refine_by_max_edge_length(length);
+            refined.validate();
+            // only an edge of a sliver of a self-intersecting contour may stay long without a grid point to split at
+            for t in refined.triangles.iter() {
+                for k in 0..3 {
+                    let b = t.vertices[(k + 1) % 3].point;
+                    let c = t.vertices[(k + 2) % 3].point;
+                    let sqr = b.sqr_distance(c);
+                    if sqr <= (length * length) as i64 {
+                        continue;
+                    }
+                    let height =
+                        |a: IntPoint| Triangle::area_two_point(a, b, c).unsigned_abs() as f64 / (sqr as f64).sqrt();
+                    let mut min = height(t.vertices[k].point);
+                    let n = t.neighbors[k];
+                    if n < refined.triangles.len() {
+                        let other = &refined.triangles[n];
+                        let [i, j] = [(k + 1) % 3, (k + 2) % 3].map(|i| t.vertices[i].index);
+                        min = min.min(height(other.vertices[other.other_vertex(i, j)].point));
+                    }
+                    assert!(min < 2.0);
+                }
+            }
+        }
+    }
+}
@@ -1,2 +1,10 @@
 pub mod split;
-pub mod circumcenter;
\ No newline at end of file
+pub mod circumcenter;
+pub mod quality;
+pub mod sizing;
+pub mod priority;
+pub mod length;
+pub mod adaptive;
+pub mod decimation;
+
+pub(crate) mod pass;
@@ -0,0 +1,96 @@
+use crate::advanced::delaunay::IntDelaunay;
+
+/// A refinement made of passes over the mesh. Every sweep of a pass visits the triangles
+/// in index order, the ones made during the sweep too.
+///
+/// With the `rayon` feature, the independent parts of a mesh run their sweeps in parallel
+/// and are merged back in the order a single run would give, so the result is the same.
+pub(crate) trait Passes: Sync {
+    /// The working data of one mesh, e.g. buffers.
+    type State: Send;
+
+    /// The number of sweeps in a pass.
+    const SWEEPS: usize = 1;
+
+    fn state(&self, delaunay: &IntDelaunay) -> Self::State;
+
+    /// Visits the triangle in the given sweep, returns true if it changed the mesh.
+    fn visit(&self, delaunay: &mut IntDelaunay, state: &mut Self::State, sweep: usize, abc_index: usize) -> bool;
+
+    /// True if one more pass is needed after `pass` passes, the last one changed the mesh `changes` times.
+    /// Before the first pass `changes` is the triangle count.
+    fn is_next(&self, pass: usize, changes: usize, triangles: usize) -> bool;
+
+    /// True if the mesh must not grow anymore, checked before every visit.
+    #[inline]
+    fn is_full(&self, _delaunay: &IntDelaunay) -> bool {
+        false
+    }
+
+    /// False if the passes depend on the whole mesh, e.g. [`Passes::is_full`] does,
+    /// such a mesh is never refined by parts.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn is_parallel(&self) -> bool {
+        true
+    }
+}
+
+impl IntDelaunay {
+    pub(crate) fn run_passes<P: Passes>(&mut self, passes: &P) {
+        #[cfg(feature = "rayon")]
+        if passes.is_parallel() && self.par_run_passes(passes) {
+            return;
+        }
+
+        self.run_passes_serial(passes);
+    }
+
+    pub(crate) fn run_passes_serial<P: Passes>(&mut self, passes: &P) {
+        let mut state = passes.state(self);
+        let mut pass = 0;
+        let mut changes = self.triangles.len();
+        while passes.is_next(pass, changes, self.triangles.len()) {
+            changes = if let Some(changes) = self.run_pass(passes, &mut state, pass, |_, _, _| {}) {
+                changes
+            } else {
+                return;
+            };
+            pass += 1;
+        }
+    }
+
+    /// Runs one pass and returns the number of changes, `None` if the mesh got full.
+    /// `on_add` gets the round (the sweep over all passes), the visited triangle and the mesh
+    /// after every visit that added triangles or points.
+    pub(crate) fn run_pass<P: Passes, F: FnMut(usize, usize, &IntDelaunay)>(
+        &mut self,
+        passes: &P,
+        state: &mut P::State,
+        pass: usize,
+        mut on_add: F,
+    ) -> Option<usize> {
+        let mut changes = 0;
+        for sweep in 0..P::SWEEPS {
+            let round = pass * P::SWEEPS + sweep;
+            let mut abc_index = 0;
+            while abc_index < self.triangles.len() {
+                if passes.is_full(self) {
+                    return None;
+                }
+
+                let triangles = self.triangles.len();
+                let points = self.points.len();
+                if passes.visit(self, state, sweep, abc_index) {
+                    changes += 1;
+                }
+
+                if self.triangles.len() != triangles || self.points.len() != points {
+                    on_add(round, abc_index, self);
+                }
+                abc_index += 1;
+            }
+        }
+        Some(changes)
+    }
+}
@@ -0,0 +1,263 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::tessellation::circumcenter::{EdgeSelector, Limits, RefinementOptions};
+use crate::tessellation::quality::abc_by_edge;
+use i_overlay::i_float::int::point::IntPoint;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+impl IntDelaunay {
+    /// Splits the same triangles as [`IntDelaunay::refine_with_options`], but always the worst one first.
+    ///
+    /// Triangles bigger than `options.min_area` (or with an edge longer than `options.max_edge_length`)
+    /// are kept in a queue ordered by [`EdgeSelector::priority`], ties go to the smaller index.
+    /// After a split only the triangles it touched are checked again.
+    /// The refinement stops when no such triangle is left or `options.target_triangle_count`
+    /// or `options.max_point_count` is reached, `max_iterations` and `min_split_ratio` are not used.
+    ///
+    /// The result depends only on the input mesh and the options.
+    /// The queue is global, so the mesh is refined on one thread also with the `rayon` feature.
+    #[inline]
+    pub fn refine_worst_first<S: EdgeSelector>(mut self, options: &RefinementOptions, selector: S) -> Self {
+        self.refine_worst_first_mut(options, selector);
+        self
+    }
+
+    #[inline]
+    pub fn refine_worst_first_mut<S: EdgeSelector>(&mut self, options: &RefinementOptions, selector: S) {
+        self.refine_queue(options, selector, false);
+    }
+
+    /// With `split_other_edges`, a triangle whose selected edge can not be split
+    /// has its other edges longer than `options.max_edge_length` tried, the longest first.
+    pub(crate) fn refine_queue<S: EdgeSelector>(
+        &mut self,
+        options: &RefinementOptions,
+        selector: S,
+        split_other_edges: bool,
+    ) {
+        let selector = &selector;
+        let limits = options.limits();
+
+        let mut unchecked = HashSet::with_capacity(16);
+        let mut buffer = Vec::with_capacity(16);
+        let mut touched = Vec::with_capacity(16);
+
+        let mut queue = BinaryHeap::with_capacity(self.triangles.len());
+        for abc_index in 0..self.triangles.len() {
+            if let Some(entry) = self.queue_entry(&limits, selector, abc_index) {
+                queue.push(entry);
+            }
+        }
+
+        while let Some(entry) = queue.pop() {
+            if self.triangles.len() >= limits.max_triangles || self.points.len() >= limits.max_points {
+                return;
+            }
+
+            // a changed triangle is queued again with its new priority
+            if self.priority(selector, entry.index).to_bits() != entry.priority.to_bits() {
+                continue;
+            }
+
+            let abc = &self.triangles[entry.index];
+            let Some(abc) =
+                Self::select_edge_for_refinement(limits.two_area, limits.sqr_edge, selector, entry.index, abc)
+            else {
+                continue;
+            };
+
+            let selected = abc.v0.vertex.index;
+            let is_split = self.split_triangle(entry.index, abc, &mut buffer)
+                || split_other_edges
+                    && self.split_other_long_edge(entry.index, selected, limits.sqr_edge, &mut buffer);
+            if !is_split {
+                // a triangle that can not be split stays out of the queue until a neighbor changes it
+                continue;
+            }
+
+            self.fix_triangles_and_collect(&mut buffer, &mut unchecked, &mut touched);
+            touched.sort_unstable();
+            touched.dedup();
+            for &abc_index in touched.iter() {
+                if let Some(entry) = self.queue_entry(&limits, selector, abc_index) {
+                    queue.push(entry);
+                }
+            }
+            touched.clear();
+        }
+    }
+
+    /// Splits the longest other edge longer than `sqr_edge` if the selected one can not be split,
+    /// e.g. an edge of a sliver without a grid point near its middle.
+    fn split_other_long_edge(
+        &mut self,
+        abc_index: usize,
+        selected: usize,
+        sqr_edge: u64,
+        buffer: &mut Vec<usize>,
+    ) -> bool {
+        let t = &self.triangles[abc_index];
+        let mut edges: Vec<(u64, usize)> = (0..3)
+            .filter(|&k| t.vertices[k].index != selected)
+            .map(|k| {
+                let b = t.vertices[(k + 1) % 3].point;
+                let c = t.vertices[(k + 2) % 3].point;
+                (b.sqr_distance(c) as u64, k)
+            })
+            .filter(|&(sqr, _)| sqr > sqr_edge)
+            .collect();
+        edges.sort_unstable_by(|x, y| y.cmp(x));
+
+        for (_, k) in edges {
+            let abc = abc_by_edge(&self.triangles[abc_index], k);
+            if self.split_triangle(abc_index, abc, buffer) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[inline]
+    fn queue_entry<S: EdgeSelector>(&self, limits: &Limits, selector: &S, abc_index: usize) -> Option<QueueEntry> {
+        let abc = &self.triangles[abc_index];
+        Self::select_edge_for_refinement(limits.two_area, limits.sqr_edge, selector, abc_index, abc)?;
+        Some(QueueEntry {
+            priority: self.priority(selector, abc_index),
+            index: abc_index,
+        })
+    }
+
+    #[inline]
+    fn priority<S: EdgeSelector>(&self, selector: &S, abc_index: usize) -> f64 {
+        let points: [IntPoint; 3] = self.triangles[abc_index].vertices.map(|v| v.point);
+        selector.priority(abc_index, points)
+    }
+}
+
+struct QueueEntry {
+    priority: f64,
+    index: usize,
+}
+
+impl PartialEq for QueueEntry {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    // the max heap pops the biggest priority, then the smallest index
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .total_cmp(&other.priority)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::tessellation::circumcenter::{RefinementOptions, SelectBiggerAngle};
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    #[test]
+    fn test_0() {
+        // the criterion is met exactly, without a pass limit
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let options = RefinementOptions {
+            min_area: 5_000,
+            ..Default::default()
+        };
+        let refined = contour.triangulate().into_delaunay().refine_worst_first(&options, SelectBiggerAngle);
+        refined.validate();
+        assert_eq!(refined.area(), contour.area_two());
+        for t in refined.triangles.iter() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            assert!(Triangle::area_two_point(a, b, c).unsigned_abs() <= 10_000);
+        }
+
+        // the same input gives the same mesh
+        let again = contour.triangulate().into_delaunay().refine_worst_first(&options, SelectBiggerAngle);
+        assert_eq!(refined.points, again.points);
+    }
+
+    #[test]
+    fn test_1() {
+        // with a budget the biggest triangles are split first
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let options = RefinementOptions {
+            min_area: 1,
+            target_triangle_count: Some(64),
+            ..Default::default()
+        };
+        let refined = contour.triangulate().into_delaunay().refine_worst_first(&options, SelectBiggerAngle);
+        refined.validate();
+        assert!((64..=65).contains(&refined.triangles.len()));
+
+        let areas: Vec<u64> = refined
+            .triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.vertices.map(|v| v.point);
+                Triangle::area_two_point(a, b, c).unsigned_abs()
+            })
+            .collect();
+        let min = *areas.iter().min().unwrap();
+        let max = *areas.iter().max().unwrap();
+        assert!(max <= 8 * min);
+    }
+
+    #[test]
+    fn test_2() {
+        let contour = path(&[[0, 0], [1000, 0], [1000, 1000], [0, 1000]]);
+        let options = RefinementOptions {
+            max_edge_length: Some(100),
+            max_point_count: Some(30),
+            ..Default::default()
+        };
+        let refined = contour.triangulate().into_delaunay().refine_worst_first(&options, SelectBiggerAngle);
+        refined.validate();
+        assert_eq!(refined.points.len(), 30);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(3..=8);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000)))
+                .collect();
+
+            let options = RefinementOptions {
+                min_area: 2_000,
+                ..Default::default()
+            };
+            let delaunay = contour.triangulate().into_delaunay();
+            let area = delaunay.area();
+            let refined = delaunay.refine_worst_first(&options, SelectBiggerAngle);
+            refined.validate();
+            // rounded split points of slanted edges may move the boundary a little
+            assert!((refined.area() - area).abs() <= refined.points.len() as i64 * 2000);
+        }
+    }
+}
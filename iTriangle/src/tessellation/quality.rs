@@ -0,0 +1,538 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::point::IndexPoint;
+use crate::geom::triangle::{Abc, IntTriangle};
+use crate::index::Index;
+use crate::tessellation::circumcenter::circumscribed_center_f64;
+use crate::tessellation::pass::Passes;
+use i_overlay::i_float::int::point::IntPoint;
+use i_overlay::i_float::triangle::Triangle;
+use std::collections::HashSet;
+
+/// Above this bound Delaunay refinement is not guaranteed to terminate.
+const MAX_MIN_ANGLE: f64 = 33.0;
+
+/// Triangles with a shorter edge (squared) are left as they are, rounding dominates at this size.
+const MIN_SQR_EDGE: i64 = 16;
+
+/// Circumcenters are walked to in int coordinates, a far one is pulled back into this range.
+const MAX_COORD: f64 = (1 << 30) as f64;
+
+impl IntDelaunay {
+    /// Refines the mesh until no triangle has an angle below `min_angle` (in degrees)
+    /// or an area above `max_area` (`0` means no area limit).
+    ///
+    /// A Ruppert-style refinement: bad triangles get their circumcenter inserted,
+    /// unless it encroaches a segment (a boundary or constrained edge), then the segment is split instead.
+    /// Segments are split on concentric shells around input vertices, so small input angles do not
+    /// cascade into endless splits. A segment is only split at an int point lying on it,
+    /// so the boundary never moves and a segment without such points stays whole.
+    ///
+    /// `min_angle` is clamped to 33°. There is no pass limit, the passes repeat until no triangle can be
+    /// improved: every inserted point is a new int point inside the mesh, so the refinement ends,
+    /// and the shells and the clamp keep the number of points small.
+    ///
+    /// A triangle stays below the bound only where no point can fix it: at an angle between two segments
+    /// at an input vertex, with an edge shorter than 4 units, or next to a segment which can not be split
+    /// (shorter than 4 units or without an int point strictly inside).
+    /// With the `rayon` feature, independent parts of the mesh are refined in parallel,
+    /// the result is the same as without the feature.
+    #[inline]
+    pub fn refine_to_min_angle(mut self, min_angle: f64, max_area: u64) -> Self {
+        self.refine_to_min_angle_mut(min_angle, max_area);
+        self
+    }
+
+    #[inline]
+    pub fn refine_to_min_angle_mut(&mut self, min_angle: f64, max_area: u64) {
+        self.run_passes(&MinAnglePasses { min_angle, max_area });
+    }
+
+    /// True if the edge `k` of the triangle is a boundary or a constrained edge.
+    #[inline]
+    pub(crate) fn is_segment(&self, abc_index: usize, k: usize) -> bool {
+        let t = &self.triangles[abc_index];
+        !t.neighbors[k].is_not_nil()
+            || self
+                .constraints
+                .contains(t.vertices[(k + 1) % 3].index, t.vertices[(k + 2) % 3].index)
+    }
+
+    /// Inserts `m` strictly inside the triangle, splitting it into three.
+    fn insert_point(&mut self, abc_index: usize, m: IntPoint, buffer: &mut Vec<usize>) {
+        let m_index = self.points.len();
+        self.points.push(m);
+        self.add_refinement_source();
+        let vm = IndexPoint {
+            index: m_index,
+            point: m,
+        };
+
+        let abc = self.triangles[abc_index].clone();
+        let [v0, v1, v2] = abc.vertices;
+        let [n0, n1, n2] = abc.neighbors;
+
+        // abc -> mbc, amc, abm
+        let mbc_index = abc_index;
+        let amc_index = self.triangles.len();
+        let abm_index = amc_index + 1;
+
+        let mbc = IntTriangle {
+            vertices: [vm, v1, v2],
+            neighbors: [n0, amc_index, abm_index],
+        };
+
+        let amc = IntTriangle {
+            vertices: [v0, vm, v2],
+            neighbors: [mbc_index, n1, abm_index],
+        };
+
+        let abm = IntTriangle {
+            vertices: [v0, v1, vm],
+            neighbors: [mbc_index, amc_index, n2],
+        };
+
+        self.update_neighbor(n1, abc_index, amc_index);
+        self.update_neighbor(n2, abc_index, abm_index);
+
+        self.triangles[mbc_index] = mbc;
+        self.triangles.push(amc);
+        self.triangles.push(abm);
+        self.inherit_shape_id(abc_index);
+        self.inherit_shape_id(abc_index);
+
+        buffer.extend_from_slice(&[mbc_index, amc_index, abm_index]);
+    }
+}
+
+/// The passes of [`IntDelaunay::refine_to_min_angle`],
+/// encroached segments are split first, then bad triangles.
+pub(crate) struct MinAnglePasses {
+    pub(crate) min_angle: f64,
+    pub(crate) max_area: u64,
+}
+
+impl Passes for MinAnglePasses {
+    type State = QualityRefinement;
+
+    const SWEEPS: usize = 2;
+
+    #[inline]
+    fn state(&self, delaunay: &IntDelaunay) -> Self::State {
+        QualityRefinement::new(delaunay, self.min_angle, self.max_area)
+    }
+
+    fn visit(&self, delaunay: &mut IntDelaunay, refinement: &mut Self::State, sweep: usize, abc_index: usize) -> bool {
+        let mut is_changed = false;
+        if sweep == 0 {
+            for k in 0..3 {
+                if refinement.is_encroached(delaunay, abc_index, k) {
+                    is_changed |= refinement.split_segment(delaunay, abc_index, k);
+                }
+            }
+        } else if refinement.is_bad(delaunay, abc_index) {
+            is_changed = refinement.insert_circumcenter(delaunay, abc_index);
+        }
+        is_changed
+    }
+
+    #[inline]
+    fn is_next(&self, pass: usize, changes: usize, _triangles: usize) -> bool {
+        pass == 0 || changes > 0
+    }
+}
+
+/// The result of walking through the mesh toward a point.
+enum Location {
+    /// The point is inside the triangle or on its border.
+    Inside(usize),
+    /// The walk hit the segment `k` of the triangle.
+    Blocked(usize, usize),
+    /// The walk did not converge.
+    Lost,
+}
+
+pub(crate) struct QualityRefinement {
+    cos_bound: f64,
+    two_area: u64,
+    // points below this index are the input of the refinement
+    input_count: usize,
+    unchecked: HashSet<usize>,
+    buffer: Vec<usize>,
+    fan: Vec<usize>,
+}
+
+impl QualityRefinement {
+    fn new(delaunay: &IntDelaunay, min_angle: f64, max_area: u64) -> Self {
+        let min_angle = min_angle.clamp(0.0, MAX_MIN_ANGLE);
+        Self {
+            cos_bound: min_angle.to_radians().cos(),
+            two_area: max_area << 1,
+            input_count: delaunay.points.len(),
+            unchecked: HashSet::with_capacity(16),
+            buffer: Vec::with_capacity(16),
+            fan: Vec::with_capacity(16),
+        }
+    }
+
+    /// A segment is encroached if the opposite vertex lies strictly inside its diametral circle.
+    #[inline]
+    fn is_encroached(&self, delaunay: &IntDelaunay, abc_index: usize, k: usize) -> bool {
+        if !delaunay.is_segment(abc_index, k) {
+            return false;
+        }
+        let t = &delaunay.triangles[abc_index];
+        let a = t.vertices[(k + 1) % 3].point;
+        let b = t.vertices[(k + 2) % 3].point;
+        a.sqr_distance(b) >= MIN_SQR_EDGE && is_encroached_by(t.vertices[k].point, a, b)
+    }
+
+    fn is_bad(&self, delaunay: &IntDelaunay, abc_index: usize) -> bool {
+        let t = &delaunay.triangles[abc_index];
+        let [a, b, c] = t.vertices.map(|v| v.point);
+
+        // squared length of the edge opposite each vertex
+        let sqr = [b.sqr_distance(c), c.sqr_distance(a), a.sqr_distance(b)];
+        let k = if sqr[0] <= sqr[1] && sqr[0] <= sqr[2] {
+            0
+        } else if sqr[1] <= sqr[2] {
+            1
+        } else {
+            2
+        };
+        if sqr[k] < MIN_SQR_EDGE {
+            return false;
+        }
+
+        if self.two_area > 0 && Triangle::area_two_point(a, b, c).unsigned_abs() > self.two_area {
+            return true;
+        }
+
+        // the smallest angle is opposite the shortest edge
+        let s = sqr[k] as f64;
+        let l1 = sqr[(k + 1) % 3] as f64;
+        let l2 = sqr[(k + 2) % 3] as f64;
+        let cos = (l1 + l2 - s) / (2.0 * (l1 * l2).sqrt());
+        if cos <= self.cos_bound {
+            return false;
+        }
+
+        // a small input angle between two segments can not be improved
+        let is_input_corner = t.vertices[k].index < self.input_count
+            && delaunay.is_segment(abc_index, (k + 1) % 3)
+            && delaunay.is_segment(abc_index, (k + 2) % 3);
+
+        !is_input_corner
+    }
+
+    fn insert_circumcenter(&mut self, delaunay: &mut IntDelaunay, abc_index: usize) -> bool {
+        let [a, b, c] = delaunay.triangles[abc_index].vertices.map(|v| v.point);
+        let [x, y] = circumscribed_center_f64(a, b, c);
+        if !x.is_finite() || !y.is_finite() {
+            return false;
+        }
+        let p = IntPoint::new(
+            x.clamp(-MAX_COORD, MAX_COORD).round() as i32,
+            y.clamp(-MAX_COORD, MAX_COORD).round() as i32,
+        );
+
+        let t_index = match Self::locate(delaunay, abc_index, p) {
+            Location::Inside(t_index) => t_index,
+            Location::Blocked(t_index, k) => return self.split_segment(delaunay, t_index, k),
+            Location::Lost => return false,
+        };
+
+        let t = &delaunay.triangles[t_index];
+        if t.vertices.iter().any(|v| v.point == p) {
+            return false;
+        }
+
+        // a circumcenter encroaching a segment is rejected, the segment is split instead
+        for k in 0..3 {
+            let a = t.vertices[(k + 1) % 3].point;
+            let b = t.vertices[(k + 2) % 3].point;
+            if delaunay.is_segment(t_index, k) && is_encroached_by(p, a, b) {
+                return if a.sqr_distance(b) >= MIN_SQR_EDGE {
+                    self.split_segment(delaunay, t_index, k)
+                } else {
+                    false
+                };
+            }
+        }
+
+        let on_edge = (0..3).find(|&k| {
+            let a = t.vertices[(k + 1) % 3].point;
+            let b = t.vertices[(k + 2) % 3].point;
+            Triangle::area_two_point(a, b, p) == 0
+        });
+
+        if let Some(k) = on_edge {
+            let abc = abc_by_edge(&delaunay.triangles[t_index], k);
+            delaunay.split_edge(t_index, abc, p, &mut self.buffer);
+        } else {
+            delaunay.insert_point(t_index, p, &mut self.buffer);
+        }
+        let m_index = delaunay.points.len() - 1;
+        self.fan.clear();
+        self.fan.extend_from_slice(&self.buffer);
+        delaunay.fix_triangles(&mut self.buffer, &mut self.unchecked);
+
+        self.split_encroached_around(delaunay, m_index);
+
+        true
+    }
+
+    /// Splits the segments encroached by the new point `m_index`.
+    fn split_encroached_around(&mut self, delaunay: &mut IntDelaunay, m_index: usize) {
+        // a few rounds, every split changes the fan of the point
+        for _ in 0..8 {
+            let start = if let Some(&start) = self
+                .fan
+                .iter()
+                .find(|&&i| delaunay.triangles[i].vertices.iter().any(|v| v.index == m_index))
+            {
+                start
+            } else {
+                return;
+            };
+            collect_vertex_fan(delaunay, start, m_index, &mut self.fan);
+
+            let encroached = self.fan.iter().find_map(|&t_index| {
+                let k = delaunay.triangles[t_index].vertex_position(m_index);
+                if self.is_encroached(delaunay, t_index, k) {
+                    Some((t_index, k))
+                } else {
+                    None
+                }
+            });
+
+            if let Some((t_index, k)) = encroached {
+                if !self.split_segment(delaunay, t_index, k) {
+                    return;
+                }
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Splits the segment `k` of the triangle, returns false if it can not be split.
+    fn split_segment(&mut self, delaunay: &mut IntDelaunay, abc_index: usize, k: usize) -> bool {
+        let t = &delaunay.triangles[abc_index];
+        let va = t.vertices[(k + 1) % 3];
+        let vb = t.vertices[(k + 2) % 3];
+
+        let m = if let Some(m) = self.segment_split_point(va, vb) {
+            m
+        } else {
+            return false;
+        };
+
+        let abc = abc_by_edge(t, k);
+        delaunay.split_edge(abc_index, abc, m, &mut self.buffer);
+        delaunay.fix_triangles(&mut self.buffer, &mut self.unchecked);
+
+        true
+    }
+
+    /// The middle of the segment, or a point on a concentric shell (a power of two distance)
+    /// around its only input vertex, snapped to the grid points of the segment.
+    /// `None` if there is no grid point strictly inside the segment.
+    fn segment_split_point(&self, va: IndexPoint, vb: IndexPoint) -> Option<IntPoint> {
+        let is_a_input = va.index < self.input_count;
+        let is_b_input = vb.index < self.input_count;
+
+        let (o, q, t) = if is_a_input == is_b_input {
+            (va.point, vb.point, 0.5)
+        } else {
+            let (o, q) = if is_a_input { (va.point, vb.point) } else { (vb.point, va.point) };
+            let len = (o.sqr_distance(q) as f64).sqrt();
+            let shell = (0.5 * len).log2().round().exp2();
+            (o, q, shell / len)
+        };
+
+        grid_point_on_segment(o, q, t)
+    }
+
+    /// Walks from the triangle toward `p`, stops at the triangle containing it or at a segment.
+    fn locate(delaunay: &IntDelaunay, start: usize, p: IntPoint) -> Location {
+        let mut t_index = start;
+        for step in 0..delaunay.triangles.len() {
+            let t = &delaunay.triangles[t_index];
+
+            // start from a rotating edge to never cycle
+            let next = (0..3).map(|i| (i + step) % 3).find(|&k| {
+                let a = t.vertices[(k + 1) % 3].point;
+                let b = t.vertices[(k + 2) % 3].point;
+                Triangle::area_two_point(a, b, p) > 0
+            });
+
+            match next {
+                None => return Location::Inside(t_index),
+                Some(k) if delaunay.is_segment(t_index, k) => return Location::Blocked(t_index, k),
+                Some(k) => t_index = t.neighbors[k],
+            }
+        }
+
+        Location::Lost
+    }
+}
+
+/// True if `p` lies strictly inside the circle with the diameter `ab`.
+#[inline]
+pub(crate) fn is_encroached_by(p: IntPoint, a: IntPoint, b: IntPoint) -> bool {
+    a.subtract(p).dot_product(b.subtract(p)) < 0
+}
+
+/// The grid point of the segment `oq` nearest to `o + t * (q - o)`, strictly between the ends.
+/// `None` if the segment has no such grid point.
+pub(crate) fn grid_point_on_segment(o: IntPoint, q: IntPoint, t: f64) -> Option<IntPoint> {
+    let dx = q.x as i64 - o.x as i64;
+    let dy = q.y as i64 - o.y as i64;
+    let g = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as i64;
+    if g < 2 {
+        return None;
+    }
+
+    let n = ((t * g as f64).round() as i64).clamp(1, g - 1);
+    let x = o.x as i64 + n * dx / g;
+    let y = o.y as i64 + n * dy / g;
+    Some(IntPoint::new(x as i32, y as i32))
+}
+
+#[inline]
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[inline]
+pub(crate) fn abc_by_edge(t: &IntTriangle, k: usize) -> Abc {
+    match k {
+        0 => t.abc_by_a(),
+        1 => t.abc_by_b(),
+        _ => t.abc_by_c(),
+    }
+}
+
+/// Collects all triangles around `vertex`, starting from a triangle containing it.
+pub(crate) fn collect_vertex_fan(delaunay: &IntDelaunay, start: usize, vertex: usize, fan: &mut Vec<usize>) {
+    fan.clear();
+    fan.push(start);
+    let mut i = 0;
+    while i < fan.len() {
+        let t = &delaunay.triangles[fan[i]];
+        for &n in t.neighbors.iter() {
+            if n.is_not_nil() && !fan.contains(&n) && delaunay.triangles[n].vertices.iter().any(|v| v.index == vertex) {
+                fan.push(n);
+            }
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::int::triangulator::Triangulator;
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_float::triangle::Triangle;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn validate(delaunay: &IntDelaunay) {
+        for (i, t) in delaunay.triangles.iter().enumerate() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            assert!(Triangle::area_two_point(a, b, c) < 0);
+            for v in t.vertices.iter() {
+                assert_eq!(delaunay.points[v.index], v.point);
+            }
+            for &n in t.neighbors.iter() {
+                if n < delaunay.triangles.len() {
+                    assert!(delaunay.triangles[n].neighbors.contains(&i));
+                }
+            }
+        }
+    }
+
+    fn mesh_area(delaunay: &IntDelaunay) -> i64 {
+        delaunay.triangles.iter().fold(0, |s, t| {
+            s + Triangle::area_two_point(t.vertices[0].point, t.vertices[1].point, t.vertices[2].point)
+        })
+    }
+
+    fn min_angle(delaunay: &IntDelaunay) -> f64 {
+        delaunay.triangles.iter().fold(180.0, |m: f64, t| {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            let angle = |o: IntPoint, p: IntPoint, q: IntPoint| {
+                let u = p.subtract(o);
+                let v = q.subtract(o);
+                (u.cross_product(v) as f64).abs().atan2(u.dot_product(v) as f64).to_degrees()
+            };
+            m.min(angle(a, b, c)).min(angle(b, c, a)).min(angle(c, a, b))
+        })
+    }
+
+    #[test]
+    fn test_0() {
+        // a long thin rectangle has only bad triangles
+        let contour = path(&[[0, 0], [100_000, 0], [100_000, 5_000], [0, 5_000]]);
+        let delaunay = contour.triangulate().into_delaunay();
+        assert!(min_angle(&delaunay) < 20.0);
+
+        let refined = delaunay.refine_to_min_angle(25.0, 0);
+        validate(&refined);
+        assert!(min_angle(&refined) >= 25.0);
+        assert_eq!(mesh_area(&refined), contour.area_two());
+    }
+
+    #[test]
+    fn test_1() {
+        let shape = vec![
+            path(&[[0, 0], [64_000, 0], [64_000, 64_000], [0, 64_000]]),
+            path(&[[16_000, 16_000], [16_000, 48_000], [48_000, 48_000], [48_000, 16_000]]),
+        ];
+        let refined = shape.triangulate().into_delaunay().refine_to_min_angle(30.0, 10_000_000);
+        validate(&refined);
+        assert!(min_angle(&refined) >= 30.0);
+        for t in refined.triangles.iter() {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            assert!(Triangle::area_two_point(a, b, c).unsigned_abs() <= 20_000_000);
+        }
+        assert_eq!(mesh_area(&refined), shape.area_two());
+    }
+
+    #[test]
+    fn test_2() {
+        // a sharp input angle is kept, the refinement still terminates
+        let contour = path(&[[0, 0], [100_000, 0], [100_000, 4_000]]);
+        let refined = contour.triangulate().into_delaunay().refine_to_min_angle(30.0, 0);
+        validate(&refined);
+        assert_eq!(mesh_area(&refined), contour.area_two());
+    }
+
+    #[test]
+    fn test_random_0() {
+        let triangulator = Triangulator::default();
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let n = rng.random_range(3..12);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-10_000..=10_000), rng.random_range(-10_000..=10_000)))
+                .collect();
+
+            let delaunay = triangulator.triangulate_contour(&contour).into_delaunay();
+            let area = mesh_area(&delaunay);
+            let refined = delaunay.refine_to_min_angle(20.0, 0);
+            validate(&refined);
+            assert_eq!(mesh_area(&refined), area);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use crate::advanced::delaunay::IntDelaunay;
+use crate::geom::triangle::Abc;
+use crate::tessellation::pass::Passes;
+use crate::tessellation::quality::grid_point_on_segment;
+use i_overlay::i_float::int::point::IntPoint;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Desired lengths below this are raised to it, shorter edges are never split.
+const MIN_LENGTH: f64 = 4.0;
+
+/// A desired edge length at every point of the plane, in int units.
+///
+/// Implemented for any `Fn(IntPoint) -> f64`, see also [`DistanceField`] and [`GradedField`].
+pub trait SizingField: Sync {
+    fn edge_length(&self, p: IntPoint) -> f64;
+}
+
+impl<F: Fn(IntPoint) -> f64 + Sync> SizingField for F {
+    #[inline]
+    fn edge_length(&self, p: IntPoint) -> f64 {
+        self(p)
+    }
+}
+
+/// A sizing field growing with the distance to a set of segments or points:
+/// `min_length + growth * distance`, but never above `max_length`.
+pub struct DistanceField {
+    grid: SegmentGrid,
+    min_length: f64,
+    growth: f64,
+    max_length: f64,
+}
+
+/// Limits how fast a sizing field may change: the length at a point never exceeds
+/// the length at a mesh point plus `gradation` times the distance to it.
+///
+/// The limit is propagated along the edges of the mesh it was built for.
+pub struct GradedField<S: SizingField> {
+    field: S,
+    grid: SegmentGrid,
+    lengths: Vec<f64>,
+    gradation: f64,
+}
+
+impl IntDelaunay {
+    /// Splits triangles until every edge is not longer than the length `field` wants at the triangle centroid.
+    ///
+    /// Long edges are split by the circumcenter or the middle, the same way as
+    /// [`IntDelaunay::refine_with_circumcenters`], so the mesh stays Delaunay.
+    /// Desired lengths below 4 units are raised to 4.
+    ///
+    /// There is no pass limit, every split adds a new int point inside the mesh, so the refinement ends.
+    /// An edge whose rounded split point would make an invalid triangle is split at its int point
+    /// nearest to the middle instead. Only a long edge without int points strictly inside stays whole,
+    /// [`IntDelaunay::long_edges`] returns such edges.
+    /// With the `rayon` feature, independent parts of the mesh are refined in parallel,
+    /// the result is the same as without the feature.
+    #[inline]
+    pub fn refine_with_sizing<S: SizingField>(mut self, field: S) -> Self {
+        self.refine_with_sizing_mut(field);
+        self
+    }
+
+    #[inline]
+    pub fn refine_with_sizing_mut<S: SizingField>(&mut self, field: S) {
+        self.run_passes(&SizingPasses { field: &field });
+    }
+
+    /// Returns a [`DistanceField`] growing with the distance to the boundary and constrained edges.
+    pub fn boundary_distance_field(&self, min_length: f64, growth: f64, max_length: f64) -> DistanceField {
+        let mut segments = Vec::new();
+        for (i, t) in self.triangles.iter().enumerate() {
+            for k in 0..3 {
+                let a = t.vertices[(k + 1) % 3];
+                let b = t.vertices[(k + 2) % 3];
+                // an inner constrained edge is seen from both sides, take it once
+                let neighbor = t.neighbors[k];
+                let is_once = neighbor >= self.triangles.len() || neighbor > i;
+                if is_once && self.is_segment(i, k) {
+                    segments.push([a.point, b.point]);
+                }
+            }
+        }
+        DistanceField::with_segments(&segments, min_length, growth, max_length)
+    }
+
+    /// The longest edges of the triangles which are longer than `field` wants at the triangle centroid,
+    /// as sorted point index pairs without duplicates.
+    pub fn long_edges<S: SizingField>(&self, field: &S) -> Vec<[usize; 2]> {
+        let mut edges = Vec::new();
+        for abc_index in 0..self.triangles.len() {
+            if let Some(abc) = self.select_edge_by_size(field, abc_index) {
+                let a = abc.v1.vertex.index;
+                let b = abc.v2.vertex.index;
+                edges.push(if a < b { [a, b] } else { [b, a] });
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Splits the edge `bc` of `abc` at its int point nearest to the middle,
+    /// false if the edge has no int point strictly inside.
+    #[inline]
+    fn split_edge_at_grid_point(&mut self, abc_index: usize, abc: Abc, buffer: &mut Vec<usize>) -> bool {
+        let b = abc.v1.vertex.point;
+        let c = abc.v2.vertex.point;
+        if let Some(m) = grid_point_on_segment(b, c, 0.5) {
+            self.split_edge(abc_index, abc, m, buffer);
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    fn select_edge_by_size<S: SizingField>(&self, field: &S, abc_index: usize) -> Option<Abc> {
+        let t = &self.triangles[abc_index];
+        let [a, b, c] = t.vertices.map(|v| v.point);
+
+        let sqr_a = b.sqr_distance(c);
+        let sqr_b = c.sqr_distance(a);
+        let sqr_c = a.sqr_distance(b);
+        let max = sqr_a.max(sqr_b).max(sqr_c);
+
+        let x = (a.x as i64 + b.x as i64 + c.x as i64) / 3;
+        let y = (a.y as i64 + b.y as i64 + c.y as i64) / 3;
+        let length = field.edge_length(IntPoint::new(x as i32, y as i32)).max(MIN_LENGTH);
+        if (max as f64) <= length * length {
+            return None;
+        }
+
+        Some(if max == sqr_c {
+            t.abc_by_c()
+        } else if max == sqr_b {
+            t.abc_by_b()
+        } else {
+            t.abc_by_a()
+        })
+    }
+}
+
+/// The passes of [`IntDelaunay::refine_with_sizing`].
+pub(crate) struct SizingPasses<'a, S> {
+    pub(crate) field: &'a S,
+}
+
+impl<S: SizingField> Passes for SizingPasses<'_, S> {
+    type State = (HashSet<usize>, Vec<usize>);
+
+    #[inline]
+    fn state(&self, _delaunay: &IntDelaunay) -> Self::State {
+        (HashSet::with_capacity(16), Vec::with_capacity(16))
+    }
+
+    #[inline]
+    fn visit(&self, delaunay: &mut IntDelaunay, state: &mut Self::State, _sweep: usize, abc_index: usize) -> bool {
+        let (unchecked, buffer) = state;
+        if let Some(abc) = delaunay.select_edge_by_size(self.field, abc_index) {
+            // a point on the edge itself always makes valid triangles
+            if delaunay.split_triangle(abc_index, abc.clone(), buffer)
+                || delaunay.split_edge_at_grid_point(abc_index, abc, buffer)
+            {
+                delaunay.fix_triangles(buffer, unchecked);
+                return true;
+            }
+        }
+        false
+    }
+
+    #[inline]
+    fn is_next(&self, pass: usize, changes: usize, _triangles: usize) -> bool {
+        pass == 0 || changes > 0
+    }
+}
+
+impl DistanceField {
+    /// A field growing with the distance to the nearest point of `points`.
+    pub fn with_points(points: &[IntPoint], min_length: f64, growth: f64, max_length: f64) -> Self {
+        let segments: Vec<_> = points.iter().map(|&p| [p, p]).collect();
+        Self::with_segments(&segments, min_length, growth, max_length)
+    }
+
+    /// A field growing with the distance to the nearest segment of `segments`.
+    pub fn with_segments(segments: &[[IntPoint; 2]], min_length: f64, growth: f64, max_length: f64) -> Self {
+        Self {
+            grid: SegmentGrid::new(segments.to_vec()),
+            min_length,
+            growth,
+            max_length,
+        }
+    }
+}
+
+impl SizingField for DistanceField {
+    #[inline]
+    fn edge_length(&self, p: IntPoint) -> f64 {
+        match self.grid.nearest(p) {
+            Some((_, sqr_distance)) => (self.min_length + self.growth * sqr_distance.sqrt()).min(self.max_length),
+            None => self.max_length,
+        }
+    }
+}
+
+impl<S: SizingField> GradedField<S> {
+    /// Limits `field` over the points of `delaunay`, `gradation` is the allowed length change per unit of distance.
+    pub fn new(delaunay: &IntDelaunay, field: S, gradation: f64) -> Self {
+        let points = &delaunay.points;
+        let mut lengths: Vec<f64> = points.iter().map(|&p| field.edge_length(p)).collect();
+
+        // Dijkstra from every point at once, lengths are not negative so their bits keep the order
+        let mut heap: BinaryHeap<_> = lengths
+            .iter()
+            .enumerate()
+            .map(|(i, l)| Reverse((l.max(0.0).to_bits(), i)))
+            .collect();
+        let topology = delaunay.topology();
+        while let Some(Reverse((bits, i))) = heap.pop() {
+            if bits != lengths[i].max(0.0).to_bits() {
+                continue;
+            }
+            let li = lengths[i];
+            for j in topology.vertex_neighbors(i) {
+                let distance = (points[i].sqr_distance(points[j]) as f64).sqrt();
+                let limit = li + gradation * distance;
+                if limit < lengths[j] {
+                    lengths[j] = limit;
+                    heap.push(Reverse((limit.max(0.0).to_bits(), j)));
+                }
+            }
+        }
+
+        Self {
+            field,
+            grid: SegmentGrid::new(points.iter().map(|&p| [p, p]).collect()),
+            lengths,
+            gradation,
+        }
+    }
+}
+
+impl<S: SizingField> SizingField for GradedField<S> {
+    #[inline]
+    fn edge_length(&self, p: IntPoint) -> f64 {
+        let length = self.field.edge_length(p);
+        match self.grid.nearest(p) {
+            Some((i, sqr_distance)) => length.min(self.lengths[i] + self.gradation * sqr_distance.sqrt()),
+            None => length,
+        }
+    }
+}
+
+/// Segments (a point is a segment with equal ends) bucketed by a uniform grid for nearest queries.
+struct SegmentGrid {
+    segments: Vec<[IntPoint; 2]>,
+    min_x: i64,
+    min_y: i64,
+    cell: i64,
+    columns: i64,
+    rows: i64,
+    // segments of the cell `c` are `items[offsets[c]..offsets[c + 1]]`
+    offsets: Vec<usize>,
+    items: Vec<usize>,
+}
+
+impl SegmentGrid {
+    fn new(segments: Vec<[IntPoint; 2]>) -> Self {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (i64::MAX, i64::MAX, i64::MIN, i64::MIN);
+        for p in segments.iter().flatten() {
+            min_x = min_x.min(p.x as i64);
+            min_y = min_y.min(p.y as i64);
+            max_x = max_x.max(p.x as i64);
+            max_y = max_y.max(p.y as i64);
+        }
+        if segments.is_empty() {
+            (min_x, min_y, max_x, max_y) = (0, 0, 0, 0);
+        }
+
+        // about four cells per segment
+        let width = (max_x - min_x + 1) as f64;
+        let height = (max_y - min_y + 1) as f64;
+        let cell = ((width * height / (4 * segments.len().max(1)) as f64).sqrt().ceil() as i64).max(1);
+        let columns = (max_x - min_x) / cell + 1;
+        let rows = (max_y - min_y) / cell + 1;
+
+        let mut grid = Self {
+            segments,
+            min_x,
+            min_y,
+            cell,
+            columns,
+            rows,
+            offsets: vec![0; (columns * rows + 1) as usize],
+            items: Vec::new(),
+        };
+
+        // a segment goes to every cell it touches, the first pass only counts them
+        let mut pairs = Vec::new();
+        for (i, s) in grid.segments.iter().enumerate() {
+            grid.for_each_touched_cell(s, |c| pairs.push((c, i)));
+        }
+        for &(c, _) in pairs.iter() {
+            grid.offsets[c + 1] += 1;
+        }
+        for c in 1..grid.offsets.len() {
+            grid.offsets[c] += grid.offsets[c - 1];
+        }
+        let mut fill = grid.offsets.clone();
+        grid.items = vec![0; pairs.len()];
+        for (c, i) in pairs {
+            grid.items[fill[c]] = i;
+            fill[c] += 1;
+        }
+
+        grid
+    }
+
+    fn for_each_touched_cell<F: FnMut(usize)>(&self, s: &[IntPoint; 2], mut f: F) {
+        let (c0, r0) = self.cell_of(s[0].x.min(s[1].x) as i64, s[0].y.min(s[1].y) as i64);
+        let (c1, r1) = self.cell_of(s[0].x.max(s[1].x) as i64, s[0].y.max(s[1].y) as i64);
+        let half_diagonal = 0.5 * std::f64::consts::SQRT_2 * self.cell as f64;
+        for r in r0..=r1 {
+            for c in c0..=c1 {
+                let x = (self.min_x + c * self.cell) as f64 + 0.5 * self.cell as f64;
+                let y = (self.min_y + r * self.cell) as f64 + 0.5 * self.cell as f64;
+                if sqr_distance_to_segment(x, y, s) <= half_diagonal * half_diagonal {
+                    f((r * self.columns + c) as usize);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn cell_of(&self, x: i64, y: i64) -> (i64, i64) {
+        let c = ((x - self.min_x).div_euclid(self.cell)).clamp(0, self.columns - 1);
+        let r = ((y - self.min_y).div_euclid(self.cell)).clamp(0, self.rows - 1);
+        (c, r)
+    }
+
+    /// The nearest segment and the squared distance to it.
+    fn nearest(&self, p: IntPoint) -> Option<(usize, f64)> {
+        if self.segments.is_empty() {
+            return None;
+        }
+
+        let (pc, pr) = self.cell_of(p.x as i64, p.y as i64);
+        // the point may be outside the grid, rings start from its clamped cell
+        let (x, y) = (p.x as f64, p.y as f64);
+        let outside_x = (self.min_x as f64 - x).max(x - (self.min_x + self.columns * self.cell) as f64).max(0.0);
+        let outside_y = (self.min_y as f64 - y).max(y - (self.min_y + self.rows * self.cell) as f64).max(0.0);
+        let outside = outside_x.min(outside_y);
+
+        let mut best: Option<(usize, f64)> = None;
+        let max_ring = self.columns.max(self.rows);
+        for ring in 0..=max_ring {
+            for r in (pr - ring).max(0)..=(pr + ring).min(self.rows - 1) {
+                for c in (pc - ring).max(0)..=(pc + ring).min(self.columns - 1) {
+                    if (r - pr).abs() != ring && (c - pc).abs() != ring {
+                        continue;
+                    }
+                    let cell = (r * self.columns + c) as usize;
+                    for &i in self.items[self.offsets[cell]..self.offsets[cell + 1]].iter() {
+                        let d = sqr_distance_to_segment(x, y, &self.segments[i]);
+                        if best.is_none_or(|(_, b)| d < b) {
+                            best = Some((i, d));
+                        }
+                    }
+                }
+            }
+
+            // cells beyond the next ring are farther than `ring` cells
+            if let Some((_, b)) = best {
+                let reach = outside + (ring as f64) * self.cell as f64;
+                if b <= reach * reach {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+}
+
+#[inline]
+fn sqr_distance_to_segment(x: f64, y: f64, s: &[IntPoint; 2]) -> f64 {
+    let (ax, ay) = (s[0].x as f64, s[0].y as f64);
+    let (bx, by) = (s[1].x as f64, s[1].y as f64);
+    let (dx, dy) = (bx - ax, by - ay);
+    let len = dx * dx + dy * dy;
+    let t = if len > 0.0 {
+        (((x - ax) * dx + (y - ay) * dy) / len).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (ex, ey) = (ax + t * dx - x, ay + t * dy - y);
+    ex * ex + ey * ey
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::advanced::delaunay::IntDelaunay;
+    use crate::int::triangulatable::IntTriangulatable;
+    use crate::int::triangulator::Triangulator;
+    use crate::tessellation::quality::grid_point_on_segment;
+    use crate::tessellation::sizing::{DistanceField, GradedField, SegmentGrid, SizingField};
+    use i_overlay::i_float::int::point::IntPoint;
+    use i_overlay::i_shape::int::area::Area;
+    use i_overlay::i_shape::int::path::IntPath;
+    use rand::Rng;
+
+    fn path(slice: &[[i32; 2]]) -> IntPath {
+        slice.iter().map(|p| IntPoint::new(p[0], p[1])).collect()
+    }
+
+    fn max_edge(delaunay: &IntDelaunay, filter: impl Fn(IntPoint) -> bool) -> f64 {
+        delaunay.triangles.iter().fold(0.0, |m: f64, t| {
+            let [a, b, c] = t.vertices.map(|v| v.point);
+            if !filter(IntPoint::new((a.x + b.x + c.x) / 3, (a.y + b.y + c.y) / 3)) {
+                return m;
+            }
+            let max = a.sqr_distance(b).max(b.sqr_distance(c)).max(c.sqr_distance(a));
+            m.max((max as f64).sqrt())
+        })
+    }
+
+    #[test]
+    fn test_0() {
+        let contour = path(&[[0, 0], [10_000, 0], [10_000, 10_000], [0, 10_000]]);
+        let refined = contour.triangulate().into_delaunay().refine_with_sizing(|_: IntPoint| 1_000.0);
+        refined.validate();
+        assert!(max_edge(&refined, |_| true) <= 1_000.0);
+        assert!(refined.long_edges(&|_: IntPoint| 1_000.0).is_empty());
+        assert_eq!(refined.area(), contour.area_two());
+    }
+
+    #[test]
+    fn test_1() {
+        // dense near the point (0, 0), coarse far from it
+        let contour = path(&[[0, 0], [10_000, 0], [10_000, 10_000], [0, 10_000]]);
+        let field = DistanceField::with_points(&[IntPoint::new(0, 0)], 100.0, 0.5, 5_000.0);
+        let refined = contour.triangulate().into_delaunay().refine_with_sizing(field);
+        refined.validate();
+
+        let near = max_edge(&refined, |p| p.x < 1_000 && p.y < 1_000);
+        let far = max_edge(&refined, |p| p.x > 5_000 && p.y > 5_000);
+        assert!(near <= 1_000.0);
+        assert!(far > 2.0 * near);
+        assert_eq!(refined.area(), contour.area_two());
+    }
+
+    #[test]
+    fn test_2() {
+        let contour = path(&[[0, 0], [10_000, 0], [10_000, 10_000], [0, 10_000]]);
+        let delaunay = contour.triangulate().into_delaunay();
+
+        let boundary = delaunay.boundary_distance_field(100.0, 1.0, 1_000_000.0);
+        assert_eq!(boundary.edge_length(IntPoint::new(0, 5_000)), 100.0);
+        assert_eq!(boundary.edge_length(IntPoint::new(5_000, 5_000)), 5_100.0);
+
+        // a field jumping from 100 to 10 000 is limited to a slope of 0.25
+        let step = |p: IntPoint| if p.x < 5_000 { 100.0 } else { 10_000.0 };
+        let graded = GradedField::new(&delaunay, step, 0.25);
+        assert_eq!(graded.edge_length(IntPoint::new(10_000, 0)), 100.0 + 0.25 * 10_000.0);
+    }
+
+    #[test]
+    fn test_random_0() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(1..40);
+            let segments: Vec<[IntPoint; 2]> = (0..n)
+                .map(|_| {
+                    let a = IntPoint::new(rng.random_range(-1000..=1000), rng.random_range(-1000..=1000));
+                    let b = IntPoint::new(a.x + rng.random_range(-300..=300), a.y + rng.random_range(-300..=300));
+                    [a, b]
+                })
+                .collect();
+            let grid = SegmentGrid::new(segments.clone());
+
+            for _ in 0..50 {
+                let p = IntPoint::new(rng.random_range(-2000..=2000), rng.random_range(-2000..=2000));
+                let (_, d) = grid.nearest(p).unwrap();
+                let min = segments
+                    .iter()
+                    .map(|s| super::sqr_distance_to_segment(p.x as f64, p.y as f64, s))
+                    .fold(f64::MAX, f64::min);
+                assert_eq!(d, min);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_1() {
+        // every long edge left has no int point strictly inside
+        let triangulator = Triangulator::default();
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let n = rng.random_range(3..12);
+            let contour: IntPath = (0..n)
+                .map(|_| IntPoint::new(rng.random_range(-1_000..=1_000), rng.random_range(-1_000..=1_000)))
+                .collect();
+
+            let delaunay = triangulator.triangulate_contour(&contour).into_delaunay();
+            let length = rng.random_range(4.0..200.0);
+            let refined = delaunay.refine_with_sizing(|_: IntPoint| length);
+            refined.validate();
+            for [a, b] in refined.long_edges(&|_: IntPoint| length) {
+                assert!(grid_point_on_segment(refined.points[a], refined.points[b], 0.5).is_none());
+            }
+        }
+    }
+}
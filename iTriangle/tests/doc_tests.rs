@@ -4,6 +4,7 @@ mod tests {
     use i_triangle::float::triangulation::Triangulation;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_0() {
         let shape = vec![
             vec![
@@ -1,7 +1,37 @@
 #[cfg(test)]
 mod tests {
     use i_overlay::i_float::float::point::FloatPoint;
+    use i_triangle::advanced::edges::EdgeKind;
+    use i_triangle::float::delaunay::Delaunay;
+    use i_triangle::tessellation::adaptive::RefinementStrategy;
+    use i_triangle::tessellation::circumcenter::{RefinementOptions, SelectBiggerAngle};
+    use i_triangle::advanced::convex::{ConvexMode, ConvexOptions};
+    use i_triangle::tessellation::decimation::DecimationOptions;
+    use i_triangle::float::builder::TriangulationBuilder;
+    use i_triangle::float::trapezoid::Trapezoidable;
     use i_triangle::float::triangulatable::Triangulatable;
+    use i_triangle::float::unchecked::UncheckedTriangulatable;
+    use i_triangle::int::context::TriangulatorContext;
+    use i_triangle::int::error::TriangulationError;
+    use i_triangle::int::provenance::VertexSource;
+
+    fn square(size: f64) -> Vec<FloatPoint<f64>> {
+        [
+            FloatPoint::new(0.0, 0.0),
+            FloatPoint::new(size, 0.0),
+            FloatPoint::new(size, size),
+            FloatPoint::new(0.0, size),
+        ].to_vec()
+    }
+
+    fn distance(p: FloatPoint<f64>, q: FloatPoint<f64>) -> f64 {
+        ((p.x - q.x).powi(2) + (p.y - q.y).powi(2)).sqrt()
+    }
+
+    // the longest side of a triangle
+    fn max_edge(a: FloatPoint<f64>, b: FloatPoint<f64>, c: FloatPoint<f64>) -> f64 {
+        distance(a, b).max(distance(b, c)).max(distance(c, a))
+    }
 
     #[test]
     fn test_0() {
@@ -21,4 +51,511 @@ mod tests {
         assert_eq!(triangulation.points.len(), 6);
         assert_eq!(triangulation.indices.len(), 12);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_constraints() {
+        let contour = square(4.0);
+
+        let segments = [[FloatPoint::new(1.0, 1.0), FloatPoint::new(3.0, 3.0)]];
+
+        let triangulation = contour
+            .triangulate_with_constraints(&segments)
+            .to_triangulation::<u16>();
+
+        assert_eq!(triangulation.points.len(), 6);
+        assert_eq!(triangulation.indices.len(), 18);
+
+        let a = triangulation.points.iter().position(|p| p.x == 1.0 && p.y == 1.0).unwrap() as u16;
+        let b = triangulation.points.iter().position(|p| p.x == 3.0 && p.y == 3.0).unwrap() as u16;
+
+        let has_edge = triangulation.indices.chunks(3).any(|t| t.contains(&a) && t.contains(&b));
+        assert!(has_edge);
+    }
+
+    #[test]
+    fn test_index_overflow() {
+        let contour: Vec<FloatPoint<f64>> = (0..200)
+            .map(|i| {
+                let a = i as f64 * std::f64::consts::TAU / 200.0;
+                FloatPoint::new(a.cos(), a.sin())
+            })
+            .collect();
+
+        let triangulation = contour.triangulate().try_to_triangulation::<u8>().unwrap();
+
+        let mut builder = TriangulationBuilder::default();
+        assert!(builder.try_append(triangulation.clone()).is_ok());
+        assert_eq!(
+            builder.try_append(triangulation).err(),
+            Some(TriangulationError::IndexOverflow { index_type: "u8", points_count: 400 })
+        );
+        assert_eq!(builder.build().points.len(), 200);
+    }
+
+    #[test]
+    fn test_triangulate_into_context() {
+        let square = [
+            FloatPoint::<f32>::new(0.0, 0.0),
+            FloatPoint::<f32>::new(1.0, 0.0),
+            FloatPoint::<f32>::new(1.0, 1.0),
+            FloatPoint::<f32>::new(0.0, 1.0),
+        ].to_vec();
+
+        let triangle = [
+            FloatPoint::<f32>::new(2.0, 0.0),
+            FloatPoint::<f32>::new(3.0, 0.0),
+            FloatPoint::<f32>::new(2.0, 1.0),
+        ].to_vec();
+
+        let mut context = TriangulatorContext::default();
+        let mut points = Vec::new();
+        let mut indices: Vec<u16> = Vec::new();
+
+        square.unchecked_triangulate_into(&mut context, &mut points, &mut indices).unwrap();
+        triangle.triangulate_into(&mut context, &mut points, &mut indices).unwrap();
+
+        assert_eq!(points.len(), 7);
+        assert_eq!(indices.len(), 9);
+        assert!(indices[6..].iter().all(|&i| i >= 4));
+        assert!(points[4..].iter().all(|p| p.x >= 2.0));
+    }
+
+    #[test]
+    fn test_shape_ids() {
+        let square = square(1.0);
+
+        let triangle = [
+            FloatPoint::<f64>::new(0.5, 0.5),
+            FloatPoint::<f64>::new(2.0, 0.5),
+            FloatPoint::<f64>::new(0.5, 2.0),
+        ].to_vec();
+
+        let shapes = [vec![square], vec![triangle]];
+        let triangulation = shapes
+            .triangulate_with_shape_ids()
+            .into_delaunay()
+            .to_triangulation::<u16>();
+
+        assert_eq!(triangulation.shape_ids(), Some([0, 0, 1].as_slice()));
+        assert_eq!(triangulation.indices.len(), 9);
+        assert!(shapes.triangulate().to_triangulation::<u16>().shape_ids().is_none());
+    }
+
+    #[test]
+    fn test_vertex_sources() {
+        let square = square(1.0);
+        let steiner = [FloatPoint::<f64>::new(0.25, 0.5)];
+        let shapes = [vec![square]];
+
+        let raw = shapes
+            .triangulate_with_steiner_points(&steiner)
+            .with_vertex_sources(&shapes, &steiner);
+        let points = raw.points();
+        let sources = raw.vertex_sources().unwrap();
+
+        let i = points.iter().position(|p| p.x == 0.25 && p.y == 0.5).unwrap();
+        assert_eq!(sources[i], VertexSource::Steiner { index: 0 });
+        let j = points.iter().position(|p| p.x == 1.0 && p.y == 1.0).unwrap();
+        assert_eq!(sources[j], VertexSource::Original { shape: 0, contour: 0, index: 2 });
+    }
+
+    #[test]
+    fn test_input_indices() {
+        let contour = [
+            FloatPoint::<f32>::new(0.0, 0.0),
+            FloatPoint::<f32>::new(2.0, 0.0),
+            FloatPoint::<f32>::new(2.0, 1.0),
+            FloatPoint::<f32>::new(1.0, 2.0),
+            FloatPoint::<f32>::new(0.0, 1.0),
+        ];
+
+        let indices = contour.unchecked_triangulate_indices::<u16>();
+        assert_eq!(indices.len(), 9);
+
+        let area = indices.chunks(3).fold(0.0, |s, t| {
+            let a = contour[t[0] as usize];
+            let b = contour[t[1] as usize];
+            let c = contour[t[2] as usize];
+            s + ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs()
+        });
+        assert_eq!(area, 2.0 * 3.0);
+    }
+
+    #[test]
+    fn test_topology() {
+        let square = square(1.0);
+
+        let delaunay = square.triangulate().into_delaunay();
+        let topology = delaunay.topology();
+
+        assert_eq!(topology.triangles_count(), 2);
+        assert_eq!(topology.boundary_edges().count(), 4);
+        let shared = (0..3).find(|&e| topology.neighbor(0, e).is_some()).unwrap();
+        assert_eq!(topology.neighbor(0, shared), Some(1));
+        assert!(!topology.triangle(0).contains(&topology.opposite_vertex(0, shared).unwrap()));
+        assert_eq!(delaunay.points().len(), topology.points().len());
+    }
+
+    #[test]
+    fn test_edge_kinds() {
+        let shape = [
+            [
+                FloatPoint::<f64>::new(0.0, 0.0),
+                FloatPoint::<f64>::new(3.0, 0.0),
+                FloatPoint::<f64>::new(3.0, 3.0),
+                FloatPoint::<f64>::new(0.0, 3.0),
+            ].to_vec(),
+            [
+                FloatPoint::<f64>::new(1.0, 1.0),
+                FloatPoint::<f64>::new(1.0, 2.0),
+                FloatPoint::<f64>::new(2.0, 2.0),
+                FloatPoint::<f64>::new(2.0, 1.0),
+            ].to_vec(),
+        ].to_vec();
+
+        let edges = shape.triangulate().into_delaunay().edges();
+
+        let outer = edges.iter().filter(|e| e.kind == EdgeKind::OuterBoundary).count();
+        let hole = edges.iter().filter(|e| e.kind == EdgeKind::HoleBoundary).count();
+        assert_eq!(outer, 4);
+        assert_eq!(hole, 4);
+        assert_eq!(edges.len(), 16);
+    }
+
+    #[test]
+    fn test_from_points() {
+        let points = [
+            FloatPoint::<f64>::new(0.0, 0.0),
+            FloatPoint::<f64>::new(1.0, 0.0),
+            FloatPoint::<f64>::new(1.0, 1.0),
+            FloatPoint::<f64>::new(0.0, 1.0),
+            FloatPoint::<f64>::new(0.5, 0.5),
+            FloatPoint::<f64>::new(0.5, 0.5),
+        ];
+
+        let delaunay = Delaunay::from_points(&points);
+        assert_eq!(delaunay.points().len(), 5);
+        assert_eq!(delaunay.triangle_indices::<u16>().len(), 4 * 3);
+
+        let collinear = [FloatPoint::<f64>::new(0.0, 0.0), FloatPoint::<f64>::new(1.0, 1.0)];
+        assert!(Delaunay::from_points(&collinear).triangle_indices::<u16>().is_empty());
+    }
+
+    #[test]
+    fn test_voronoi() {
+        let square = square(1.0);
+
+        let delaunay = square
+            .triangulate_with_steiner_points(&[FloatPoint::<f64>::new(0.5, 0.5)])
+            .into_delaunay();
+        let points = delaunay.points();
+        let cells = delaunay.to_voronoi_cells();
+        assert_eq!(cells.len(), 5);
+
+        let center = points.iter().position(|p| p.x == 0.5 && p.y == 0.5).unwrap();
+        let cell = cells.iter().find(|c| c.site == center).unwrap();
+        assert_eq!(cell.neighbors.len(), 4);
+        for p in cell.shapes.iter().flatten().flatten() {
+            assert_eq!((p.x - 0.5).abs() + (p.y - 0.5).abs(), 0.5);
+        }
+    }
+
+    #[test]
+    fn test_min_angle() {
+        let rect = [
+            FloatPoint::<f64>::new(0.0, 0.0),
+            FloatPoint::<f64>::new(20.0, 0.0),
+            FloatPoint::<f64>::new(20.0, 1.0),
+            FloatPoint::<f64>::new(0.0, 1.0),
+        ].to_vec();
+
+        let delaunay = rect.triangulate().into_delaunay().refine_to_min_angle(25.0, 0.0);
+        let triangulation = delaunay.to_triangulation::<u16>();
+        let points = &triangulation.points;
+
+        let mut area = 0.0;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            let sides = [distance(b, c), distance(c, a), distance(a, b)];
+            let s = 0.5 * (sides[0] + sides[1] + sides[2]);
+            let t_area = (s * (s - sides[0]) * (s - sides[1]) * (s - sides[2])).sqrt();
+            area += t_area;
+
+            // the smallest angle is opposite the shortest side
+            let min_side = sides[0].min(sides[1]).min(sides[2]);
+            let sin = 2.0 * t_area * min_side / (sides[0] * sides[1] * sides[2]);
+            assert!(sin.asin().to_degrees() >= 24.9);
+        }
+        assert!((area - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_conforming_delaunay() {
+        // the corner of the lower square is inside the circumcircle of the flat triangle
+        let shapes = [
+            vec![vec![
+                FloatPoint::<f64>::new(0.0, 0.0),
+                FloatPoint::<f64>::new(4.0, 0.0),
+                FloatPoint::<f64>::new(2.0, 0.5),
+            ]],
+            vec![vec![
+                FloatPoint::<f64>::new(1.0, -2.0),
+                FloatPoint::<f64>::new(3.0, -2.0),
+                FloatPoint::<f64>::new(3.0, -0.25),
+                FloatPoint::<f64>::new(1.0, -0.25),
+            ]],
+        ];
+
+        let delaunay = shapes.triangulate().into_delaunay();
+        let conforming = shapes.triangulate().into_conforming_delaunay();
+        assert!(conforming.points().len() > delaunay.points().len());
+
+        let triangulation = conforming.to_triangulation::<u16>();
+        let points = &triangulation.points;
+        let mut area = 0.0;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            area += 0.5 * ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs();
+        }
+        assert!((area - 4.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sizing_field() {
+        let square = square(10.0);
+
+        // dense along the left side
+        let delaunay = square.triangulate().into_delaunay().refine_with_sizing(|p: FloatPoint<f64>| 0.5 + 0.5 * p.x);
+        let triangulation = delaunay.to_triangulation::<u16>();
+        let points = &triangulation.points;
+
+        let mut left = 0;
+        let mut right = 0;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            let x = (a.x + b.x + c.x) / 3.0;
+            assert!(max_edge(a, b, c) <= 0.5 + 0.5 * x + 1e-6);
+            if x < 5.0 {
+                left += 1;
+            } else {
+                right += 1;
+            }
+        }
+        assert!(left > 2 * right);
+
+        let field = delaunay.point_distance_field(&[FloatPoint::new(10.0, 10.0)], 0.5, 0.5, 5.0);
+        let graded = delaunay.graded_field(field, 0.5);
+        let refined = delaunay.refine_with_sizing_field(graded);
+        assert!(refined.points().len() > points.len());
+    }
+
+    #[test]
+    fn test_refinement_options() {
+        let square = square(10.0);
+
+        let options = RefinementOptions {
+            max_iterations: 16,
+            min_area: f64::MAX,
+            max_edge_length: Some(2.0),
+            ..Default::default()
+        };
+
+        let refined = square.triangulate().into_delaunay().refine_with_options(&options, SelectBiggerAngle);
+        let triangulation = refined.to_triangulation::<u16>();
+        let points = &triangulation.points;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            assert!(max_edge(a, b, c) <= 2.0 + 1e-6);
+        }
+
+        // refine only the top half, selected by float points
+        let top = |_: usize, p: [FloatPoint<f64>; 3]| {
+            if p.iter().all(|p| p.y >= 5.0) {
+                // the longest edge
+                let sqr = |a: FloatPoint<f64>, b: FloatPoint<f64>| (a.x - b.x).powi(2) + (a.y - b.y).powi(2);
+                let lengths = [sqr(p[1], p[2]), sqr(p[2], p[0]), sqr(p[0], p[1])];
+                (0..3).max_by(|&i, &j| lengths[i].total_cmp(&lengths[j]))
+            } else {
+                None
+            }
+        };
+        let options = RefinementOptions {
+            max_edge_length: Some(1.0),
+            ..options
+        };
+        let count = refined.points().len();
+        let bottom = refined.points().iter().filter(|p| p.y < 4.0).count();
+        let refined = refined.refine_with_options_by(&options, top);
+        assert!(refined.points().len() > count);
+        assert_eq!(refined.points().iter().filter(|p| p.y < 4.0).count(), bottom);
+    }
+
+    #[test]
+    fn test_worst_first() {
+        let square = square(10.0);
+
+        let options = RefinementOptions {
+            min_area: 1.0,
+            ..Default::default()
+        };
+
+        let refined = square.triangulate().into_delaunay().refine_worst_first(&options, SelectBiggerAngle);
+        let triangulation = refined.to_triangulation::<u16>();
+        let points = &triangulation.points;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            let area = 0.5 * ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs();
+            assert!(area <= 1.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_max_edge_length() {
+        let shape = [
+            square(10.0),
+            [
+                FloatPoint::<f64>::new(3.0, 3.0),
+                FloatPoint::<f64>::new(3.0, 7.0),
+                FloatPoint::<f64>::new(7.0, 7.0),
+                FloatPoint::<f64>::new(7.0, 3.0),
+            ].to_vec(),
+        ].to_vec();
+
+        let refined = shape.triangulate().into_delaunay().refine_by_max_edge_length(1.5);
+        let triangulation = refined.to_triangulation::<u16>();
+        let points = &triangulation.points;
+        for t in triangulation.indices.chunks(3) {
+            let [a, b, c] = [points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]];
+            assert!(max_edge(a, b, c) <= 1.5 + 1e-6);
+        }
+
+        // the outer boundary is sliced
+        assert!(points.iter().filter(|p| p.y == 0.0).count() >= 8);
+
+        // a length that is not positive is ignored
+        let count = shape.triangulate().into_delaunay().points().len();
+        for length in [0.0, -1.0, f64::NAN] {
+            let refined = shape.triangulate().into_delaunay().refine_by_max_edge_length(length);
+            assert_eq!(refined.points().len(), count);
+        }
+    }
+
+    #[test]
+    fn test_refine_triangles() {
+        let square = square(10.0);
+
+        let mut delaunay = square.triangulate().into_delaunay();
+        let children = delaunay.refine_triangles(&[0], RefinementStrategy::LongestEdge);
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|list| list.len() == 2));
+        assert_eq!(delaunay.to_triangulation::<u16>().indices.len(), 12);
+    }
+
+    #[test]
+    fn test_decimation() {
+        let square = square(10.0);
+
+        let refined = square.triangulate().into_delaunay().refine_with_circumcenters(0.5);
+        let count = refined.to_triangulation::<u16>().indices.len() / 3;
+
+        let options = DecimationOptions {
+            target_triangle_count: Some(count / 2),
+            ..Default::default()
+        };
+        let decimated = refined.decimate(&options);
+        let triangulation = decimated.to_triangulation::<u16>();
+        assert!(triangulation.indices.len() / 3 <= count / 2);
+
+        // the corners are kept
+        for p in square.iter() {
+            assert!(triangulation.points.iter().any(|q| q.x == p.x && q.y == p.y));
+        }
+    }
+
+    #[test]
+    fn test_convex_options() {
+        // an L shape
+        let shape = [
+            FloatPoint::<f64>::new(0.0, 0.0),
+            FloatPoint::<f64>::new(2.0, 0.0),
+            FloatPoint::<f64>::new(2.0, 1.0),
+            FloatPoint::<f64>::new(1.0, 1.0),
+            FloatPoint::<f64>::new(1.0, 2.0),
+            FloatPoint::<f64>::new(0.0, 2.0),
+        ].to_vec();
+
+        let delaunay = shape.triangulate().into_delaunay();
+        for mode in [ConvexMode::Greedy, ConvexMode::HertelMehlhorn, ConvexMode::Exact] {
+            let options = ConvexOptions {
+                max_vertices: Some(4),
+                mode,
+            };
+            let polygons = delaunay.to_convex_polygons_with_options(&options);
+            assert!(polygons.iter().all(|p| p.len() <= 4));
+            if mode == ConvexMode::Exact {
+                assert_eq!(polygons.len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_from_raw() {
+        // an L shape straight from the raw triangulation
+        let shape = [
+            FloatPoint::<f64>::new(0.0, 0.0),
+            FloatPoint::<f64>::new(2.0, 0.0),
+            FloatPoint::<f64>::new(2.0, 1.0),
+            FloatPoint::<f64>::new(1.0, 1.0),
+            FloatPoint::<f64>::new(1.0, 2.0),
+            FloatPoint::<f64>::new(0.0, 2.0),
+        ].to_vec();
+
+        let raw = shape.triangulate();
+        assert!(raw.to_convex_polygons().len() >= 2);
+
+        let options = ConvexOptions {
+            max_vertices: Some(4),
+            mode: ConvexMode::Exact,
+        };
+        let decomposition = raw.to_convex_decomposition(&options);
+        assert_eq!(decomposition.polygons.len(), 2);
+        assert_eq!(decomposition.adjacency, vec![vec![1], vec![0]]);
+    }
+
+    #[test]
+    fn test_quads() {
+        let square = square(10.0);
+
+        let delaunay = square.triangulate().into_delaunay();
+        let mesh = delaunay.to_quad_dominant();
+        assert_eq!(mesh.quads.len(), 1);
+        assert!(mesh.triangles.is_empty());
+
+        let mesh = delaunay.to_all_quad();
+        assert_eq!(mesh.quads.len(), 4);
+        assert_eq!(mesh.points.len(), 9);
+        assert!(mesh.points.iter().any(|p| (p.x - 5.0).abs() < 1e-6 && (p.y - 5.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_trapezoids() {
+        let square = square(10.0);
+
+        let trapezoids = square.trapezoidate();
+        assert_eq!(trapezoids.len(), 1);
+        assert_eq!(trapezoids[0].len(), 4);
+
+        let triangle = [
+            FloatPoint::<f64>::new(0.0, 0.0),
+            FloatPoint::<f64>::new(10.0, 0.0),
+            FloatPoint::<f64>::new(0.0, 10.0),
+        ].to_vec();
+
+        let trapezoids = triangle.trapezoidate();
+        assert_eq!(trapezoids.len(), 1);
+        assert_eq!(trapezoids[0].len(), 3);
+
+        let shapes = [[square].to_vec()];
+        assert_eq!(shapes.trapezoidate().len(), 1);
+    }
+}